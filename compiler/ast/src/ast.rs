@@ -0,0 +1,538 @@
+//! The abstract syntax tree produced by the parser
+//!
+//! Every node knows the source it was parsed from through its `src_data`
+//! and nodes which are referred to by later passes have a [`NodeId`].
+
+use source_idx::{sym, GroupSrcIdx, SrcData, Symbol};
+
+use crate::{
+    lit::LitValue,
+    token::{CommentKind, Delimiter},
+    tokenstream::TokenStream,
+};
+
+/// owning pointer to a node, used to break up recursive types
+pub type P<T> = Box<T>;
+
+/// Identifies a node, the ids are unique within a parse session
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct NodeId(u32);
+
+/// id of nodes which are created before the real id is known
+pub const DUMMY_NODE_ID: NodeId = NodeId(u32::MAX);
+
+impl NodeId {
+    pub const fn from_u32(id: u32) -> NodeId {
+        NodeId(id)
+    }
+
+    pub fn as_u32(self) -> u32 {
+        self.0
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Ident {
+    pub name: Symbol,
+    pub src_data: SrcData,
+}
+
+impl Ident {
+    pub fn new(name: Symbol, src_data: SrcData) -> Ident {
+        Ident { name, src_data }
+    }
+}
+
+/// The root of a parsed file
+#[derive(Clone, Debug)]
+pub struct Crate {
+    pub attrs: Vec<Attribute>,
+    pub items: Vec<P<Item>>,
+    pub id: NodeId,
+    pub src_data: SrcData,
+}
+
+/// A path like `std::mem::replace` or `Vec<u8>`
+#[derive(Clone, Debug)]
+pub struct Path {
+    pub segments: Vec<PathSegment>,
+    pub src_data: SrcData,
+}
+
+#[derive(Clone, Debug)]
+pub struct PathSegment {
+    pub ident: Ident,
+    pub id: NodeId,
+    /// `<u8>` in `Vec<u8>` or `Vec::<u8>`
+    pub args: Option<P<GenericArgs>>,
+}
+
+/// `<A, B>`
+#[derive(Clone, Debug)]
+pub struct GenericArgs {
+    pub args: Vec<P<Ty>>,
+    pub src_data: SrcData,
+}
+
+impl Path {
+    /// true if the path is the single identifier `name`
+    pub fn is_ident(&self, name: Symbol) -> bool {
+        matches!(&self.segments[..], [segment] if segment.ident.name == name)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AttrStyle {
+    /// `#[attr]`, applies to the following item
+    Outer,
+    /// `#![attr]`, applies to the surrounding item
+    Inner,
+}
+
+#[derive(Clone, Debug)]
+pub struct Attribute {
+    pub kind: AttrKind,
+    pub style: AttrStyle,
+    pub src_data: SrcData,
+}
+
+#[derive(Clone, Debug)]
+pub enum AttrKind {
+    /// `#[path args]`
+    Normal(P<AttrItem>),
+    /// `/// text` or `/** text */`, the same as `#[doc = "text"]`
+    DocComment(CommentKind, Symbol),
+}
+
+#[derive(Clone, Debug)]
+pub struct AttrItem {
+    pub path: Path,
+    pub args: AttrArgs,
+}
+
+#[derive(Clone, Debug)]
+pub enum AttrArgs {
+    /// `#[entry]`
+    Empty,
+    /// `#[inline(always)]`
+    Delimited(DelimArgs),
+    /// `#[doc = "text"]`, the location of the `=` and the value
+    Eq(SrcData, P<Expr>),
+}
+
+impl Attribute {
+    /// true if the attribute is `#[name ...]`
+    pub fn has_name(&self, name: Symbol) -> bool {
+        match &self.kind {
+            AttrKind::Normal(item) => item.path.is_ident(name),
+            AttrKind::DocComment(..) => name == sym::doc,
+        }
+    }
+}
+
+/// The tokens between the delimiters of a macro call or an attribute
+#[derive(Clone, Debug)]
+pub struct DelimArgs {
+    pub dspan: GroupSrcIdx,
+    pub delim: Delimiter,
+    pub tokens: TokenStream,
+}
+
+/// A macro call like `def!()`
+#[derive(Clone, Debug)]
+pub struct MacCall {
+    pub path: Path,
+    pub args: DelimArgs,
+}
+
+#[derive(Clone, Debug)]
+pub struct Item {
+    pub attrs: Vec<Attribute>,
+    pub id: NodeId,
+    pub ident: Ident,
+    pub kind: ItemKind,
+    pub src_data: SrcData,
+}
+
+#[derive(Clone, Debug)]
+pub enum ItemKind {
+    /// `fn name(params) ret { body }`
+    Fn(P<Fn>),
+    /// `mod name { items }` or `mod name;`
+    Mod(ModKind),
+    /// placeholder for an item with a syntax error, which was already reported
+    Err,
+}
+
+#[derive(Clone, Debug)]
+pub enum ModKind {
+    /// the items are known, either because the module is inline or its file was loaded
+    Loaded(Vec<P<Item>>),
+    /// `mod name;` whose file was not loaded yet
+    Unloaded,
+}
+
+#[derive(Clone, Debug)]
+pub struct Fn {
+    pub sig: FnSig,
+    /// `None` for a function declared with `;`
+    pub body: Option<P<Block>>,
+}
+
+/// Everything of a function except its body
+#[derive(Clone, Debug)]
+pub struct FnSig {
+    pub inputs: Vec<Param>,
+    pub output: FnRetTy,
+    pub src_data: SrcData,
+}
+
+/// A parameter `ident: ty = default`
+#[derive(Clone, Debug)]
+pub struct Param {
+    pub id: NodeId,
+    pub ident: Ident,
+    pub ty: P<Ty>,
+    /// the value used if the parameter is not passed explicitly
+    pub default: Option<P<Expr>>,
+    pub src_data: SrcData,
+}
+
+/// The return type of a function, which is written without an arrow
+#[derive(Clone, Debug)]
+pub enum FnRetTy {
+    /// no return type, the function returns `()`,
+    /// the location is the empty source where the type would be
+    Default(SrcData),
+    /// `T`
+    Ty(P<Ty>),
+    /// `T!` or `T?`, sugar for `Option<T>`
+    Option { ty: P<Ty>, bang: SrcData },
+    /// `T!E`, sugar for `Result<T, E>` or `!E`, sugar for `Result<(), E>`
+    Result { ok: Option<P<Ty>>, bang: SrcData, err: P<Ty> },
+    /// `!`, the function never returns
+    Never(SrcData),
+}
+
+impl FnRetTy {
+    pub fn src_data(&self) -> SrcData {
+        match self {
+            FnRetTy::Default(src_data) | FnRetTy::Never(src_data) => *src_data,
+            FnRetTy::Ty(ty) => ty.src_data,
+            FnRetTy::Option { ty, bang } => ty.src_data.combine(*bang),
+            FnRetTy::Result { ok, bang, err } => match ok {
+                Some(ok) => ok.src_data.combine(err.src_data),
+                None => bang.combine(err.src_data),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Mutability {
+    Not,
+    Mut,
+}
+
+#[derive(Clone, Debug)]
+pub struct Ty {
+    pub id: NodeId,
+    pub kind: TyKind,
+    pub src_data: SrcData,
+}
+
+#[derive(Clone, Debug)]
+pub enum TyKind {
+    /// `usize`, `std::string::String`, `Vec<u8>`
+    Path(Path),
+    /// `&T` or `&mut T`
+    Ref(Mutability, P<Ty>),
+    /// `[T]`
+    Slice(P<Ty>),
+    /// `[T; len]`
+    Array(P<Ty>, P<Expr>),
+    /// `(A, B)`, the unit type is the empty tuple
+    Tup(Vec<P<Ty>>),
+    /// `!`
+    Never,
+    /// `_`
+    Infer,
+}
+
+/// `{ stmts }`, the value of the block is the last statement if it is a [`StmtKind::Expr`]
+#[derive(Clone, Debug)]
+pub struct Block {
+    pub stmts: Vec<Stmt>,
+    pub id: NodeId,
+    pub src_data: SrcData,
+}
+
+#[derive(Clone, Debug)]
+pub struct Stmt {
+    pub id: NodeId,
+    pub kind: StmtKind,
+    pub src_data: SrcData,
+}
+
+#[derive(Clone, Debug)]
+pub enum StmtKind {
+    /// `let pat: ty = init;`
+    Let(P<Local>),
+    Item(P<Item>),
+    /// an expression without a trailing `;`
+    Expr(P<Expr>),
+    /// an expression with a trailing `;`
+    Semi(P<Expr>),
+    /// a lone `;`
+    Empty,
+}
+
+/// A `let` statement
+#[derive(Clone, Debug)]
+pub struct Local {
+    pub id: NodeId,
+    pub pat: P<Pat>,
+    pub ty: Option<P<Ty>>,
+    pub init: Option<P<Expr>>,
+    pub src_data: SrcData,
+}
+
+#[derive(Clone, Debug)]
+pub struct Pat {
+    pub id: NodeId,
+    pub kind: PatKind,
+    pub src_data: SrcData,
+}
+
+#[derive(Clone, Debug)]
+pub enum PatKind {
+    /// `_`
+    Wild,
+    /// `name` or `mut name`, binds the matched value
+    Ident(Mutability, Ident),
+    /// `(a, b)`
+    Tuple(Vec<P<Pat>>),
+    /// `&pat` or `&mut pat`
+    Ref(P<Pat>, Mutability),
+    /// a literal like `1` or `"a"`
+    Lit(P<Expr>),
+    /// `a..b`, `a..=b` or `a..`, the bounds are literals or paths
+    Range(Option<P<Expr>>, Option<P<Expr>>, RangeLimits),
+    /// a constant like `a::B`
+    Path(Path),
+    /// `..` in a tuple pattern
+    Rest,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RangeLimits {
+    /// `..`
+    HalfOpen,
+    /// `..=`
+    Closed,
+}
+
+#[derive(Clone, Debug)]
+pub struct Expr {
+    pub id: NodeId,
+    pub kind: ExprKind,
+    pub src_data: SrcData,
+}
+
+#[derive(Clone, Debug)]
+pub enum ExprKind {
+    Lit(LitValue),
+    /// `a::b`
+    Path(Path),
+    /// `(expr)`
+    Paren(P<Expr>),
+    /// `(a, b)`, the unit value is the empty tuple
+    Tup(Vec<P<Expr>>),
+    /// `[a, b]`
+    Array(Vec<P<Expr>>),
+    Block(P<Block>),
+    /// `if cond { then } else els`, `els` is a block or another `if`
+    If(P<Expr>, P<Block>, Option<P<Expr>>),
+    /// `while cond { body }`
+    While(P<Expr>, P<Block>),
+    /// `loop { body }`
+    Loop(P<Block>),
+    /// `|params| body`
+    Closure(P<Closure>),
+    /// `func(args)`
+    Call(P<Expr>, Vec<CallArg>),
+    /// `receiver.method(args)`
+    MethodCall(P<MethodCall>),
+    /// `expr.field`
+    Field(P<Expr>, Ident),
+    /// `lhs op rhs`
+    Binary(BinOp, P<Expr>, P<Expr>),
+    /// `op expr`
+    Unary(UnOp, P<Expr>),
+    /// `&expr` or `&mut expr`
+    AddrOf(Mutability, P<Expr>),
+    /// `lhs = rhs`, the location is the one of the `=`
+    Assign(P<Expr>, P<Expr>, SrcData),
+    /// `lhs op= rhs`
+    AssignOp(BinOp, P<Expr>, P<Expr>),
+    /// `start..end`, `start..=end` where both bounds are optional
+    Range(Option<P<Expr>>, Option<P<Expr>>, RangeLimits),
+    /// `expr?`
+    Try(P<Expr>),
+    /// `expr[index]`
+    Index(P<Expr>, P<Expr>),
+    /// `return` with an optional value
+    Ret(Option<P<Expr>>),
+    /// `break` with an optional value
+    Break(Option<P<Expr>>),
+    Continue,
+    MacCall(P<MacCall>),
+    /// placeholder for an expression with a syntax error, which was already reported
+    Err,
+}
+
+impl Expr {
+    /// true for expressions which do not need a `;` to end a statement
+    pub fn is_block_like(&self) -> bool {
+        matches!(self.kind, ExprKind::Block(_) | ExprKind::If(..) | ExprKind::While(..) | ExprKind::Loop(_))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Closure {
+    pub params: Vec<ClosureParam>,
+    pub body: P<Expr>,
+}
+
+/// `pat` or `pat: ty`
+#[derive(Clone, Debug)]
+pub struct ClosureParam {
+    pub id: NodeId,
+    pub pat: P<Pat>,
+    pub ty: Option<P<Ty>>,
+    pub src_data: SrcData,
+}
+
+#[derive(Clone, Debug)]
+pub struct MethodCall {
+    pub ident: Ident,
+    pub receiver: P<Expr>,
+    pub args: Vec<CallArg>,
+}
+
+/// An argument of a function or method call
+#[derive(Clone, Debug)]
+pub struct CallArg {
+    pub kind: CallArgKind,
+    pub src_data: SrcData,
+}
+
+#[derive(Clone, Debug)]
+pub enum CallArgKind {
+    /// `expr`
+    Positional(P<Expr>),
+    /// `name: expr`, passes the parameter `name`
+    Named(Ident, P<Expr>),
+    /// `..expr`, passes every element of a tuple as its own argument
+    Spread(P<Expr>),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BinOp {
+    pub kind: BinOpKind,
+    pub src_data: SrcData,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum BinOpKind {
+    /// `+`
+    Add,
+    /// `-`
+    Sub,
+    /// `*`
+    Mul,
+    /// `/`
+    Div,
+    /// `%`
+    Rem,
+    /// `&&`
+    And,
+    /// `||`
+    Or,
+    /// `^`
+    BitXor,
+    /// `&`
+    BitAnd,
+    /// `|`
+    BitOr,
+    /// `<<`
+    Shl,
+    /// `>>`
+    Shr,
+    /// `==`
+    Eq,
+    /// `<`
+    Lt,
+    /// `<=`
+    Le,
+    /// `!=`
+    Ne,
+    /// `>=`
+    Ge,
+    /// `>`
+    Gt,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum UnOp {
+    /// `*`
+    Deref,
+    /// `!`
+    Not,
+    /// `-`
+    Neg,
+    /// `~`
+    BitNot,
+}
+
+impl UnOp {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            UnOp::Deref => "*",
+            UnOp::Not => "!",
+            UnOp::Neg => "-",
+            UnOp::BitNot => "~",
+        }
+    }
+}
+
+impl BinOpKind {
+    pub fn as_str(self) -> &'static str {
+        use BinOpKind::*;
+        match self {
+            Add => "+",
+            Sub => "-",
+            Mul => "*",
+            Div => "/",
+            Rem => "%",
+            And => "&&",
+            Or => "||",
+            BitXor => "^",
+            BitAnd => "&",
+            BitOr => "|",
+            Shl => "<<",
+            Shr => ">>",
+            Eq => "==",
+            Lt => "<",
+            Le => "<=",
+            Ne => "!=",
+            Ge => ">=",
+            Gt => ">",
+        }
+    }
+
+    pub fn is_comparison(self) -> bool {
+        use BinOpKind::*;
+        matches!(self, Eq | Lt | Le | Ne | Ge | Gt)
+    }
+}
@@ -1,2 +1,8 @@
+pub mod ast;
+pub mod lit;
+pub mod mut_visit;
+pub mod pprust;
 pub mod token;
-pub mod tokenstream;
\ No newline at end of file
+pub mod tokenstream;
+pub mod util;
+pub mod visit;
\ No newline at end of file
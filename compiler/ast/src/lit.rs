@@ -0,0 +1,392 @@
+//! Evaluation of literal tokens into the values they represent
+
+use std::{fmt, rc::Rc};
+
+use lex::unescape::{self, MixedUnit, Mode};
+use source_idx::{sym, Symbol};
+
+use crate::token::{Lit, LitKind};
+
+/// The value of a literal e.g. `0x2a` is `Int(42, Unsuffixed)`
+#[derive(Clone, PartialEq, Debug)]
+pub enum LitValue {
+    /// the unescaped content of a string
+    Str(Symbol, StrStyle),
+    ByteStr(Rc<[u8]>, StrStyle),
+    /// the bytes of a C string including the terminating nul
+    CStr(Rc<[u8]>, StrStyle),
+    Byte(u8),
+    Char(char),
+    Int(u128, LitIntType),
+    /// the float is kept as its decimal text without `_`, so no precision is lost
+    /// before its type is known, see [`LitValue::float_to_f64`]
+    Float(Symbol, LitFloatType),
+    /// produced by the parser for the `true` and `false` keywords
+    Bool(bool),
+    /// the literal was already reported as invalid by the lexer
+    Err,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum StrStyle {
+    /// a string with escapes e.g. `"a\nb"`
+    Cooked,
+    /// a raw string with the number of `#`s e.g. `r#"a\nb"#`
+    Raw(u8),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum IntTy {
+    Isize,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum UintTy {
+    Usize,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum FloatTy {
+    F32,
+    F64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum LitIntType {
+    Signed(IntTy),
+    Unsigned(UintTy),
+    /// the type is inferred later
+    Unsuffixed,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum LitFloatType {
+    Suffixed(FloatTy),
+    /// the type is inferred later
+    Unsuffixed,
+}
+
+/// Why a literal token could not be turned into a [`LitValue`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LitError {
+    /// the suffix is not valid for this kind of literal
+    InvalidSuffix(Symbol),
+    /// a float with a base other than decimal e.g. `0b1f32`
+    NonDecimalFloat(u32),
+    /// the value does not even fit into a `u128`
+    IntTooLarge(u32),
+    /// the value does not fit into the type of its suffix e.g. `256u8`
+    IntOutOfRange(LitIntType),
+    /// the float is infinite in the type of its suffix e.g. `1e39f32`
+    FloatOutOfRange(FloatTy),
+    /// an escape error which should have been reported by the lexer
+    InvalidEscape,
+}
+
+impl IntTy {
+    pub fn name(self) -> Symbol {
+        match self {
+            IntTy::Isize => sym::isize,
+            IntTy::I8 => sym::i8,
+            IntTy::I16 => sym::i16,
+            IntTy::I32 => sym::i32,
+            IntTy::I64 => sym::i64,
+            IntTy::I128 => sym::i128,
+        }
+    }
+
+    /// bit width of the type, `isize` is assumed to be 64 bits wide
+    pub fn bit_width(self) -> u32 {
+        match self {
+            IntTy::I8 => 8,
+            IntTy::I16 => 16,
+            IntTy::I32 => 32,
+            IntTy::Isize | IntTy::I64 => 64,
+            IntTy::I128 => 128,
+        }
+    }
+
+    /// the largest value of the type
+    pub fn max(self) -> u128 {
+        (1u128 << (self.bit_width() - 1)) - 1
+    }
+}
+
+impl UintTy {
+    pub fn name(self) -> Symbol {
+        match self {
+            UintTy::Usize => sym::usize,
+            UintTy::U8 => sym::u8,
+            UintTy::U16 => sym::u16,
+            UintTy::U32 => sym::u32,
+            UintTy::U64 => sym::u64,
+            UintTy::U128 => sym::u128,
+        }
+    }
+
+    /// bit width of the type, `usize` is assumed to be 64 bits wide
+    pub fn bit_width(self) -> u32 {
+        match self {
+            UintTy::U8 => 8,
+            UintTy::U16 => 16,
+            UintTy::U32 => 32,
+            UintTy::Usize | UintTy::U64 => 64,
+            UintTy::U128 => 128,
+        }
+    }
+
+    pub fn max(self) -> u128 {
+        u128::MAX >> (128 - self.bit_width())
+    }
+}
+
+impl FloatTy {
+    pub fn name(self) -> Symbol {
+        match self {
+            FloatTy::F32 => sym::f32,
+            FloatTy::F64 => sym::f64,
+        }
+    }
+}
+
+impl LitIntType {
+    pub fn from_suffix(suffix: Symbol) -> Option<LitIntType> {
+        Some(match suffix {
+            s if s == sym::isize => LitIntType::Signed(IntTy::Isize),
+            s if s == sym::i8 => LitIntType::Signed(IntTy::I8),
+            s if s == sym::i16 => LitIntType::Signed(IntTy::I16),
+            s if s == sym::i32 => LitIntType::Signed(IntTy::I32),
+            s if s == sym::i64 => LitIntType::Signed(IntTy::I64),
+            s if s == sym::i128 => LitIntType::Signed(IntTy::I128),
+            s if s == sym::usize => LitIntType::Unsigned(UintTy::Usize),
+            s if s == sym::u8 => LitIntType::Unsigned(UintTy::U8),
+            s if s == sym::u16 => LitIntType::Unsigned(UintTy::U16),
+            s if s == sym::u32 => LitIntType::Unsigned(UintTy::U32),
+            s if s == sym::u64 => LitIntType::Unsigned(UintTy::U64),
+            s if s == sym::u128 => LitIntType::Unsigned(UintTy::U128),
+            _ => return None,
+        })
+    }
+
+    /// true if `value` fits into the type, `negated` is used for literals with a leading `-`
+    /// as the minimum of a signed type is one larger than its maximum e.g. `-128i8`
+    pub fn fits(self, value: u128, negated: bool) -> bool {
+        match self {
+            LitIntType::Signed(ty) => value <= ty.max() + negated as u128,
+            LitIntType::Unsigned(ty) => !negated && value <= ty.max(),
+            LitIntType::Unsuffixed => true,
+        }
+    }
+}
+
+impl fmt::Display for LitIntType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LitIntType::Signed(ty) => write!(f, "{}", ty.name()),
+            LitIntType::Unsigned(ty) => write!(f, "{}", ty.name()),
+            LitIntType::Unsuffixed => write!(f, "{{integer}}"),
+        }
+    }
+}
+
+impl LitFloatType {
+    pub fn from_suffix(suffix: Symbol) -> Option<LitFloatType> {
+        match suffix {
+            s if s == sym::f32 => Some(LitFloatType::Suffixed(FloatTy::F32)),
+            s if s == sym::f64 => Some(LitFloatType::Suffixed(FloatTy::F64)),
+            _ => None,
+        }
+    }
+}
+
+impl LitValue {
+    /// evaluates a literal token, the errors of the lexer are not reported again
+    pub fn from_token_lit(lit: Lit) -> Result<LitValue, LitError> {
+        let text = lit.symbol.as_str();
+        Ok(match lit.kind {
+            LitKind::Err => LitValue::Err,
+            _ if !lit.kind.is_numeric() && lit.suffix.is_some() => {
+                return Err(LitError::InvalidSuffix(lit.suffix.unwrap()));
+            }
+            LitKind::Char => LitValue::Char(unescape_char(text, Mode::Char)?),
+            LitKind::Byte => LitValue::Byte(unescape_char(text, Mode::Byte)? as u8),
+            LitKind::Str => LitValue::Str(unescape_str(lit.symbol)?, StrStyle::Cooked),
+            // raw strings do not contain escapes and bare `\r`s were rejected by the lexer
+            LitKind::StrRaw(n) => LitValue::Str(lit.symbol, StrStyle::Raw(n)),
+            LitKind::ByteStr => LitValue::ByteStr(unescape_bytes(text, Mode::ByteStr)?, StrStyle::Cooked),
+            LitKind::ByteStrRaw(n) => LitValue::ByteStr(text.as_bytes().into(), StrStyle::Raw(n)),
+            LitKind::CStr => LitValue::CStr(unescape_c_str(text, Mode::CStr)?, StrStyle::Cooked),
+            LitKind::CStrRaw(n) => LitValue::CStr(unescape_c_str(text, Mode::RawCStr)?, StrStyle::Raw(n)),
+            LitKind::Int => int_lit(text, lit.suffix)?,
+            LitKind::Float => float_lit(text, lit.suffix)?,
+        })
+    }
+
+    /// the value of a float literal in the given type, the result is always finite
+    pub fn float_to_f64(symbol: Symbol, ty: FloatTy) -> Result<f64, LitError> {
+        // `str::parse` rounds correctly and the text was validated by the lexer
+        let text = symbol.as_str();
+        let value = match ty {
+            FloatTy::F32 => text.parse::<f32>().map(f64::from),
+            FloatTy::F64 => text.parse::<f64>(),
+        }
+        .map_err(|_| LitError::FloatOutOfRange(ty))?;
+        if value.is_finite() {
+            Ok(value)
+        } else {
+            Err(LitError::FloatOutOfRange(ty))
+        }
+    }
+}
+
+impl LitKind {
+    pub fn is_numeric(self) -> bool {
+        matches!(self, LitKind::Int | LitKind::Float)
+    }
+}
+
+fn unescape_char(text: &str, mode: Mode) -> Result<char, LitError> {
+    let mut res = Err(LitError::InvalidEscape);
+    unescape::unescape_unicode(text, mode, &mut |_, c| {
+        res = c.map_err(|_| LitError::InvalidEscape);
+    });
+    res
+}
+
+fn unescape_str(symbol: Symbol) -> Result<Symbol, LitError> {
+    let text = symbol.as_str();
+    // most strings do not contain escapes, so they can be reused
+    if !text.contains(['\\', '\r']) {
+        return Ok(symbol);
+    }
+    let mut buf = String::with_capacity(text.len());
+    let mut res = Ok(());
+    unescape::unescape_unicode(text, Mode::Str, &mut |_, c| match c {
+        Ok(c) => buf.push(c),
+        Err(err) if err.is_fatal() => res = Err(LitError::InvalidEscape),
+        Err(_) => {}
+    });
+    res.map(|_| Symbol::get_or_store(&buf))
+}
+
+fn unescape_bytes(text: &str, mode: Mode) -> Result<Rc<[u8]>, LitError> {
+    let mut buf = Vec::with_capacity(text.len());
+    let mut res = Ok(());
+    unescape::unescape_unicode(text, mode, &mut |_, c| match c {
+        // all chars in byte strings are below 0x100
+        Ok(c) => buf.push(c as u8),
+        Err(err) if err.is_fatal() => res = Err(LitError::InvalidEscape),
+        Err(_) => {}
+    });
+    res.map(|_| buf.into())
+}
+
+fn unescape_c_str(text: &str, mode: Mode) -> Result<Rc<[u8]>, LitError> {
+    let mut buf = Vec::with_capacity(text.len() + 1);
+    let mut res = Ok(());
+    unescape::unescape_mixed(text, mode, &mut |_, unit| match unit {
+        Ok(MixedUnit::Char(c)) => buf.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+        Ok(MixedUnit::HighByte(byte)) => buf.push(byte),
+        Err(err) if err.is_fatal() => res = Err(LitError::InvalidEscape),
+        Err(_) => {}
+    });
+    buf.push(0);
+    res.map(|_| buf.into())
+}
+
+fn int_lit(text: &str, suffix: Option<Symbol>) -> Result<LitValue, LitError> {
+    let (base, digits) = match text.as_bytes() {
+        [b'0', b'x', ..] => (16, &text[2..]),
+        [b'0', b'o', ..] => (8, &text[2..]),
+        [b'0', b'b', ..] => (2, &text[2..]),
+        _ => (10, text),
+    };
+
+    let ty = match suffix {
+        None => LitIntType::Unsuffixed,
+        Some(suffix) => match LitIntType::from_suffix(suffix) {
+            Some(ty) => ty,
+            // an int with a float suffix e.g. `1f32`
+            None if LitFloatType::from_suffix(suffix).is_some() => {
+                if base != 10 {
+                    return Err(LitError::NonDecimalFloat(base));
+                }
+                return float_lit(text, Some(suffix));
+            }
+            None => return Err(LitError::InvalidSuffix(suffix)),
+        },
+    };
+
+    let digits = digits.replace('_', "");
+    let value = u128::from_str_radix(&digits, base).map_err(|_| LitError::IntTooLarge(base))?;
+    // negative literals are not known here, so the minimum of signed types is allowed as well
+    if !ty.fits(value, matches!(ty, LitIntType::Signed(_))) {
+        return Err(LitError::IntOutOfRange(ty));
+    }
+    Ok(LitValue::Int(value, ty))
+}
+
+fn float_lit(text: &str, suffix: Option<Symbol>) -> Result<LitValue, LitError> {
+    let ty = match suffix {
+        None => LitFloatType::Unsuffixed,
+        Some(suffix) => LitFloatType::from_suffix(suffix).ok_or(LitError::InvalidSuffix(suffix))?,
+    };
+    let symbol = Symbol::get_or_store(&text.replace('_', ""));
+    let check_ty = match ty {
+        LitFloatType::Suffixed(ty) => ty,
+        LitFloatType::Unsuffixed => FloatTy::F64,
+    };
+    LitValue::float_to_f64(symbol, check_ty)?;
+    Ok(LitValue::Float(symbol, ty))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn eval(kind: LitKind, text: &str, suffix: Option<&str>) -> Result<LitValue, LitError> {
+        let lit = Lit {
+            kind,
+            symbol: Symbol::get_or_store(text),
+            suffix: suffix.map(Symbol::get_or_store),
+        };
+        LitValue::from_token_lit(lit)
+    }
+
+    #[test]
+    fn lit_values() {
+        use LitValue::{Byte, ByteStr, CStr, Char, Float, Int, Str};
+        assert_eq!(eval(LitKind::Int, "0b1010_1010", None), Ok(Int(0xaa, LitIntType::Unsuffixed)));
+        assert_eq!(eval(LitKind::Int, "0xff", Some("u8")), Ok(Int(255, LitIntType::Unsigned(UintTy::U8))));
+        assert_eq!(eval(LitKind::Int, "256", Some("u8")), Err(LitError::IntOutOfRange(LitIntType::Unsigned(UintTy::U8))));
+        assert_eq!(eval(LitKind::Int, "128", Some("i8")), Ok(Int(128, LitIntType::Signed(IntTy::I8))));
+        assert!(!LitIntType::Signed(IntTy::I8).fits(128, false));
+        assert_eq!(eval(LitKind::Int, &"9".repeat(40), None), Err(LitError::IntTooLarge(10)));
+        assert_eq!(
+            eval(LitKind::Int, "1", Some("f32")),
+            Ok(Float(Symbol::get_or_store("1"), LitFloatType::Suffixed(FloatTy::F32)))
+        );
+
+        assert_eq!(eval(LitKind::Float, "1.0e-4", None), Ok(Float(Symbol::get_or_store("1.0e-4"), LitFloatType::Unsuffixed)));
+        assert_eq!(LitValue::float_to_f64(Symbol::get_or_store("1.0e-4"), FloatTy::F64), Ok(1.0e-4));
+        assert_eq!(eval(LitKind::Float, "1e39", Some("f32")), Err(LitError::FloatOutOfRange(FloatTy::F32)));
+
+        assert_eq!(eval(LitKind::Char, r"\x41", None), Ok(Char('A')));
+        assert_eq!(eval(LitKind::Byte, r"\xff", None), Ok(Byte(0xff)));
+        assert_eq!(eval(LitKind::Str, r"a\nb", None), Ok(Str(Symbol::get_or_store("a\nb"), StrStyle::Cooked)));
+        assert_eq!(eval(LitKind::StrRaw(1), r"a\nb", None), Ok(Str(Symbol::get_or_store(r"a\nb"), StrStyle::Raw(1))));
+        assert_eq!(eval(LitKind::ByteStr, r"a\xff", None), Ok(ByteStr(b"a\xff"[..].into(), StrStyle::Cooked)));
+        assert_eq!(eval(LitKind::CStr, r"ä\xff", None), Ok(CStr(b"\xc3\xa4\xff\0"[..].into(), StrStyle::Cooked)));
+        assert_eq!(eval(LitKind::Str, "a", Some("x")), Err(LitError::InvalidSuffix(Symbol::get_or_store("x"))));
+    }
+}
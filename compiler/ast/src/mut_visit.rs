@@ -0,0 +1,237 @@
+//! Mutable traversal of the AST, used by passes which rewrite nodes in place
+//!
+//! Works like [`crate::visit`], every `visit_*` method of a [`MutVisitor`] calls the matching
+//! `walk_*` function by default. A node can be replaced by assigning to it, e.g. the `kind` of an
+//! [`Expr`]. The ids of all nodes are passed to [`MutVisitor::visit_id`].
+
+use crate::ast::*;
+
+pub trait MutVisitor: Sized {
+    fn visit_crate(&mut self, krate: &mut Crate) {
+        walk_crate(self, krate)
+    }
+    fn visit_item(&mut self, item: &mut Item) {
+        walk_item(self, item)
+    }
+    fn visit_fn(&mut self, func: &mut Fn) {
+        walk_fn(self, func)
+    }
+    fn visit_param(&mut self, param: &mut Param) {
+        walk_param(self, param)
+    }
+    fn visit_fn_ret_ty(&mut self, ret_ty: &mut FnRetTy) {
+        walk_fn_ret_ty(self, ret_ty)
+    }
+    fn visit_attribute(&mut self, _attr: &mut Attribute) {}
+    fn visit_block(&mut self, block: &mut Block) {
+        walk_block(self, block)
+    }
+    fn visit_stmt(&mut self, stmt: &mut Stmt) {
+        walk_stmt(self, stmt)
+    }
+    fn visit_local(&mut self, local: &mut Local) {
+        walk_local(self, local)
+    }
+    fn visit_pat(&mut self, pat: &mut Pat) {
+        walk_pat(self, pat)
+    }
+    fn visit_ty(&mut self, ty: &mut Ty) {
+        walk_ty(self, ty)
+    }
+    fn visit_expr(&mut self, expr: &mut Expr) {
+        walk_expr(self, expr)
+    }
+    fn visit_call_arg(&mut self, arg: &mut CallArg) {
+        walk_call_arg(self, arg)
+    }
+    fn visit_path(&mut self, path: &mut Path) {
+        walk_path(self, path)
+    }
+    fn visit_mac_call(&mut self, _mac: &mut MacCall) {}
+    fn visit_ident(&mut self, _ident: &mut Ident) {}
+    fn visit_id(&mut self, _id: &mut NodeId) {}
+}
+
+pub fn walk_crate<V: MutVisitor>(visitor: &mut V, krate: &mut Crate) {
+    visitor.visit_id(&mut krate.id);
+    krate.attrs.iter_mut().for_each(|attr| visitor.visit_attribute(attr));
+    krate.items.iter_mut().for_each(|item| visitor.visit_item(item));
+}
+
+pub fn walk_item<V: MutVisitor>(visitor: &mut V, item: &mut Item) {
+    visitor.visit_id(&mut item.id);
+    item.attrs.iter_mut().for_each(|attr| visitor.visit_attribute(attr));
+    visitor.visit_ident(&mut item.ident);
+    match &mut item.kind {
+        ItemKind::Fn(func) => visitor.visit_fn(func),
+        ItemKind::Mod(ModKind::Loaded(items)) => items.iter_mut().for_each(|item| visitor.visit_item(item)),
+        ItemKind::Mod(ModKind::Unloaded) | ItemKind::Err => {}
+    }
+}
+
+pub fn walk_fn<V: MutVisitor>(visitor: &mut V, func: &mut Fn) {
+    func.sig.inputs.iter_mut().for_each(|param| visitor.visit_param(param));
+    visitor.visit_fn_ret_ty(&mut func.sig.output);
+    if let Some(body) = &mut func.body {
+        visitor.visit_block(body);
+    }
+}
+
+pub fn walk_param<V: MutVisitor>(visitor: &mut V, param: &mut Param) {
+    visitor.visit_id(&mut param.id);
+    visitor.visit_ident(&mut param.ident);
+    visitor.visit_ty(&mut param.ty);
+    if let Some(default) = &mut param.default {
+        visitor.visit_expr(default);
+    }
+}
+
+pub fn walk_fn_ret_ty<V: MutVisitor>(visitor: &mut V, ret_ty: &mut FnRetTy) {
+    match ret_ty {
+        FnRetTy::Default(_) | FnRetTy::Never(_) => {}
+        FnRetTy::Ty(ty) | FnRetTy::Option { ty, .. } => visitor.visit_ty(ty),
+        FnRetTy::Result { ok, err, .. } => {
+            if let Some(ok) = ok {
+                visitor.visit_ty(ok);
+            }
+            visitor.visit_ty(err);
+        }
+    }
+}
+
+pub fn walk_block<V: MutVisitor>(visitor: &mut V, block: &mut Block) {
+    visitor.visit_id(&mut block.id);
+    block.stmts.iter_mut().for_each(|stmt| visitor.visit_stmt(stmt));
+}
+
+pub fn walk_stmt<V: MutVisitor>(visitor: &mut V, stmt: &mut Stmt) {
+    visitor.visit_id(&mut stmt.id);
+    match &mut stmt.kind {
+        StmtKind::Let(local) => visitor.visit_local(local),
+        StmtKind::Item(item) => visitor.visit_item(item),
+        StmtKind::Expr(expr) | StmtKind::Semi(expr) => visitor.visit_expr(expr),
+        StmtKind::Empty => {}
+    }
+}
+
+pub fn walk_local<V: MutVisitor>(visitor: &mut V, local: &mut Local) {
+    visitor.visit_id(&mut local.id);
+    visitor.visit_pat(&mut local.pat);
+    if let Some(ty) = &mut local.ty {
+        visitor.visit_ty(ty);
+    }
+    if let Some(init) = &mut local.init {
+        visitor.visit_expr(init);
+    }
+}
+
+pub fn walk_pat<V: MutVisitor>(visitor: &mut V, pat: &mut Pat) {
+    visitor.visit_id(&mut pat.id);
+    match &mut pat.kind {
+        PatKind::Wild | PatKind::Rest => {}
+        PatKind::Ident(_, ident) => visitor.visit_ident(ident),
+        PatKind::Tuple(pats) => pats.iter_mut().for_each(|pat| visitor.visit_pat(pat)),
+        PatKind::Ref(pat, _) => visitor.visit_pat(pat),
+        PatKind::Lit(expr) => visitor.visit_expr(expr),
+        PatKind::Range(start, end, _) => {
+            start.iter_mut().chain(end).for_each(|expr| visitor.visit_expr(expr));
+        }
+        PatKind::Path(path) => visitor.visit_path(path),
+    }
+}
+
+pub fn walk_ty<V: MutVisitor>(visitor: &mut V, ty: &mut Ty) {
+    visitor.visit_id(&mut ty.id);
+    match &mut ty.kind {
+        TyKind::Path(path) => visitor.visit_path(path),
+        TyKind::Ref(_, ty) | TyKind::Slice(ty) => visitor.visit_ty(ty),
+        TyKind::Array(ty, len) => {
+            visitor.visit_ty(ty);
+            visitor.visit_expr(len);
+        }
+        TyKind::Tup(tys) => tys.iter_mut().for_each(|ty| visitor.visit_ty(ty)),
+        TyKind::Never | TyKind::Infer => {}
+    }
+}
+
+pub fn walk_path<V: MutVisitor>(visitor: &mut V, path: &mut Path) {
+    for segment in &mut path.segments {
+        visitor.visit_id(&mut segment.id);
+        visitor.visit_ident(&mut segment.ident);
+        if let Some(args) = &mut segment.args {
+            args.args.iter_mut().for_each(|ty| visitor.visit_ty(ty));
+        }
+    }
+}
+
+pub fn walk_call_arg<V: MutVisitor>(visitor: &mut V, arg: &mut CallArg) {
+    match &mut arg.kind {
+        CallArgKind::Positional(expr) | CallArgKind::Spread(expr) => visitor.visit_expr(expr),
+        CallArgKind::Named(ident, expr) => {
+            visitor.visit_ident(ident);
+            visitor.visit_expr(expr);
+        }
+    }
+}
+
+pub fn walk_expr<V: MutVisitor>(visitor: &mut V, expr: &mut Expr) {
+    visitor.visit_id(&mut expr.id);
+    match &mut expr.kind {
+        ExprKind::Lit(_) | ExprKind::Continue | ExprKind::Err => {}
+        ExprKind::Path(path) => visitor.visit_path(path),
+        ExprKind::Paren(expr) => visitor.visit_expr(expr),
+        ExprKind::Tup(exprs) | ExprKind::Array(exprs) => exprs.iter_mut().for_each(|expr| visitor.visit_expr(expr)),
+        ExprKind::Block(block) | ExprKind::Loop(block) => visitor.visit_block(block),
+        ExprKind::If(cond, then, els) => {
+            visitor.visit_expr(cond);
+            visitor.visit_block(then);
+            if let Some(els) = els {
+                visitor.visit_expr(els);
+            }
+        }
+        ExprKind::While(cond, body) => {
+            visitor.visit_expr(cond);
+            visitor.visit_block(body);
+        }
+        ExprKind::Closure(closure) => {
+            for param in &mut closure.params {
+                visitor.visit_id(&mut param.id);
+                visitor.visit_pat(&mut param.pat);
+                if let Some(ty) = &mut param.ty {
+                    visitor.visit_ty(ty);
+                }
+            }
+            visitor.visit_expr(&mut closure.body);
+        }
+        ExprKind::Call(func, args) => {
+            visitor.visit_expr(func);
+            args.iter_mut().for_each(|arg| visitor.visit_call_arg(arg));
+        }
+        ExprKind::MethodCall(call) => {
+            visitor.visit_expr(&mut call.receiver);
+            visitor.visit_ident(&mut call.ident);
+            call.args.iter_mut().for_each(|arg| visitor.visit_call_arg(arg));
+        }
+        ExprKind::Field(expr, ident) => {
+            visitor.visit_expr(expr);
+            visitor.visit_ident(ident);
+        }
+        ExprKind::Binary(_, lhs, rhs)
+        | ExprKind::Assign(lhs, rhs, _)
+        | ExprKind::AssignOp(_, lhs, rhs)
+        | ExprKind::Index(lhs, rhs) => {
+            visitor.visit_expr(lhs);
+            visitor.visit_expr(rhs);
+        }
+        ExprKind::Unary(_, expr) | ExprKind::AddrOf(_, expr) | ExprKind::Try(expr) => visitor.visit_expr(expr),
+        ExprKind::Range(start, end, _) => {
+            start.iter_mut().chain(end).for_each(|expr| visitor.visit_expr(expr));
+        }
+        ExprKind::Ret(expr) | ExprKind::Break(expr) => {
+            if let Some(expr) = expr {
+                visitor.visit_expr(expr);
+            }
+        }
+        ExprKind::MacCall(mac) => visitor.visit_mac_call(mac),
+    }
+}
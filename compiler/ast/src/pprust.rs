@@ -0,0 +1,187 @@
+//! Printing tokens and token streams back to source text
+//!
+//! Tokens are separated as their [`Spacing`] says, so a stream of the lexer is printed to
+//! text which is lexed to the same stream again. Only the amount of whitespace and the text of
+//! plain comments, which is not kept, are lost.
+
+use crate::{
+    ast::AttrStyle,
+    token::{CommentKind, Delimiter, Lit, LitKind, Token, TokenKind},
+    tokenstream::{Spacing, TokenStream, TokenTree},
+};
+
+/// printed in place of [`Delimiter::Invisible`] by [`tts_to_string_with_invisible_delims`],
+/// they are comments so the text can still be lexed
+pub const INVISIBLE_OPEN_MARKER: &str = "/*«*/";
+pub const INVISIBLE_CLOSE_MARKER: &str = "/*»*/";
+
+pub fn token_kind_to_string(kind: &TokenKind) -> String {
+    match *kind {
+        TokenKind::Literal(lit) => lit_to_string(lit),
+        TokenKind::Ident(name) => name.to_string(),
+        TokenKind::Comment(CommentKind::Line) => "//".to_string(),
+        TokenKind::Comment(CommentKind::Block) => "/**/".to_string(),
+        TokenKind::DocComment(comment_kind, style, text) => doc_comment_to_string(comment_kind, style, text.as_str()),
+        TokenKind::Eof => String::new(),
+        ref kind => kind.punct_str().unwrap_or_default().to_string(),
+    }
+}
+
+pub fn token_to_string(token: &Token) -> String {
+    token_kind_to_string(&token.kind)
+}
+
+/// the literal with its quotes, prefix and suffix e.g. `b"abc"` or `12u8`
+pub fn lit_to_string(lit: Lit) -> String {
+    let symbol = lit.symbol;
+    let hashes = |n: u8| "#".repeat(n as usize);
+    let mut out = match lit.kind {
+        LitKind::Char => format!("'{}'", symbol),
+        LitKind::Byte => format!("b'{}'", symbol),
+        LitKind::Str => format!("\"{}\"", symbol),
+        LitKind::StrRaw(n) => format!("r{0}\"{1}\"{0}", hashes(n), symbol),
+        LitKind::ByteStr => format!("b\"{}\"", symbol),
+        LitKind::ByteStrRaw(n) => format!("br{0}\"{1}\"{0}", hashes(n), symbol),
+        LitKind::CStr => format!("c\"{}\"", symbol),
+        LitKind::CStrRaw(n) => format!("cr{0}\"{1}\"{0}", hashes(n), symbol),
+        LitKind::Float | LitKind::Int | LitKind::Err => symbol.to_string(),
+    };
+    if let Some(suffix) = lit.suffix {
+        out.push_str(suffix.as_str());
+    }
+    out
+}
+
+pub fn doc_comment_to_string(comment_kind: CommentKind, style: AttrStyle, text: &str) -> String {
+    match (comment_kind, style) {
+        (CommentKind::Line, AttrStyle::Outer) => format!("///{}", text),
+        (CommentKind::Line, AttrStyle::Inner) => format!("//!{}", text),
+        (CommentKind::Block, AttrStyle::Outer) => format!("/**{}*/", text),
+        (CommentKind::Block, AttrStyle::Inner) => format!("/*!{}*/", text),
+    }
+}
+
+pub fn tt_to_string(tree: &TokenTree) -> String {
+    let mut printer = Printer::new(false);
+    printer.print_tt(tree);
+    printer.out
+}
+
+/// invisible delimiters are left out, the content of their groups is printed
+pub fn tts_to_string(stream: &TokenStream) -> String {
+    let mut printer = Printer::new(false);
+    printer.print_tts(stream);
+    printer.out
+}
+
+/// like [`tts_to_string`], but invisible delimiters are shown as [`INVISIBLE_OPEN_MARKER`]
+/// and [`INVISIBLE_CLOSE_MARKER`]
+pub fn tts_to_string_with_invisible_delims(stream: &TokenStream) -> String {
+    let mut printer = Printer::new(true);
+    printer.print_tts(stream);
+    printer.out
+}
+
+struct Printer {
+    out: String,
+    /// the spacing of the last printed token, the space is only added in front of the next one
+    /// to not end the text with a space
+    last_spacing: Spacing,
+    mark_invisible_delims: bool,
+}
+
+impl Printer {
+    fn new(mark_invisible_delims: bool) -> Printer {
+        Printer { out: String::new(), last_spacing: Spacing::JointHidden, mark_invisible_delims }
+    }
+
+    fn print_tts(&mut self, stream: &TokenStream) {
+        for tree in stream.trees() {
+            self.print_tt(tree);
+        }
+    }
+
+    fn print_tt(&mut self, tree: &TokenTree) {
+        match tree {
+            TokenTree::SingleToken(token, spacing) => self.print_token(&token_to_string(token), &token.kind, *spacing),
+            TokenTree::TokenGroup(_, spacing, delim, stream) => {
+                let (open, close) = match delim {
+                    Delimiter::Invisible if !self.mark_invisible_delims => {
+                        // the spacing of the delimiters does not matter as they are not printed
+                        return self.print_tts(stream);
+                    }
+                    Delimiter::Invisible => (INVISIBLE_OPEN_MARKER, INVISIBLE_CLOSE_MARKER),
+                    delim => (delim.open_str().unwrap_or_default(), delim.close_str().unwrap_or_default()),
+                };
+                self.print_token(open, &TokenKind::OpenDelim(*delim), spacing.open());
+                self.print_tts(stream);
+                self.print_token(close, &TokenKind::CloseDelim(*delim), spacing.close());
+            }
+        }
+    }
+
+    fn print_token(&mut self, text: &str, kind: &TokenKind, spacing: Spacing) {
+        if self.last_spacing == Spacing::Alone && !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push(' ');
+        }
+        self.out.push_str(text);
+        self.last_spacing = spacing;
+        // a line comment ends at the end of the line
+        if matches!(kind, TokenKind::Comment(CommentKind::Line) | TokenKind::DocComment(CommentKind::Line, ..)) {
+            self.out.push('\n');
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use source_idx::{GroupSrcIdx, Symbol, DUMMY_SRC_DATA};
+
+    use super::*;
+    use crate::tokenstream::GroupSpacing;
+
+    fn token(kind: TokenKind, spacing: Spacing) -> TokenTree {
+        TokenTree::SingleToken(Token::new(kind, DUMMY_SRC_DATA), spacing)
+    }
+
+    fn group(delim: Delimiter, trees: Vec<TokenTree>, close: Spacing) -> TokenTree {
+        let dspan = GroupSrcIdx::from_pair(DUMMY_SRC_DATA, DUMMY_SRC_DATA);
+        let spacing = GroupSpacing::new(Spacing::JointHidden, close);
+        TokenTree::TokenGroup(dspan, spacing, delim, TokenStream::new(trees))
+    }
+
+    #[test]
+    fn literals() {
+        let lit = |kind, text, suffix: Option<&str>| {
+            lit_to_string(Lit { kind, symbol: Symbol::get_or_store(text), suffix: suffix.map(Symbol::get_or_store) })
+        };
+        assert_eq!(lit(LitKind::Int, "12", Some("u8")), "12u8");
+        assert_eq!(lit(LitKind::Char, "\\n", None), "'\\n'");
+        assert_eq!(lit(LitKind::Byte, "a", None), "b'a'");
+        assert_eq!(lit(LitKind::ByteStr, "a", None), "b\"a\"");
+        assert_eq!(lit(LitKind::StrRaw(2), "a\"#b", None), "r##\"a\"#b\"##");
+        assert_eq!(lit(LitKind::CStrRaw(0), "a", None), "cr\"a\"");
+    }
+
+    #[test]
+    fn streams() {
+        let ident = |name, spacing| token(TokenKind::Ident(Symbol::get_or_store(name)), spacing);
+        let stream = TokenStream::new(vec![
+            token(TokenKind::DocComment(CommentKind::Line, AttrStyle::Outer, Symbol::get_or_store(" doc")), Spacing::Alone),
+            ident("f", Spacing::JointHidden),
+            group(
+                Delimiter::Parenthesis,
+                vec![
+                    ident("a", Spacing::Joint),
+                    token(TokenKind::Comma, Spacing::Alone),
+                    group(Delimiter::Invisible, vec![ident("b", Spacing::JointHidden)], Spacing::JointHidden),
+                ],
+                Spacing::Alone,
+            ),
+            token(TokenKind::FatArrow, Spacing::Alone),
+            ident("c", Spacing::Alone),
+        ]);
+        assert_eq!(tts_to_string(&stream), "/// doc\nf(a, b) => c");
+        assert_eq!(tts_to_string_with_invisible_delims(&stream), "/// doc\nf(a, /*«*/b/*»*/) => c");
+    }
+}
@@ -1,4 +1,6 @@
-use source_idx::{SrcData, Symbol, DUMMY_SRC_DATA};
+use source_idx::{BytePos, SrcData, Symbol, DUMMY_SRC_DATA};
+
+use crate::ast::AttrStyle;
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum TokenKind {
@@ -70,7 +72,10 @@ pub enum TokenKind {
     Literal(Lit),
 
     // comments
-    Comment(CommentType),
+    /// a plain comment, its text is not kept
+    Comment(CommentKind),
+    /// `/// text` or `//! text`, the symbol is the text behind the `///` or between `/**` and `*/`
+    DocComment(CommentKind, AttrStyle, Symbol),
 
     Ident(Symbol),
 
@@ -85,10 +90,62 @@ pub struct Token {
 }
 
 impl TokenKind {
-    pub fn lit(kind: LitKind, symbol: Symbol) -> TokenKind {
-        TokenKind::Literal(Lit { kind, symbol })
+    pub fn lit(kind: LitKind, symbol: Symbol, suffix: Option<Symbol>) -> TokenKind {
+        TokenKind::Literal(Lit { kind, symbol, suffix })
     }
 
+    /// the text of punctuation and delimiters, which always look the same
+    pub fn punct_str(&self) -> Option<&'static str> {
+        use BinOpToken::*;
+        use TokenKind::*;
+        Some(match *self {
+            Eq => "=",
+            Lt => "<",
+            Le => "<=",
+            EqEq => "==",
+            Ne => "!=",
+            Ge => ">=",
+            Gt => ">",
+            AndAnd => "&&",
+            OrOr => "||",
+            Not => "!",
+            Tilde => "~",
+            BinOp(op) => op.as_str(),
+            BinOpEq(op) => match op {
+                Plus => "+=",
+                Minus => "-=",
+                Star => "*=",
+                Slash => "/=",
+                Percent => "%=",
+                Peak => "^=",
+                And => "&=",
+                Or => "|=",
+                Shl => "<<=",
+                Shr => ">>=",
+            },
+            At => "@",
+            Dot => ".",
+            DotDot => "..",
+            DotDotDot => "...",
+            DotDotEq => "..=",
+            Comma => ",",
+            Semi => ";",
+            Colon => ":",
+            DoubleColon => "::",
+            RArrow => "->",
+            LArrow => "<-",
+            FatArrow => "=>",
+            Hashtag => "#",
+            Dollar => "$",
+            Question => "?",
+            SingleQote => "'",
+            OpenDelim(delim) => delim.open_str()?,
+            CloseDelim(delim) => delim.close_str()?,
+            Literal(_) | Comment(_) | DocComment(..) | Ident(_) | Eof => return None,
+        })
+    }
+
+    /// splits a glued token into two, the first one is a prefix of the glued token
     pub fn split_double_token(&self) -> Option<(TokenKind, TokenKind)> {
         use TokenKind::*;
         use BinOpToken::*;
@@ -107,10 +164,13 @@ impl TokenKind {
             BinOpEq(Peak) => (BinOp(Peak), Eq),
             BinOpEq(And) => (BinOp(And), Eq),
             BinOpEq(Or) => (BinOp(Or), Eq),
-            BinOpEq(Shl) => (BinOp(Shl), Eq),
-            BinOpEq(Shr) => (BinOp(Shr), Eq),
+            BinOpEq(Shl) => (Lt, Le),
+            BinOpEq(Shr) => (Gt, Ge),
+            BinOp(Shl) => (Lt, Lt),
+            BinOp(Shr) => (Gt, Gt),
             DotDot => (Dot, Dot),
             DotDotDot => (Dot, DotDot),
+            DotDotEq => (DotDot, Eq),
             DoubleColon => (Colon, Colon),
             RArrow => (BinOp(Minus), Gt),
             LArrow => (Lt, BinOp(Minus)),
@@ -125,6 +185,14 @@ impl Token {
         Token { kind, src_data }
     }
 
+    /// like [`TokenKind::split_double_token`], the source is split after the first token
+    pub fn split_double(&self) -> Option<(Token, Token)> {
+        let (first, second) = self.kind.split_double_token()?;
+        let first_len = first.punct_str()?.len() as u32;
+        let mid = BytePos(self.src_data.lo().0 + first_len);
+        Some((Token::new(first, self.src_data.with_hi(mid)), Token::new(second, self.src_data.with_lo(mid))))
+    }
+
     pub fn dummy() -> Token {
         Token::new(TokenKind::Question, DUMMY_SRC_DATA)
     }
@@ -176,13 +244,13 @@ impl Token {
             | BinOpEq(_) | At | Dot | DotDot | DotDotDot | DotDotEq | Comma | Semi | Colon
             | DoubleColon | RArrow | LArrow | FatArrow | Hashtag | Dollar | Question | SingleQote => true,
             
-            OpenDelim(_) | CloseDelim(_) | Literal(_) | Comment(_) | Ident(_) | Eof => false,
+            OpenDelim(_) | CloseDelim(_) | Literal(_) | Comment(_) | DocComment(..) | Ident(_) | Eof => false,
         }
     }
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
-pub enum CommentType {
+pub enum CommentKind {
     Line,
     Block,
 }
@@ -212,6 +280,24 @@ pub enum BinOpToken {
     Shr,
 }
 
+impl BinOpToken {
+    pub fn as_str(self) -> &'static str {
+        use BinOpToken::*;
+        match self {
+            Plus => "+",
+            Minus => "-",
+            Star => "*",
+            Slash => "/",
+            Percent => "%",
+            Peak => "^",
+            And => "&",
+            Or => "|",
+            Shl => "<<",
+            Shr => ">>",
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum Delimiter {
     /// `( ... )`
@@ -224,16 +310,53 @@ pub enum Delimiter {
     Invisible,
 }
 
+impl Delimiter {
+    /// `None` for [`Delimiter::Invisible`]
+    pub fn open_str(self) -> Option<&'static str> {
+        match self {
+            Delimiter::Parenthesis => Some("("),
+            Delimiter::Brace => Some("{"),
+            Delimiter::Bracket => Some("["),
+            Delimiter::Invisible => None,
+        }
+    }
+
+    /// `None` for [`Delimiter::Invisible`]
+    pub fn close_str(self) -> Option<&'static str> {
+        match self {
+            Delimiter::Parenthesis => Some(")"),
+            Delimiter::Brace => Some("}"),
+            Delimiter::Bracket => Some("]"),
+            Delimiter::Invisible => None,
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub struct Lit {
     pub kind: LitKind,
+    /// the content of the literal without quotes, prefixes and suffix
     pub symbol: Symbol,
+    /// e.g. `u8` in `12u8`
+    pub suffix: Option<Symbol>,
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum LitKind {
     Char,
+    /// `b'a'`
+    Byte,
     Str,
+    /// `r#"raw"#` with the number of `#`s
+    StrRaw(u8),
+    /// `b"bytes"`
+    ByteStr,
+    /// `br#"raw bytes"#` with the number of `#`s
+    ByteStrRaw(u8),
+    /// `c"C string"`
+    CStr,
+    /// `cr#"raw C string"#` with the number of `#`s
+    CStrRaw(u8),
     Float,
     Int,
     Err
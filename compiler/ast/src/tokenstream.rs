@@ -1,31 +1,285 @@
+//! Token streams, the input of the parser and of macros
+//!
+//! A [`TokenStream`] is a list of [`TokenTree`]s, which are either single tokens or groups of
+//! tokens surrounded by delimiters. Streams are reference counted, so cloning them is cheap and
+//! modifying a shared stream copies it first.
+
 use std::rc::Rc;
 
-use crate::token::{Delimiter, Token};
-use source_idx::GroupSrcIdx;
+use crate::token::{Delimiter, Token, TokenKind};
+use source_idx::{GroupSrcIdx, DUMMY_SRC_DATA};
 
-#[derive(Debug)]
+#[derive(Clone, PartialEq, Default, Debug)]
 pub struct TokenStream( pub Rc<Vec<TokenTree>>);
 
 impl TokenStream {
     pub fn new(tts: Vec<TokenTree>) -> TokenStream {
         TokenStream(Rc::new(tts))
     }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// the trees of this stream without going into groups
+    pub fn trees(&self) -> std::slice::Iter<'_, TokenTree> {
+        self.0.iter()
+    }
+
+    /// walks through every token including the ones in groups, see [`TokenStreamCursor`]
+    pub fn cursor(&self) -> TokenStreamCursor {
+        TokenStreamCursor::new(self.clone())
+    }
+
+    /// appends `tree`, a token is glued to the last one if they are joint e.g. `=` and `=` to `==`
+    pub fn push_tree(&mut self, tree: TokenTree) {
+        let trees = Rc::make_mut(&mut self.0);
+        if let (Some(TokenTree::SingleToken(last, Spacing::Joint)), TokenTree::SingleToken(token, spacing)) =
+            (trees.last_mut(), &tree)
+        {
+            if let Some(glued) = last.glue(token) {
+                trees.pop();
+                trees.push(TokenTree::SingleToken(glued, *spacing));
+                return;
+            }
+        }
+        trees.push(tree);
+    }
+
+    /// appends all trees of `stream`, the first one can be glued like in [`TokenStream::push_tree`]
+    pub fn push_stream(&mut self, stream: TokenStream) {
+        let mut trees = stream.trees();
+        if let Some(first) = trees.next() {
+            self.push_tree(first.clone());
+        }
+        Rc::make_mut(&mut self.0).extend(trees.cloned());
+    }
+
+    pub fn concat(streams: Vec<TokenStream>) -> TokenStream {
+        let mut builder = TokenStreamBuilder::new();
+        for stream in streams {
+            builder.push(stream);
+        }
+        builder.build()
+    }
+
+    /// a new stream with `f` applied to every token, also the ones in groups
+    ///
+    /// delimiters of groups are not passed to `f`
+    pub fn map_tokens(&self, mut f: impl FnMut(Token) -> Token) -> TokenStream {
+        self.map_tokens_dyn(&mut f)
+    }
+
+    fn map_tokens_dyn(&self, f: &mut dyn FnMut(Token) -> Token) -> TokenStream {
+        let trees = self
+            .trees()
+            .map(|tree| match tree {
+                &TokenTree::SingleToken(token, spacing) => TokenTree::SingleToken(f(token), spacing),
+                TokenTree::TokenGroup(dspan, spacing, delim, stream) => {
+                    TokenTree::TokenGroup(*dspan, *spacing, *delim, stream.map_tokens_dyn(f))
+                }
+            })
+            .collect();
+        TokenStream::new(trees)
+    }
+
+    /// compares the tokens and delimiters of both streams, but not their sources and spacing
+    pub fn eq_unspanned(&self, other: &TokenStream) -> bool {
+        self.len() == other.len() && self.trees().zip(other.trees()).all(|(a, b)| a.eq_unspanned(b))
+    }
+
+    /// the stream with the content of invisible groups in place of the groups
+    pub fn flattened(&self) -> TokenStream {
+        fn flatten(stream: &TokenStream, trees: &mut Vec<TokenTree>) {
+            for tree in stream.trees() {
+                match tree {
+                    TokenTree::TokenGroup(_, _, Delimiter::Invisible, stream) => flatten(stream, trees),
+                    TokenTree::TokenGroup(dspan, spacing, delim, stream) => {
+                        trees.push(TokenTree::TokenGroup(*dspan, *spacing, *delim, stream.flattened()))
+                    }
+                    TokenTree::SingleToken(..) => trees.push(tree.clone()),
+                }
+            }
+        }
+
+        let mut trees = Vec::new();
+        flatten(self, &mut trees);
+        TokenStream::new(trees)
+    }
+}
+
+impl FromIterator<TokenTree> for TokenStream {
+    fn from_iter<I: IntoIterator<Item = TokenTree>>(iter: I) -> TokenStream {
+        TokenStream::new(iter.into_iter().collect())
+    }
+}
+
+/// Collects multiple streams into one
+#[derive(Default)]
+pub struct TokenStreamBuilder(Vec<TokenStream>);
+
+impl TokenStreamBuilder {
+    pub fn new() -> TokenStreamBuilder {
+        TokenStreamBuilder(Vec::new())
+    }
+
+    pub fn push(&mut self, stream: TokenStream) {
+        self.0.push(stream);
+    }
+
+    pub fn build(self) -> TokenStream {
+        let mut streams = self.0.into_iter();
+        let Some(mut first) = streams.next() else {
+            return TokenStream::default();
+        };
+        for stream in streams {
+            first.push_stream(stream);
+        }
+        first
+    }
 }
 
-#[derive(Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub enum TokenTree {
     SingleToken(Token, Spacing),
     TokenGroup(GroupSrcIdx, GroupSpacing, Delimiter, TokenStream)
 }
 
-#[derive(Debug)]
+impl TokenTree {
+    /// compares the tokens and delimiters, but not their sources and spacing
+    pub fn eq_unspanned(&self, other: &TokenTree) -> bool {
+        match (self, other) {
+            (TokenTree::SingleToken(a, _), TokenTree::SingleToken(b, _)) => a.kind == b.kind,
+            (TokenTree::TokenGroup(_, _, delim_a, a), TokenTree::TokenGroup(_, _, delim_b, b)) => {
+                delim_a == delim_b && a.eq_unspanned(b)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Position in a single token stream, groups are returned as a whole
+#[derive(Clone)]
+pub struct TokenTreeCursor {
+    stream: TokenStream,
+    index: usize,
+}
+
+impl TokenTreeCursor {
+    pub fn new(stream: TokenStream) -> TokenTreeCursor {
+        TokenTreeCursor { stream, index: 0 }
+    }
+
+    /// the stream the cursor walks through
+    pub fn stream(&self) -> &TokenStream {
+        &self.stream
+    }
+
+    /// the tree `n` trees after the next one, `0` is the next one
+    pub fn look_ahead(&self, n: usize) -> Option<&TokenTree> {
+        self.stream.0.get(self.index + n)
+    }
+
+    /// moves to the end, the next call to `next` returns `None`
+    pub fn skip_rest(&mut self) {
+        self.index = self.stream.len();
+    }
+}
+
+impl Iterator for TokenTreeCursor {
+    type Item = TokenTree;
+
+    fn next(&mut self) -> Option<TokenTree> {
+        let tree = self.stream.0.get(self.index)?.clone();
+        self.index += 1;
+        Some(tree)
+    }
+}
+
+/// Walks through the tokens of a stream as if it was a flat list of tokens
+///
+/// Entering a group returns its opening delimiter and leaving it the closing one,
+/// including the [`Delimiter::Invisible`] ones. After the last token [`TokenKind::Eof`] is returned.
+#[derive(Clone)]
+pub struct TokenStreamCursor {
+    tree_cursor: TokenTreeCursor,
+    /// the cursors of the streams containing the groups the cursor is in
+    stack: Vec<(TokenTreeCursor, Delimiter, GroupSrcIdx, GroupSpacing)>,
+}
+
+impl TokenStreamCursor {
+    pub fn new(stream: TokenStream) -> TokenStreamCursor {
+        TokenStreamCursor { tree_cursor: TokenTreeCursor::new(stream), stack: Vec::new() }
+    }
+
+    pub fn next_token(&mut self) -> (Token, Spacing) {
+        if let Some(tree) = self.tree_cursor.next() {
+            match tree {
+                TokenTree::SingleToken(token, spacing) => (token, spacing),
+                TokenTree::TokenGroup(dspan, spacing, delim, stream) => {
+                    let parent = std::mem::replace(&mut self.tree_cursor, TokenTreeCursor::new(stream));
+                    self.stack.push((parent, delim, dspan, spacing));
+                    (Token::new(TokenKind::OpenDelim(delim), dspan.open()), spacing.open())
+                }
+            }
+        } else if let Some((parent, delim, dspan, spacing)) = self.stack.pop() {
+            self.tree_cursor = parent;
+            (Token::new(TokenKind::CloseDelim(delim), dspan.close()), spacing.close())
+        } else {
+            (Token::new(TokenKind::Eof, DUMMY_SRC_DATA), Spacing::Alone)
+        }
+    }
+
+    /// the token `n` tokens after the next one, `0` is the next one
+    pub fn look_ahead(&self, n: usize) -> Token {
+        let mut cursor = self.clone();
+        let mut token = cursor.next_token().0;
+        for _ in 0..n {
+            if token.kind == TokenKind::Eof {
+                break;
+            }
+            token = cursor.next_token().0;
+        }
+        token
+    }
+
+    /// the number of groups the cursor is in
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// the innermost group the cursor is in and its content
+    pub fn current_group(&self) -> Option<(Delimiter, GroupSrcIdx, &TokenStream)> {
+        let &(_, delim, dspan, _) = self.stack.last()?;
+        Some((delim, dspan, self.tree_cursor.stream()))
+    }
+
+    /// skips the rest of the current group, the next token is its closing delimiter
+    pub fn skip_group(&mut self) {
+        self.tree_cursor.skip_rest();
+    }
+}
+
+/// Whether a token is followed by whitespace
+///
+/// Printing a token followed by a space when it is `Alone` and without one otherwise
+/// (and a line break behind line comments) gives source text which is lexed to the same stream.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum Spacing {
+    /// followed by whitespace or the end of the file
     Alone,
+    /// directly followed by a punctuation token e.g. `=` in `=>`, which is not glued to it
     Joint,
+    /// directly followed by a token which is not punctuation e.g. `a` in `a.b`
     JointHidden,
 }
 
-#[derive(Debug)]
+/// The [`Spacing`] behind the opening and the closing delimiter of a group
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct GroupSpacing {
     open: Spacing,
     close: Spacing,
@@ -35,4 +289,116 @@ impl GroupSpacing {
     pub fn new(open: Spacing, close: Spacing) -> GroupSpacing {
         GroupSpacing{open, close}
     }
+
+    pub fn open(&self) -> Spacing {
+        self.open
+    }
+
+    pub fn close(&self) -> Spacing {
+        self.close
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use source_idx::{kw, BytePos, GroupSrcIdx, SrcData, Symbol, DUMMY_SRC_DATA};
+
+    use super::*;
+
+    fn token(kind: TokenKind, spacing: Spacing) -> TokenTree {
+        TokenTree::SingleToken(Token::new(kind, DUMMY_SRC_DATA), spacing)
+    }
+
+    fn ident(name: &str) -> TokenTree {
+        token(TokenKind::Ident(Symbol::get_or_store(name)), Spacing::Alone)
+    }
+
+    fn group(delim: Delimiter, trees: Vec<TokenTree>) -> TokenTree {
+        let dspan = GroupSrcIdx::from_pair(DUMMY_SRC_DATA, DUMMY_SRC_DATA);
+        TokenTree::TokenGroup(dspan, GroupSpacing::new(Spacing::Alone, Spacing::Alone), delim, TokenStream::new(trees))
+    }
+
+    #[test]
+    fn build_streams() {
+        let mut stream = TokenStream::new(vec![ident("a"), token(TokenKind::Eq, Spacing::Joint)]);
+        let shared = stream.clone();
+        // joint tokens are glued
+        stream.push_tree(token(TokenKind::Eq, Spacing::Alone));
+        assert_eq!(stream.len(), 2);
+        assert!(matches!(stream.0[1], TokenTree::SingleToken(Token { kind: TokenKind::EqEq, .. }, Spacing::Alone)));
+        // the clone is not changed
+        assert_eq!(shared.len(), 2);
+        assert!(matches!(shared.0[1], TokenTree::SingleToken(Token { kind: TokenKind::Eq, .. }, Spacing::Joint)));
+
+        let concat = TokenStream::concat(vec![shared.clone(), TokenStream::new(vec![ident("b")]), shared]);
+        // `=` is not glued to `b`
+        assert_eq!(concat.len(), 5);
+        let mut builder = TokenStreamBuilder::new();
+        builder.push(stream);
+        builder.push(TokenStream::new(vec![ident("b")]));
+        assert_eq!(builder.build().len(), 3);
+    }
+
+    #[test]
+    fn compare_and_map() {
+        let a = TokenStream::new(vec![ident("f"), group(Delimiter::Parenthesis, vec![ident("x")])]);
+        let b = a.map_tokens(|token| Token::new(token.kind, SrcData::with_root_ctxt(BytePos(1), BytePos(2))));
+        assert_ne!(a, b);
+        assert!(a.eq_unspanned(&b));
+
+        let c = a.map_tokens(|token| match token.kind {
+            TokenKind::Ident(_) => Token::new(TokenKind::Ident(kw::Underscore), token.src_data),
+            _ => token,
+        });
+        assert!(!a.eq_unspanned(&c));
+        let TokenTree::TokenGroup(.., inner) = &c.0[1] else { panic!("expected a group") };
+        assert!(inner.eq_unspanned(&TokenStream::new(vec![ident("_")])));
+    }
+
+    #[test]
+    fn flattened() {
+        let stream = TokenStream::new(vec![
+            group(Delimiter::Invisible, vec![ident("a"), group(Delimiter::Invisible, vec![ident("b")])]),
+            group(Delimiter::Bracket, vec![group(Delimiter::Invisible, vec![ident("c")])]),
+        ]);
+        let expected = TokenStream::new(vec![ident("a"), ident("b"), group(Delimiter::Bracket, vec![ident("c")])]);
+        assert!(stream.flattened().eq_unspanned(&expected));
+    }
+
+    #[test]
+    fn cursor() {
+        let stream = TokenStream::new(vec![
+            ident("f"),
+            group(Delimiter::Parenthesis, vec![ident("x"), group(Delimiter::Bracket, vec![])]),
+            ident("g"),
+        ]);
+        let mut cursor = stream.cursor();
+        let kinds: Vec<_> = (0..8).map(|n| cursor.look_ahead(n).kind).collect();
+        let ident_kind = |name| TokenKind::Ident(Symbol::get_or_store(name));
+        assert_eq!(
+            kinds,
+            [
+                ident_kind("f"),
+                TokenKind::OpenDelim(Delimiter::Parenthesis),
+                ident_kind("x"),
+                TokenKind::OpenDelim(Delimiter::Bracket),
+                TokenKind::CloseDelim(Delimiter::Bracket),
+                TokenKind::CloseDelim(Delimiter::Parenthesis),
+                ident_kind("g"),
+                TokenKind::Eof,
+            ]
+        );
+
+        cursor.next_token();
+        cursor.next_token();
+        assert_eq!(cursor.depth(), 1);
+        let (delim, _, content) = cursor.current_group().unwrap();
+        assert_eq!((delim, content.len()), (Delimiter::Parenthesis, 2));
+        cursor.skip_group();
+        assert_eq!(cursor.next_token().0.kind, TokenKind::CloseDelim(Delimiter::Parenthesis));
+        assert_eq!(cursor.depth(), 0);
+        assert_eq!(cursor.next_token().0.kind, ident_kind("g"));
+        assert_eq!(cursor.next_token().0.kind, TokenKind::Eof);
+        assert_eq!(cursor.look_ahead(3).kind, TokenKind::Eof);
+    }
 }
\ No newline at end of file
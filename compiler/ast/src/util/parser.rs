@@ -0,0 +1,99 @@
+//! Precedence and associativity of the infix operators
+
+use crate::{
+    ast::{BinOpKind, RangeLimits},
+    token::{BinOpToken, Token, TokenKind},
+};
+
+/// An operator between two expressions
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AssocOp {
+    /// `+`, `==`, `&&`, ...
+    Binary(BinOpKind),
+    /// `=`
+    Assign,
+    /// `+=`, `<<=`, ...
+    AssignOp(BinOpKind),
+    /// `..` or `..=`
+    Range(RangeLimits),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Fixity {
+    /// `a - b - c` is `(a - b) - c`
+    Left,
+    /// `a = b = c` is `a = (b = c)`
+    Right,
+    /// `a == b == c` is an error
+    None,
+}
+
+/// precedence of prefix operators like `-a`, they bind stronger than every infix operator
+pub const PREC_PREFIX: u8 = 14;
+
+impl AssocOp {
+    pub fn from_token(token: &Token) -> Option<AssocOp> {
+        use AssocOp::*;
+        Some(match token.kind {
+            TokenKind::BinOp(op) => Binary(bin_op_kind(op)),
+            TokenKind::BinOpEq(op) => AssignOp(bin_op_kind(op)),
+            TokenKind::Eq => Assign,
+            TokenKind::EqEq => Binary(BinOpKind::Eq),
+            TokenKind::Ne => Binary(BinOpKind::Ne),
+            TokenKind::Lt => Binary(BinOpKind::Lt),
+            TokenKind::Le => Binary(BinOpKind::Le),
+            TokenKind::Gt => Binary(BinOpKind::Gt),
+            TokenKind::Ge => Binary(BinOpKind::Ge),
+            TokenKind::AndAnd => Binary(BinOpKind::And),
+            TokenKind::OrOr => Binary(BinOpKind::Or),
+            TokenKind::DotDot => Range(RangeLimits::HalfOpen),
+            TokenKind::DotDotEq => Range(RangeLimits::Closed),
+            _ => return None,
+        })
+    }
+
+    /// how strong the operator binds, higher binds stronger
+    pub fn precedence(self) -> u8 {
+        use BinOpKind::*;
+        match self {
+            AssocOp::Binary(kind) => match kind {
+                Mul | Div | Rem => 12,
+                Add | Sub => 11,
+                Shl | Shr => 10,
+                BitAnd => 9,
+                BitXor => 8,
+                BitOr => 7,
+                Eq | Lt | Le | Ne | Ge | Gt => 6,
+                And => 5,
+                Or => 4,
+            },
+            AssocOp::Range(_) => 3,
+            AssocOp::Assign | AssocOp::AssignOp(_) => 2,
+        }
+    }
+
+    pub fn fixity(self) -> Fixity {
+        match self {
+            AssocOp::Assign | AssocOp::AssignOp(_) => Fixity::Right,
+            AssocOp::Binary(kind) if kind.is_comparison() => Fixity::None,
+            AssocOp::Binary(_) => Fixity::Left,
+            AssocOp::Range(_) => Fixity::None,
+        }
+    }
+}
+
+/// the binary operator of `+` or `+=`
+pub fn bin_op_kind(op: BinOpToken) -> BinOpKind {
+    match op {
+        BinOpToken::Plus => BinOpKind::Add,
+        BinOpToken::Minus => BinOpKind::Sub,
+        BinOpToken::Star => BinOpKind::Mul,
+        BinOpToken::Slash => BinOpKind::Div,
+        BinOpToken::Percent => BinOpKind::Rem,
+        BinOpToken::Peak => BinOpKind::BitXor,
+        BinOpToken::And => BinOpKind::BitAnd,
+        BinOpToken::Or => BinOpKind::BitOr,
+        BinOpToken::Shl => BinOpKind::Shl,
+        BinOpToken::Shr => BinOpKind::Shr,
+    }
+}
@@ -0,0 +1,225 @@
+//! Read only traversal of the AST
+//!
+//! Every `visit_*` method of a [`Visitor`] calls the matching `walk_*` function by default,
+//! which visits the children of the node. Overwrite a method to look at a node and call the
+//! `walk_*` function in it to keep visiting its children.
+
+use crate::ast::*;
+
+pub trait Visitor<'ast>: Sized {
+    fn visit_crate(&mut self, krate: &'ast Crate) {
+        walk_crate(self, krate)
+    }
+    fn visit_item(&mut self, item: &'ast Item) {
+        walk_item(self, item)
+    }
+    fn visit_fn(&mut self, func: &'ast Fn, item: &'ast Item) {
+        walk_fn(self, func, item)
+    }
+    fn visit_param(&mut self, param: &'ast Param) {
+        walk_param(self, param)
+    }
+    fn visit_fn_ret_ty(&mut self, ret_ty: &'ast FnRetTy) {
+        walk_fn_ret_ty(self, ret_ty)
+    }
+    fn visit_attribute(&mut self, _attr: &'ast Attribute) {}
+    fn visit_block(&mut self, block: &'ast Block) {
+        walk_block(self, block)
+    }
+    fn visit_stmt(&mut self, stmt: &'ast Stmt) {
+        walk_stmt(self, stmt)
+    }
+    fn visit_local(&mut self, local: &'ast Local) {
+        walk_local(self, local)
+    }
+    fn visit_pat(&mut self, pat: &'ast Pat) {
+        walk_pat(self, pat)
+    }
+    fn visit_ty(&mut self, ty: &'ast Ty) {
+        walk_ty(self, ty)
+    }
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        walk_expr(self, expr)
+    }
+    fn visit_call_arg(&mut self, arg: &'ast CallArg) {
+        walk_call_arg(self, arg)
+    }
+    fn visit_path(&mut self, path: &'ast Path) {
+        walk_path(self, path)
+    }
+    fn visit_mac_call(&mut self, _mac: &'ast MacCall) {}
+    fn visit_ident(&mut self, _ident: Ident) {}
+}
+
+pub fn walk_crate<'a, V: Visitor<'a>>(visitor: &mut V, krate: &'a Crate) {
+    krate.attrs.iter().for_each(|attr| visitor.visit_attribute(attr));
+    krate.items.iter().for_each(|item| visitor.visit_item(item));
+}
+
+pub fn walk_item<'a, V: Visitor<'a>>(visitor: &mut V, item: &'a Item) {
+    item.attrs.iter().for_each(|attr| visitor.visit_attribute(attr));
+    visitor.visit_ident(item.ident);
+    match &item.kind {
+        ItemKind::Fn(func) => visitor.visit_fn(func, item),
+        ItemKind::Mod(ModKind::Loaded(items)) => items.iter().for_each(|item| visitor.visit_item(item)),
+        ItemKind::Mod(ModKind::Unloaded) | ItemKind::Err => {}
+    }
+}
+
+pub fn walk_fn<'a, V: Visitor<'a>>(visitor: &mut V, func: &'a Fn, _item: &'a Item) {
+    func.sig.inputs.iter().for_each(|param| visitor.visit_param(param));
+    visitor.visit_fn_ret_ty(&func.sig.output);
+    if let Some(body) = &func.body {
+        visitor.visit_block(body);
+    }
+}
+
+pub fn walk_param<'a, V: Visitor<'a>>(visitor: &mut V, param: &'a Param) {
+    visitor.visit_ident(param.ident);
+    visitor.visit_ty(&param.ty);
+    if let Some(default) = &param.default {
+        visitor.visit_expr(default);
+    }
+}
+
+pub fn walk_fn_ret_ty<'a, V: Visitor<'a>>(visitor: &mut V, ret_ty: &'a FnRetTy) {
+    match ret_ty {
+        FnRetTy::Default(_) | FnRetTy::Never(_) => {}
+        FnRetTy::Ty(ty) | FnRetTy::Option { ty, .. } => visitor.visit_ty(ty),
+        FnRetTy::Result { ok, err, .. } => {
+            if let Some(ok) = ok {
+                visitor.visit_ty(ok);
+            }
+            visitor.visit_ty(err);
+        }
+    }
+}
+
+pub fn walk_block<'a, V: Visitor<'a>>(visitor: &mut V, block: &'a Block) {
+    block.stmts.iter().for_each(|stmt| visitor.visit_stmt(stmt));
+}
+
+pub fn walk_stmt<'a, V: Visitor<'a>>(visitor: &mut V, stmt: &'a Stmt) {
+    match &stmt.kind {
+        StmtKind::Let(local) => visitor.visit_local(local),
+        StmtKind::Item(item) => visitor.visit_item(item),
+        StmtKind::Expr(expr) | StmtKind::Semi(expr) => visitor.visit_expr(expr),
+        StmtKind::Empty => {}
+    }
+}
+
+pub fn walk_local<'a, V: Visitor<'a>>(visitor: &mut V, local: &'a Local) {
+    visitor.visit_pat(&local.pat);
+    if let Some(ty) = &local.ty {
+        visitor.visit_ty(ty);
+    }
+    if let Some(init) = &local.init {
+        visitor.visit_expr(init);
+    }
+}
+
+pub fn walk_pat<'a, V: Visitor<'a>>(visitor: &mut V, pat: &'a Pat) {
+    match &pat.kind {
+        PatKind::Wild | PatKind::Rest => {}
+        PatKind::Ident(_, ident) => visitor.visit_ident(*ident),
+        PatKind::Tuple(pats) => pats.iter().for_each(|pat| visitor.visit_pat(pat)),
+        PatKind::Ref(pat, _) => visitor.visit_pat(pat),
+        PatKind::Lit(expr) => visitor.visit_expr(expr),
+        PatKind::Range(start, end, _) => {
+            start.iter().chain(end).for_each(|expr| visitor.visit_expr(expr));
+        }
+        PatKind::Path(path) => visitor.visit_path(path),
+    }
+}
+
+pub fn walk_ty<'a, V: Visitor<'a>>(visitor: &mut V, ty: &'a Ty) {
+    match &ty.kind {
+        TyKind::Path(path) => visitor.visit_path(path),
+        TyKind::Ref(_, ty) | TyKind::Slice(ty) => visitor.visit_ty(ty),
+        TyKind::Array(ty, len) => {
+            visitor.visit_ty(ty);
+            visitor.visit_expr(len);
+        }
+        TyKind::Tup(tys) => tys.iter().for_each(|ty| visitor.visit_ty(ty)),
+        TyKind::Never | TyKind::Infer => {}
+    }
+}
+
+pub fn walk_path<'a, V: Visitor<'a>>(visitor: &mut V, path: &'a Path) {
+    for segment in &path.segments {
+        visitor.visit_ident(segment.ident);
+        if let Some(args) = &segment.args {
+            args.args.iter().for_each(|ty| visitor.visit_ty(ty));
+        }
+    }
+}
+
+pub fn walk_call_arg<'a, V: Visitor<'a>>(visitor: &mut V, arg: &'a CallArg) {
+    match &arg.kind {
+        CallArgKind::Positional(expr) | CallArgKind::Spread(expr) => visitor.visit_expr(expr),
+        CallArgKind::Named(ident, expr) => {
+            visitor.visit_ident(*ident);
+            visitor.visit_expr(expr);
+        }
+    }
+}
+
+pub fn walk_expr<'a, V: Visitor<'a>>(visitor: &mut V, expr: &'a Expr) {
+    match &expr.kind {
+        ExprKind::Lit(_) | ExprKind::Continue | ExprKind::Err => {}
+        ExprKind::Path(path) => visitor.visit_path(path),
+        ExprKind::Paren(expr) => visitor.visit_expr(expr),
+        ExprKind::Tup(exprs) | ExprKind::Array(exprs) => exprs.iter().for_each(|expr| visitor.visit_expr(expr)),
+        ExprKind::Block(block) | ExprKind::Loop(block) => visitor.visit_block(block),
+        ExprKind::If(cond, then, els) => {
+            visitor.visit_expr(cond);
+            visitor.visit_block(then);
+            if let Some(els) = els {
+                visitor.visit_expr(els);
+            }
+        }
+        ExprKind::While(cond, body) => {
+            visitor.visit_expr(cond);
+            visitor.visit_block(body);
+        }
+        ExprKind::Closure(closure) => {
+            for param in &closure.params {
+                visitor.visit_pat(&param.pat);
+                if let Some(ty) = &param.ty {
+                    visitor.visit_ty(ty);
+                }
+            }
+            visitor.visit_expr(&closure.body);
+        }
+        ExprKind::Call(func, args) => {
+            visitor.visit_expr(func);
+            args.iter().for_each(|arg| visitor.visit_call_arg(arg));
+        }
+        ExprKind::MethodCall(call) => {
+            visitor.visit_expr(&call.receiver);
+            visitor.visit_ident(call.ident);
+            call.args.iter().for_each(|arg| visitor.visit_call_arg(arg));
+        }
+        ExprKind::Field(expr, ident) => {
+            visitor.visit_expr(expr);
+            visitor.visit_ident(*ident);
+        }
+        ExprKind::Binary(_, lhs, rhs)
+        | ExprKind::Assign(lhs, rhs, _)
+        | ExprKind::AssignOp(_, lhs, rhs)
+        | ExprKind::Index(lhs, rhs) => {
+            visitor.visit_expr(lhs);
+            visitor.visit_expr(rhs);
+        }
+        ExprKind::Unary(_, expr) | ExprKind::AddrOf(_, expr) | ExprKind::Try(expr) => visitor.visit_expr(expr),
+        ExprKind::Range(start, end, _) => {
+            start.iter().chain(end).for_each(|expr| visitor.visit_expr(expr));
+        }
+        ExprKind::Ret(expr) | ExprKind::Break(expr) => {
+            if let Some(expr) = expr {
+                visitor.visit_expr(expr);
+            }
+        }
+        ExprKind::MacCall(mac) => visitor.visit_mac_call(mac),
+    }
+}
@@ -0,0 +1,120 @@
+//! The attributes built into the compiler
+//!
+//! every attribute has a template of the forms it can be written in and the items it can be
+//! applied to, which the `check_attrs` pass checks. Other attributes are reported by the
+//! `unknown_attributes` lint.
+
+use source_idx::{sym, Symbol};
+
+/// What an attribute is applied to
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Target {
+    /// an inner attribute at the start of the root file
+    Crate,
+    Mod,
+    Fn,
+}
+
+impl Target {
+    pub fn descr(self) -> &'static str {
+        match self {
+            Target::Crate => "crate",
+            Target::Mod => "module",
+            Target::Fn => "function",
+        }
+    }
+
+    fn plural_descr(self) -> &'static str {
+        match self {
+            Target::Crate => "crates",
+            Target::Mod => "modules",
+            Target::Fn => "functions",
+        }
+    }
+}
+
+/// The forms an attribute can be written in
+#[derive(Debug)]
+pub struct AttributeTemplate {
+    /// `#[name]`
+    pub word: bool,
+    /// `#[name(list)]`, with a description of the list
+    pub list: Option<&'static str>,
+    /// `#[name = value]`, with a description of the value
+    pub name_value: Option<&'static str>,
+}
+
+#[derive(Debug)]
+pub struct BuiltinAttribute {
+    pub name: Symbol,
+    pub template: AttributeTemplate,
+    /// the items the attribute can be applied to, `derive` is for items which do not exist yet
+    pub targets: &'static [Target],
+}
+
+impl BuiltinAttribute {
+    /// the correct forms of the attribute, e.g. `#[inline]` and `#[inline(always|never)]`
+    pub fn forms(&self, inner: bool) -> Vec<String> {
+        let start = if inner { "#![" } else { "#[" };
+        let template = &self.template;
+        let word = template.word.then(|| format!("{}{}]", start, self.name));
+        let list = template.list.map(|list| format!("{}{}({})]", start, self.name, list));
+        let name_value = template.name_value.map(|value| format!("{}{} = {}]", start, self.name, value));
+        [word, list, name_value].into_iter().flatten().collect()
+    }
+
+    /// e.g. `functions and modules`, `None` if the attribute can not be applied to any item
+    pub fn targets_descr(&self) -> Option<String> {
+        let (last, rest) = self.targets.split_last()?;
+        let rest: Vec<_> = rest.iter().map(|target| target.plural_descr()).collect();
+        if rest.is_empty() {
+            Some(last.plural_descr().to_string())
+        } else {
+            Some(format!("{} and {}", rest.join(", "), last.plural_descr()))
+        }
+    }
+}
+
+const LINT_LEVEL_TEMPLATE: AttributeTemplate =
+    AttributeTemplate { word: false, list: Some("lint1, lint2, ..."), name_value: None };
+const ALL_TARGETS: &[Target] = &[Target::Crate, Target::Mod, Target::Fn];
+
+pub static BUILTIN_ATTRIBUTES: &[BuiltinAttribute] = &[
+    BuiltinAttribute {
+        name: sym::entry,
+        template: AttributeTemplate { word: true, list: None, name_value: None },
+        targets: &[Target::Fn],
+    },
+    BuiltinAttribute {
+        name: sym::inline,
+        template: AttributeTemplate { word: true, list: Some("always|never"), name_value: None },
+        targets: &[Target::Fn],
+    },
+    BuiltinAttribute {
+        name: sym::cfg,
+        template: AttributeTemplate { word: false, list: Some("predicate"), name_value: None },
+        targets: &[Target::Mod, Target::Fn],
+    },
+    BuiltinAttribute {
+        name: sym::derive,
+        template: AttributeTemplate { word: false, list: Some("Trait1, Trait2, ..."), name_value: None },
+        targets: &[],
+    },
+    BuiltinAttribute {
+        name: sym::test,
+        template: AttributeTemplate { word: true, list: None, name_value: None },
+        targets: &[Target::Fn],
+    },
+    BuiltinAttribute { name: sym::allow, template: LINT_LEVEL_TEMPLATE, targets: ALL_TARGETS },
+    BuiltinAttribute { name: sym::warn, template: LINT_LEVEL_TEMPLATE, targets: ALL_TARGETS },
+    BuiltinAttribute { name: sym::deny, template: LINT_LEVEL_TEMPLATE, targets: ALL_TARGETS },
+    BuiltinAttribute {
+        name: sym::doc,
+        template: AttributeTemplate { word: false, list: Some("hidden"), name_value: Some("\"text\"") },
+        targets: ALL_TARGETS,
+    },
+];
+
+pub fn find_builtin_attr(name: Symbol) -> Option<&'static BuiltinAttribute> {
+    BUILTIN_ATTRIBUTES.iter().find(|attr| attr.name == name)
+}
@@ -0,0 +1,285 @@
+//! Maps the arguments of a call onto the parameters of the called function
+//!
+//! afterwards a call to a function of the crate has exactly one positional argument for every
+//! parameter, in the order of the parameters. `..(a, b)` passes the elements of the tuple as
+//! positional arguments and `name: value` passes the parameter `name`, named arguments have to
+//! follow the positional ones. Calls with `..def!()` are already expanded at this point.
+
+use ast::{
+    ast::{Block, CallArg, CallArgKind, Crate, Expr, ExprKind, Ident, Item, ItemKind, P},
+    mut_visit::{self, MutVisitor},
+};
+use diagnostics::{codes, Diagnostic};
+use parse::ParseSess;
+use source_idx::SrcData;
+
+use crate::{
+    err_expr,
+    resolve::{FnInfo, Resolver},
+};
+
+pub(crate) fn resolve_call_args(psess: &ParseSess, krate: &mut Crate) {
+    let mut resolver = CallArgs { psess, resolver: Resolver::new(krate) };
+    resolver.visit_crate(krate);
+}
+
+struct CallArgs<'a> {
+    psess: &'a ParseSess,
+    resolver: Resolver,
+}
+
+/// A positional argument, either written or an element of a spread tuple
+struct Positional {
+    expr: P<Expr>,
+    src_data: SrcData,
+    spread: Option<Spread>,
+}
+
+/// A tuple spread into the arguments
+#[derive(Clone, Copy, PartialEq)]
+struct Spread {
+    src_data: SrcData,
+    len: usize,
+    /// the number of positional arguments in front of the tuple
+    start: usize,
+}
+
+impl MutVisitor for CallArgs<'_> {
+    fn visit_item(&mut self, item: &mut Item) {
+        if let ItemKind::Mod(_) = item.kind {
+            self.resolver.enter_module(item.ident.name);
+            mut_visit::walk_item(self, item);
+            self.resolver.exit_module();
+        } else {
+            mut_visit::walk_item(self, item);
+        }
+    }
+
+    fn visit_block(&mut self, block: &mut Block) {
+        self.resolver.enter_block(block);
+        mut_visit::walk_block(self, block);
+        self.resolver.exit_block();
+    }
+
+    fn visit_expr(&mut self, expr: &mut Expr) {
+        mut_visit::walk_expr(self, expr);
+        match &mut expr.kind {
+            ExprKind::Call(func, args) => self.resolve_call(func, args, expr.src_data),
+            ExprKind::MethodCall(call) => {
+                if let Some(arg) = call.args.iter().find(|arg| needs_params(arg)) {
+                    self.psess.dcx.emit(
+                        Diagnostic::error("methods can not be called with named or spread arguments")
+                            .with_code(codes::E0026)
+                            .with_primary(arg.src_data, "")
+                            .with_secondary(call.ident.src_data, "the parameters of this method are not known"),
+                    );
+                    self.replace_with_err(&mut call.args);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl CallArgs<'_> {
+    fn resolve_call(&self, func: &Expr, args: &mut Vec<CallArg>, call_src: SrcData) {
+        let info = match &func.kind {
+            ExprKind::Path(path) => self.resolver.resolve_fn(path),
+            _ => None,
+        };
+        let Some(info) = info else {
+            if let Some(arg) = args.iter().find(|arg| needs_params(arg)) {
+                self.psess.dcx.emit(
+                    Diagnostic::error("cannot find the function called with named or spread arguments")
+                        .with_code(codes::E0026)
+                        .with_primary(func.src_data, "not a function of this crate")
+                        .with_secondary(arg.src_data, "the parameters are needed for this argument"),
+                );
+                self.replace_with_err(args);
+            }
+            return;
+        };
+
+        let mut positional = Vec::new();
+        let mut named = Vec::new();
+        // the number of arguments is unknown if a spread argument can not be expanded
+        let mut unknown_len = false;
+        check_arg_order(self.psess, args);
+        for arg in args.drain(..) {
+            match arg.kind {
+                CallArgKind::Positional(expr) => {
+                    positional.push(Positional { expr, src_data: arg.src_data, spread: None });
+                }
+                CallArgKind::Spread(expr) => match expr.kind {
+                    ExprKind::Tup(elems) => {
+                        let spread = Some(Spread { src_data: arg.src_data, len: elems.len(), start: positional.len() });
+                        let elems = elems.into_iter().map(|expr| Positional { src_data: expr.src_data, expr, spread });
+                        positional.extend(elems);
+                    }
+                    ExprKind::Err => unknown_len = true,
+                    _ => {
+                        self.psess.dcx.emit(
+                            Diagnostic::error("only tuple expressions can be spread into the arguments")
+                                .with_code(codes::E0025)
+                                .with_primary(expr.src_data, "not a tuple expression")
+                                .with_note("the number of elements of other values is not known here"),
+                        );
+                        unknown_len = true;
+                    }
+                },
+                CallArgKind::Named(ident, expr) => named.push((ident, expr, arg.src_data)),
+            }
+        }
+
+        // the value and the location of the argument for each parameter
+        let mut values: ArgValues = info.params.iter().map(|_| None).collect();
+        let param_count = values.len();
+        let mut last_spread = None;
+        let mut extra = Vec::new();
+        for (idx, arg) in positional.into_iter().enumerate() {
+            if idx < param_count {
+                values[idx] = Some((arg.expr, arg.src_data));
+                last_spread = arg.spread;
+            } else {
+                extra.push(arg);
+            }
+        }
+        if let Some(first) = extra.first().filter(|_| !unknown_len) {
+            let diag = match first.spread {
+                // the arguments in front of the tuple did not already fill all parameters
+                Some(spread) if spread.start < param_count => {
+                    let left = param_count - spread.start;
+                    let label = format!(
+                        "{} elements, but only {} {} left",
+                        spread.len,
+                        left,
+                        plural(left, "parameter is", "parameters are")
+                    );
+                    // the arguments behind the tuple are reported on their own
+                    extra.iter().filter(|arg| arg.spread != Some(spread)).fold(
+                        Diagnostic::error("the spread tuple has more elements than parameters are left")
+                            .with_primary(spread.src_data, label),
+                        |diag, arg| diag.with_primary(arg.src_data, "unexpected argument"),
+                    )
+                }
+                _ => {
+                    let given = param_count + extra.len();
+                    extra.iter().fold(
+                        Diagnostic::error(format!(
+                            "function `{}` takes {} {} but {} were given",
+                            info.ident.name,
+                            param_count,
+                            plural(param_count, "argument", "arguments"),
+                            given,
+                        )),
+                        |diag, arg| diag.with_primary(arg.src_data, "unexpected argument"),
+                    )
+                }
+            };
+            self.psess
+                .dcx
+                .emit(diag.with_code(codes::E0022).with_secondary(info.ident.src_data, "function declared here"));
+        }
+
+        map_named_args(self.psess, &info, named, &mut values);
+
+        *args = info
+            .params
+            .iter()
+            .zip(values)
+            .map(|(param, value)| {
+                let (expr, src_data) = value.unwrap_or_else(|| {
+                    if !unknown_len {
+                        let message = format!("missing argument for parameter `{}`", param.ident.name);
+                        let mut diag = Diagnostic::error(message)
+                            .with_code(codes::E0024)
+                            .with_primary(call_src, format!("`{}` is not passed", param.ident.name))
+                            .with_secondary(param.ident.src_data, "parameter declared here");
+                        if let Some(spread) = last_spread {
+                            let elements = plural(spread.len, "element", "elements");
+                            let label = format!("the spread tuple has only {} {}", spread.len, elements);
+                            diag = diag.with_secondary(spread.src_data, label);
+                        }
+                        if param.default.is_some() {
+                            diag = diag.with_help("pass the default values of the parameters with `..def!()`");
+                        }
+                        self.psess.dcx.emit(diag);
+                    }
+                    (err_expr(self.psess, call_src), call_src)
+                });
+                CallArg { kind: CallArgKind::Positional(expr), src_data }
+            })
+            .collect();
+    }
+
+    /// the arguments can not be mapped to parameters, the errors are already reported
+    fn replace_with_err(&self, args: &mut [CallArg]) {
+        for arg in args.iter_mut().filter(|arg| needs_params(arg)) {
+            arg.kind = CallArgKind::Positional(err_expr(self.psess, arg.src_data));
+        }
+    }
+}
+
+/// the value and the location of the argument for each parameter
+pub(crate) type ArgValues = Vec<Option<(P<Expr>, SrcData)>>;
+
+/// reports the positional and spread arguments after the first named argument
+pub(crate) fn check_arg_order(psess: &ParseSess, args: &[CallArg]) {
+    let is_named = |arg: &&CallArg| matches!(arg.kind, CallArgKind::Named(..));
+    let Some(first_named) = args.iter().find(is_named) else { return };
+    for arg in args.iter().skip_while(|arg| !is_named(arg)).filter(|arg| !is_named(arg)) {
+        psess.dcx.emit(
+            Diagnostic::error("positional argument after named arguments")
+                .with_code(codes::E0023)
+                .with_primary(arg.src_data, "")
+                .with_secondary(first_named.src_data, "first named argument")
+                .with_help("pass the positional arguments first"),
+        );
+    }
+}
+
+/// passes every named argument to its parameter unless the parameter is unknown or already has a value
+pub(crate) fn map_named_args(
+    psess: &ParseSess,
+    info: &FnInfo,
+    named: Vec<(Ident, P<Expr>, SrcData)>,
+    values: &mut ArgValues,
+) {
+    for (ident, expr, src_data) in named {
+        match info.param_index(ident.name) {
+            None => psess.dcx.emit(
+                Diagnostic::error(format!("no parameter named `{}` in `{}`", ident.name, info.ident.name))
+                    .with_code(codes::E0020)
+                    .with_primary(ident.src_data, "unknown parameter")
+                    .with_secondary(info.ident.src_data, "function declared here"),
+            ),
+            Some(idx) => match &values[idx] {
+                Some((_, first)) => psess.dcx.emit(
+                    Diagnostic::error(format!("parameter `{}` is passed more than once", ident.name))
+                        .with_code(codes::E0021)
+                        .with_primary(src_data, "")
+                        .with_secondary(*first, "first passed here"),
+                ),
+                None => values[idx] = Some((expr, src_data)),
+            },
+        }
+    }
+}
+
+/// true for the arguments which can only be passed if the parameters are known,
+/// a spread `Err` is a `..def!()` which is already reported
+fn needs_params(arg: &CallArg) -> bool {
+    match &arg.kind {
+        CallArgKind::Positional(_) => false,
+        CallArgKind::Spread(expr) => !matches!(expr.kind, ExprKind::Err),
+        CallArgKind::Named(..) => true,
+    }
+}
+
+fn plural(count: usize, one: &'static str, many: &'static str) -> &'static str {
+    if count == 1 {
+        one
+    } else {
+        many
+    }
+}
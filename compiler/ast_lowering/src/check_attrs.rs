@@ -0,0 +1,203 @@
+//! Checks the attributes of the crate against the built-in attributes
+//!
+//! a built-in attribute has to be written in one of the forms of its template and applied to one
+//! of its targets. Other attributes are reported by the `unknown_attributes` lint, doc comments
+//! are always valid. The levels of the lint level attributes apply to the lints of the item they
+//! are applied to and of everything inside it, they take precedence over the command line.
+
+use std::collections::HashMap;
+
+use ast::{
+    ast::{AttrArgs, AttrItem, AttrKind, AttrStyle, Attribute, Crate, ExprKind, Item, ItemKind},
+    lit::LitValue,
+    token::{Delimiter, Lit, LitKind, Token, TokenKind},
+    tokenstream::{TokenStream, TokenTree},
+    visit::{self, Visitor},
+};
+use diagnostics::{codes, lint, Diagnostic, LintLevel};
+use parse::ParseSess;
+use source_idx::{sym, SrcData, Symbol};
+
+use crate::builtin_attrs::{find_builtin_attr, BuiltinAttribute, Target};
+
+pub(crate) fn check_attrs(psess: &ParseSess, krate: &Crate) {
+    let mut checker = AttrChecker { psess };
+    psess.dcx.push_lint_levels(lint_levels(&krate.attrs));
+    checker.check_attrs(&krate.attrs, Target::Crate, None);
+    visit::walk_crate(&mut checker, krate);
+    psess.dcx.pop_lint_levels();
+}
+
+struct AttrChecker<'a> {
+    psess: &'a ParseSess,
+}
+
+impl<'ast> Visitor<'ast> for AttrChecker<'_> {
+    fn visit_item(&mut self, item: &'ast Item) {
+        let target = match item.kind {
+            ItemKind::Fn(_) => Some(Target::Fn),
+            ItemKind::Mod(_) => Some(Target::Mod),
+            ItemKind::Err => None,
+        };
+        self.psess.dcx.push_lint_levels(lint_levels(&item.attrs));
+        if let Some(target) = target {
+            self.check_attrs(&item.attrs, target, Some(item));
+        }
+        visit::walk_item(self, item);
+        self.psess.dcx.pop_lint_levels();
+    }
+}
+
+impl AttrChecker<'_> {
+    /// `item` is `None` for the attributes of the crate
+    fn check_attrs(&self, attrs: &[Attribute], target: Target, item: Option<&Item>) {
+        for attr in attrs {
+            let AttrKind::Normal(attr_item) = &attr.kind else { continue };
+            let builtin = match &attr_item.path.segments[..] {
+                [segment] => find_builtin_attr(segment.ident.name),
+                _ => None,
+            };
+            let Some(builtin) = builtin else {
+                let names: Vec<_> = attr_item.path.segments.iter().map(|segment| segment.ident.name.as_str()).collect();
+                self.psess.dcx.emit_lint(
+                    &lint::UNKNOWN_ATTRIBUTES,
+                    Diagnostic::warning(format!("unknown attribute `{}`", names.join("::")))
+                        .with_primary(attr_item.path.src_data, ""),
+                );
+                continue;
+            };
+
+            if !builtin.targets.contains(&target) {
+                let mut diag = Diagnostic::error(format!(
+                    "`#[{}]` can not be applied to a {}",
+                    builtin.name,
+                    target.descr()
+                ))
+                .with_code(codes::E0032)
+                .with_primary(attr.src_data, "");
+                if let Some(item) = item {
+                    diag = diag.with_secondary(item.ident.src_data, format!("this is a {}", target.descr()));
+                }
+                if let Some(targets) = builtin.targets_descr() {
+                    diag = diag.with_note(format!("`#[{}]` can only be applied to {}", builtin.name, targets));
+                }
+                self.psess.dcx.emit(diag);
+            }
+            self.check_args(attr, attr_item, builtin);
+        }
+    }
+
+    /// reports an attribute which is not written in one of the forms of its template
+    fn check_args(&self, attr: &Attribute, attr_item: &AttrItem, builtin: &BuiltinAttribute) {
+        let template = &builtin.template;
+        let valid = match &attr_item.args {
+            AttrArgs::Empty => template.word,
+            AttrArgs::Delimited(args) if args.delim == Delimiter::Parenthesis && template.list.is_some() => {
+                match builtin.name {
+                    sym::inline => {
+                        matches!(&ident_list(&args.tokens).as_deref(), Some([(sym::always | sym::never, _)]))
+                    }
+                    sym::allow | sym::warn | sym::deny => match ident_list(&args.tokens) {
+                        Some(lints) => {
+                            self.check_lint_names(&lints);
+                            true
+                        }
+                        None => false,
+                    },
+                    sym::doc => matches!(&ident_list(&args.tokens).as_deref(), Some([(sym::hidden, _)])),
+                    sym::cfg => is_cfg_predicate(&args.tokens),
+                    sym::derive => ident_list(&args.tokens).is_some_and(|traits| !traits.is_empty()),
+                    name => unreachable!("`{}` has no list form", name),
+                }
+            }
+            AttrArgs::Delimited(_) => false,
+            AttrArgs::Eq(_, expr) => {
+                template.name_value.is_some() && matches!(&expr.kind, ExprKind::Lit(LitValue::Str(..)))
+            }
+        };
+        if !valid {
+            let forms: Vec<_> = builtin
+                .forms(attr.style == AttrStyle::Inner)
+                .into_iter()
+                .map(|form| format!("`{}`", form))
+                .collect();
+            let help = match &forms[..] {
+                [form] => format!("the attribute has to be written as {}", form),
+                [rest @ .., last] => format!("the attribute has to be written as {} or {}", rest.join(", "), last),
+                [] => unreachable!("every built-in attribute has a form"),
+            };
+            self.psess.dcx.emit(
+                Diagnostic::error(format!("malformed `{}` attribute input", builtin.name))
+                    .with_code(codes::E0031)
+                    .with_primary(attr.src_data, "")
+                    .with_help(help),
+            );
+        }
+    }
+
+    /// the lints of `#[allow(lint)]` and the other lint level attributes have to exist
+    fn check_lint_names(&self, lints: &[(Symbol, SrcData)]) {
+        for &(name, src_data) in lints {
+            if name.as_str() != lint::WARNINGS && lint::find_lint(name.as_str()).is_none() {
+                self.psess.dcx.emit(Diagnostic::warning(format!("unknown lint: `{}`", name)).with_primary(src_data, ""));
+            }
+        }
+    }
+}
+
+/// the levels of the well-formed lint level attributes, a later attribute overrides an earlier one
+fn lint_levels(attrs: &[Attribute]) -> HashMap<String, LintLevel> {
+    let mut levels = HashMap::new();
+    for attr in attrs {
+        let AttrKind::Normal(attr_item) = &attr.kind else { continue };
+        let ([segment], AttrArgs::Delimited(args)) = (&attr_item.path.segments[..], &attr_item.args) else {
+            continue;
+        };
+        let Some(level) = LintLevel::from_name(segment.ident.name.as_str()) else { continue };
+        if args.delim != Delimiter::Parenthesis {
+            continue;
+        }
+        for (name, _) in ident_list(&args.tokens).unwrap_or_default() {
+            levels.insert(name.as_str().to_owned(), level);
+        }
+    }
+    levels
+}
+
+/// the identifiers of a list like `a, b, c`, `None` if the tokens are not such a list
+fn ident_list(tokens: &TokenStream) -> Option<Vec<(Symbol, SrcData)>> {
+    let mut idents = Vec::new();
+    let mut trees = tokens.trees();
+    while let Some(tree) = trees.next() {
+        let TokenTree::SingleToken(Token { kind: TokenKind::Ident(name), src_data }, _) = tree else {
+            return None;
+        };
+        idents.push((*name, *src_data));
+        match trees.next() {
+            None => break,
+            Some(TokenTree::SingleToken(Token { kind: TokenKind::Comma, .. }, _)) => {}
+            Some(_) => return None,
+        }
+    }
+    Some(idents)
+}
+
+/// a single predicate of `#[cfg(..)]`: `name`, `name = "value"` or `name(predicates)`
+fn is_cfg_predicate(tokens: &TokenStream) -> bool {
+    let token = |tree: &TokenTree| match tree {
+        TokenTree::SingleToken(token, _) => Some(token.kind),
+        TokenTree::TokenGroup(..) => None,
+    };
+    match &tokens.0[..] {
+        [name] => matches!(token(name), Some(TokenKind::Ident(_))),
+        [name, eq, value] => {
+            matches!(token(name), Some(TokenKind::Ident(_)))
+                && token(eq) == Some(TokenKind::Eq)
+                && matches!(token(value), Some(TokenKind::Literal(Lit { kind: LitKind::Str, suffix: None, .. })))
+        }
+        [name, TokenTree::TokenGroup(_, _, Delimiter::Parenthesis, _)] => {
+            matches!(token(name), Some(TokenKind::Ident(_)))
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,315 @@
+//! Expands `..def!()` in the arguments of a function call
+//!
+//! `def!()` stands for the default values of all parameters of the called function, the
+//! parameters without a default value are left for the other arguments of the call. For
+//! `fn f(a: u8 = 1, b: &str, c: u8 = 0)` the call `f("b", c: 2, ..def!())` becomes `f(1, "b", 2)`:
+//! a named argument replaces the default value of its parameter and the positional arguments
+//! are passed to the parameters without a default value in order.
+//!
+//! the copied default values have to mean the same as at the declaration of the called function:
+//! their paths to functions are rewritten to start at `crate` and their own `..def!()`s are
+//! expanded as well.
+
+use std::rc::Rc;
+
+use ast::{
+    ast::{Block, CallArg, CallArgKind, Crate, Expr, ExprKind, Ident, Item, ItemKind, MacCall, PathSegment, P},
+    mut_visit::{self, MutVisitor},
+};
+use diagnostics::{codes, Applicability, Diagnostic};
+use parse::ParseSess;
+use source_idx::{kw, sym, SrcData};
+
+use crate::{
+    call_args::{check_arg_order, map_named_args, ArgValues},
+    copy_expr, err_expr,
+    resolve::{FnInfo, ParamInfo, Resolver},
+};
+
+pub(crate) fn expand_default_args(psess: &ParseSess, krate: &mut Crate) {
+    let mut expander = DefaultArgs { psess, resolver: Resolver::new(krate), copying: Vec::new() };
+    expander.visit_crate(krate);
+}
+
+struct DefaultArgs<'a> {
+    psess: &'a ParseSess,
+    resolver: Resolver,
+    /// the functions whose default values are currently copied, the innermost one is last
+    copying: Vec<Rc<FnInfo>>,
+}
+
+impl MutVisitor for DefaultArgs<'_> {
+    fn visit_item(&mut self, item: &mut Item) {
+        if let ItemKind::Mod(_) = item.kind {
+            self.resolver.enter_module(item.ident.name);
+            mut_visit::walk_item(self, item);
+            self.resolver.exit_module();
+        } else {
+            mut_visit::walk_item(self, item);
+        }
+    }
+
+    fn visit_block(&mut self, block: &mut Block) {
+        self.resolver.enter_block(block);
+        mut_visit::walk_block(self, block);
+        self.resolver.exit_block();
+    }
+
+    fn visit_expr(&mut self, expr: &mut Expr) {
+        match &mut expr.kind {
+            ExprKind::Call(func, args) => {
+                self.visit_id(&mut expr.id);
+                self.visit_expr(func);
+                self.visit_args(args);
+                self.expand_call(func, args);
+            }
+            ExprKind::MethodCall(call) => {
+                self.visit_id(&mut expr.id);
+                self.visit_expr(&mut call.receiver);
+                self.visit_args(&mut call.args);
+                if let Some(arg) = call.args.iter_mut().find(|arg| def_arg(arg).is_some()) {
+                    self.psess.dcx.emit(
+                        Diagnostic::error("`..def!()` can not be used in method calls")
+                            .with_code(codes::E0019)
+                            .with_primary(arg.src_data, "")
+                            .with_secondary(call.ident.src_data, "the parameters of this method are not known"),
+                    );
+                    self.replace_with_err(arg);
+                }
+            }
+            ExprKind::MacCall(mac) => {
+                if let Some(diag) = misplaced_macro(mac) {
+                    self.psess.dcx.emit(diag.with_primary(expr.src_data, ""));
+                    expr.kind = ExprKind::Err;
+                }
+            }
+            _ => mut_visit::walk_expr(self, expr),
+        }
+    }
+}
+
+impl DefaultArgs<'_> {
+    /// visits all arguments except `def!()`, which is expanded with the call
+    fn visit_args(&mut self, args: &mut [CallArg]) {
+        for arg in args.iter_mut().filter(|arg| def_arg(arg).is_none()) {
+            self.visit_call_arg(arg);
+        }
+    }
+
+    fn expand_call(&mut self, func: &Expr, args: &mut Vec<CallArg>) {
+        let def_indices: Vec<_> = (0..args.len()).filter(|&idx| def_arg(&args[idx]).is_some()).collect();
+        let Some(&def_idx) = def_indices.last() else { return };
+        let def_src = args[def_idx].src_data;
+
+        let mut valid = true;
+        for &idx in &def_indices {
+            let arg = &args[idx];
+            let (mac, spread) = def_arg(arg).unwrap();
+            if !mac.args.tokens.is_empty() {
+                self.psess.dcx.emit(
+                    Diagnostic::error("`def!()` takes no arguments")
+                        .with_code(codes::E0017)
+                        .with_primary(arg.src_data, ""),
+                );
+            }
+            if !spread {
+                self.psess.dcx.emit(
+                    Diagnostic::error("`def!()` has to be spread into the arguments")
+                        .with_code(codes::E0017)
+                        .with_primary(arg.src_data, "")
+                        .with_suggestion(
+                            arg.src_data.shrink_to_lo(),
+                            "add `..` in front of it",
+                            "..",
+                            Applicability::MachineApplicable,
+                        ),
+                );
+            }
+            if idx != args.len() - 1 {
+                self.psess.dcx.emit(
+                    Diagnostic::error("`..def!()` has to be the last argument")
+                        .with_code(codes::E0017)
+                        .with_primary(arg.src_data, "")
+                        .with_secondary(args[idx + 1].src_data, "followed by this argument"),
+                );
+                valid = false;
+            }
+        }
+        for arg in args.iter().filter(|arg| matches!(arg.kind, CallArgKind::Spread(_)) && def_arg(arg).is_none()) {
+            self.psess.dcx.emit(
+                Diagnostic::error("`..def!()` can not be combined with other spread arguments")
+                    .with_code(codes::E0017)
+                    .with_primary(arg.src_data, "")
+                    .with_secondary(def_src, "`..def!()` is passed here"),
+            );
+            valid = false;
+        }
+
+        if !valid {
+            return self.replace_defs_with_err(args);
+        }
+
+        let info = match &func.kind {
+            ExprKind::Path(path) => self.resolver.resolve_fn(path),
+            _ => None,
+        };
+        let info = match info {
+            Some(info) => info,
+            None => {
+                self.psess.dcx.emit(
+                    Diagnostic::error("cannot find the function called with `..def!()`")
+                        .with_code(codes::E0019)
+                        .with_primary(func.src_data, "not a function of this crate")
+                        .with_note("`def!()` needs to know the parameters of the called function"),
+                );
+                return self.replace_defs_with_err(args);
+            }
+        };
+
+        let mut values: ArgValues = info.params.iter().map(|_| None).collect();
+        let mut positional = Vec::new();
+        let mut named = Vec::new();
+        args.pop();
+        check_arg_order(self.psess, args);
+        for arg in args.drain(..) {
+            match arg.kind {
+                CallArgKind::Positional(expr) => positional.push((expr, arg.src_data)),
+                CallArgKind::Named(ident, expr) => named.push((ident, expr, arg.src_data)),
+                CallArgKind::Spread(_) => unreachable!("other spread arguments are rejected above"),
+            }
+        }
+        // the named arguments are passed first, the positional ones fill the parameters which are left
+        map_named_args(self.psess, &info, named, &mut values);
+
+        let mut positional = positional.into_iter();
+        for (param, value) in info.params.iter().zip(&mut values) {
+            if param.default.is_none() && value.is_none() {
+                *value = positional.next();
+            }
+        }
+        if let Some((_, src_data)) = positional.next() {
+            self.psess.dcx.emit(
+                Diagnostic::error("too many arguments in front of `..def!()`")
+                    .with_code(codes::E0022)
+                    .with_primary(src_data, "no parameter without a default value is left for this argument")
+                    .with_note("the positional arguments are passed to the parameters without a default value"),
+            );
+        }
+
+        *args = info
+            .params
+            .iter()
+            .zip(values)
+            .map(|(param, value)| {
+                let (expr, src_data) = match (value, &param.default) {
+                    (Some(value), _) => value,
+                    (None, Some(default)) => (self.copy_default(&info, param, default, def_src), def_src),
+                    (None, None) => {
+                        self.psess.dcx.emit(
+                            Diagnostic::error(format!("missing argument for parameter `{}`", param.ident.name))
+                                .with_code(codes::E0018)
+                                .with_primary(def_src, format!("`{}` has no default value", param.ident.name))
+                                .with_secondary(param.ident.src_data, "parameter declared here")
+                                .with_help(format!("pass `{}` in front of `..def!()`", param.ident.name)),
+                        );
+                        (err_expr(self.psess, def_src), def_src)
+                    }
+                };
+                CallArg { kind: CallArgKind::Positional(expr), src_data }
+            })
+            .collect();
+    }
+
+    /// a copy of the default value of `param` of `callee`, which is passed by the `..def!()` at `def_src`
+    fn copy_default(&mut self, callee: &Rc<FnInfo>, param: &ParamInfo, default: &Expr, def_src: SrcData) -> P<Expr> {
+        if self.copying.iter().any(|info| Rc::ptr_eq(info, callee)) {
+            self.psess.dcx.emit(
+                Diagnostic::error(format!("the default value of parameter `{}` uses itself", param.ident.name))
+                    .with_code(codes::E0033)
+                    .with_primary(def_src, "")
+                    .with_secondary(param.ident.src_data, "parameter declared here")
+                    .with_note("`..def!()` in a default value copies the default values of the called function"),
+            );
+            return err_expr(self.psess, def_src);
+        }
+        let mut copy = copy_expr(self.psess, default);
+        AbsolutePaths { psess: self.psess, resolver: &self.resolver, callee }.visit_expr(&mut copy);
+        self.copying.push(callee.clone());
+        self.visit_expr(&mut copy);
+        self.copying.pop();
+        copy
+    }
+
+    /// the call can not be expanded, the errors are already reported
+    fn replace_defs_with_err(&self, args: &mut [CallArg]) {
+        for arg in args.iter_mut().filter(|arg| def_arg(arg).is_some()) {
+            self.replace_with_err(arg);
+        }
+    }
+
+    fn replace_with_err(&self, arg: &mut CallArg) {
+        arg.kind = CallArgKind::Spread(err_expr(self.psess, arg.src_data));
+    }
+}
+
+/// Rewrites the paths to functions in a copied default value of `callee` to start at `crate`,
+/// so that they refer to the same functions at the call
+struct AbsolutePaths<'a> {
+    psess: &'a ParseSess,
+    resolver: &'a Resolver,
+    callee: &'a FnInfo,
+}
+
+impl MutVisitor for AbsolutePaths<'_> {
+    fn visit_expr(&mut self, expr: &mut Expr) {
+        if let ExprKind::Path(path) = &mut expr.kind {
+            // functions declared in blocks can not be written with an absolute path,
+            // they are visible at the call anyway
+            if let Some(module) = self.resolver.fn_module(self.callee, path) {
+                let name = path.segments.pop().expect("a path has at least one segment");
+                let src_data = path.src_data;
+                let segment = |name| {
+                    PathSegment { ident: Ident { name, src_data }, id: self.psess.next_node_id(), args: None }
+                };
+                let modules = [kw::Crate].into_iter().chain(module).map(segment);
+                path.segments = modules.chain([name]).collect();
+            }
+        }
+        mut_visit::walk_expr(self, expr);
+    }
+}
+
+/// the `def!()` of `..def!()` or `def!()` and whether it is spread
+fn def_arg(arg: &CallArg) -> Option<(&MacCall, bool)> {
+    let (expr, spread) = match &arg.kind {
+        CallArgKind::Spread(expr) => (expr, true),
+        CallArgKind::Positional(expr) => (expr, false),
+        CallArgKind::Named(..) => return None,
+    };
+    match &expr.kind {
+        ExprKind::MacCall(mac) if mac.path.is_ident(sym::def) => Some((mac, spread)),
+        _ => None,
+    }
+}
+
+/// the error for a `def!()` which is not an argument or any `explicit_param!()`
+///
+/// `explicit_param!()` only stands for the parameters without a default value in the
+/// expansion of `def!()`, it is never written
+fn misplaced_macro(mac: &MacCall) -> Option<Diagnostic> {
+    if mac.path.is_ident(sym::def) {
+        Some(
+            Diagnostic::error("`def!()` can only be used in the arguments of a function call")
+                .with_code(codes::E0017)
+                .with_help("pass the default values with `f(..def!())`"),
+        )
+    } else if mac.path.is_ident(sym::explicit_param) {
+        Some(
+            Diagnostic::error("`explicit_param!()` can not be written")
+                .with_code(codes::E0017)
+                .with_note("it only stands for the parameters without a default value in the expansion of `def!()`"),
+        )
+    } else {
+        None
+    }
+}
@@ -0,0 +1,103 @@
+//! Finds the entry point of a binary
+//!
+//! the entry point is the function marked with `#[entry]`. Without such a function it is the
+//! function called `main` in the root module of the crate, a `main` in another module is an
+//! ordinary function.
+
+use std::mem;
+
+use ast::{
+    ast::{Attribute, Crate, Ident, Item, ItemKind, NodeId},
+    visit::{self, Visitor},
+};
+use diagnostics::{codes, Diagnostic};
+use parse::ParseSess;
+use source_idx::sym;
+
+/// The function which is called when the binary is run
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct EntryFn {
+    pub id: NodeId,
+    pub ident: Ident,
+    /// true if the function is marked with `#[entry]`, false for an implicit `main`
+    pub marked: bool,
+}
+
+/// the entry point of the crate, `None` if there is no valid one and the error is reported
+///
+/// only binaries need an entry point, this is not called for libraries
+pub fn entry_fn(psess: &ParseSess, krate: &Crate) -> Option<EntryFn> {
+    let mut collector = EntryCollector { in_root: true, marked: Vec::new(), root_main: None, other_mains: Vec::new() };
+    collector.visit_crate(krate);
+
+    let mut valid = true;
+    if let [(first, first_attr, _), rest @ ..] = &collector.marked[..] {
+        for (item, attr, _) in rest {
+            psess.dcx.emit(
+                Diagnostic::error("multiple entry points")
+                    .with_code(codes::E0028)
+                    .with_primary(attr.src_data, "")
+                    .with_secondary(item.ident.src_data, "second entry point")
+                    .with_secondary(first_attr.src_data, "first entry point")
+                    .with_note(format!("`{}` is already marked with `#[entry]`", first.ident.name)),
+            );
+            valid = false;
+        }
+    }
+    for (item, attr, _) in collector.marked.iter().filter(|(_, _, in_root)| !in_root) {
+        psess.dcx.emit(
+            Diagnostic::error("entry point in non-root module")
+                .with_code(codes::E0029)
+                .with_primary(attr.src_data, "")
+                .with_secondary(item.ident.src_data, "this function is not in the root module")
+                .with_help("move the function to the root module of the crate"),
+        );
+        valid = false;
+    }
+
+    match (&collector.marked[..], collector.root_main) {
+        (_, _) if !valid => None,
+        ([(item, _, _)], _) => Some(EntryFn { id: item.id, ident: item.ident, marked: true }),
+        (_, Some(item)) => Some(EntryFn { id: item.id, ident: item.ident, marked: false }),
+        (_, None) => {
+            let diag = Diagnostic::error("no entry point for binary")
+                .with_code(codes::E0030)
+                .with_primary(krate.src_data.shrink_to_hi(), "add a `main` function to the root module")
+                .with_help("mark a function with `#[entry]` or call it `main` in the root module of the crate");
+            let diag = collector.other_mains.iter().fold(diag, |diag, item| {
+                diag.with_secondary(item.ident.src_data, "this `main` is not in the root module")
+            });
+            psess.dcx.emit(diag);
+            None
+        }
+    }
+}
+
+struct EntryCollector<'ast> {
+    /// true while the items of the root module are visited
+    in_root: bool,
+    /// the functions marked with `#[entry]`, their attribute and whether they are in the root module
+    marked: Vec<(&'ast Item, &'ast Attribute, bool)>,
+    root_main: Option<&'ast Item>,
+    /// the functions called `main` in other modules or in blocks
+    other_mains: Vec<&'ast Item>,
+}
+
+impl<'ast> Visitor<'ast> for EntryCollector<'ast> {
+    fn visit_item(&mut self, item: &'ast Item) {
+        if let ItemKind::Fn(_) = item.kind {
+            if let Some(attr) = item.attrs.iter().find(|attr| attr.has_name(sym::entry)) {
+                self.marked.push((item, attr, self.in_root));
+            } else if item.ident.name == sym::main {
+                if self.in_root {
+                    self.root_main = Some(item);
+                } else {
+                    self.other_mains.push(item);
+                }
+            }
+        }
+        let in_root = mem::replace(&mut self.in_root, false);
+        visit::walk_item(self, item);
+        self.in_root = in_root;
+    }
+}
@@ -0,0 +1,25 @@
+//! The items of the standard library which the lowered sugar refers to
+
+use source_idx::{sym, Symbol};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LangItem {
+    /// `std::option::Option`, the type of `T!` and `T?`
+    Option,
+    /// `std::result::Result`, the type of `T!E` and `!E`
+    Result,
+}
+
+impl LangItem {
+    /// the path of the definition
+    pub fn path(self) -> [Symbol; 3] {
+        match self {
+            LangItem::Option => [sym::std, sym::option, sym::Option],
+            LangItem::Result => [sym::std, sym::result, sym::Result],
+        }
+    }
+
+    pub fn name(self) -> Symbol {
+        self.path()[2]
+    }
+}
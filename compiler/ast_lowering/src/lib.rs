@@ -0,0 +1,52 @@
+//! Passes which rewrite the AST after parsing
+//!
+//! the passes remove the sugar of the language, so later stages only see the plain constructs.
+//! Errors are emitted to the [`diagnostics::DiagCtxt`] of the session and nodes which can not be
+//! lowered are replaced by `Err` nodes.
+
+use ast::{
+    ast::{Crate, Expr, ExprKind, NodeId, P},
+    mut_visit::MutVisitor,
+};
+use parse::ParseSess;
+use source_idx::SrcData;
+
+pub mod builtin_attrs;
+mod call_args;
+mod check_attrs;
+mod default_args;
+pub mod entry;
+pub mod lang_items;
+pub mod resolve;
+mod ret_ty;
+#[cfg(test)]
+mod tests;
+
+/// runs all passes on the crate
+pub fn lower_crate(psess: &ParseSess, krate: &mut Crate) {
+    check_attrs::check_attrs(psess, krate);
+    default_args::expand_default_args(psess, krate);
+    call_args::resolve_call_args(psess, krate);
+    ret_ty::lower_ret_tys(psess, krate);
+}
+
+/// gives a node and all of its children new ids, used for copies of nodes
+struct FreshIds<'a>(&'a ParseSess);
+
+impl MutVisitor for FreshIds<'_> {
+    fn visit_id(&mut self, id: &mut NodeId) {
+        *id = self.0.next_node_id();
+    }
+}
+
+/// a copy of the expression with new ids
+fn copy_expr(psess: &ParseSess, expr: &Expr) -> P<Expr> {
+    let mut copy = Box::new(expr.clone());
+    FreshIds(psess).visit_expr(&mut copy);
+    copy
+}
+
+/// placeholder for an expression which could not be lowered, the error is already reported
+fn err_expr(psess: &ParseSess, src_data: SrcData) -> P<Expr> {
+    Box::new(Expr { id: psess.next_node_id(), kind: ExprKind::Err, src_data })
+}
@@ -0,0 +1,158 @@
+//! Finds the function a call refers to
+//!
+//! only functions declared in the crate are known. A single identifier is looked up in the
+//! enclosing blocks and then in the current module, longer paths start at the current module
+//! unless they start with `crate`, `self` or `super`.
+
+use std::{collections::HashMap, rc::Rc};
+
+use ast::ast::{Block, Crate, Expr, Fn, Ident, Item, ItemKind, ModKind, Path, StmtKind, P};
+use source_idx::{kw, Symbol};
+
+/// The signature of a function as far as its callers need to know it
+#[derive(Debug)]
+pub struct FnInfo {
+    pub ident: Ident,
+    pub params: Vec<ParamInfo>,
+    /// the module the function is declared in
+    module: Vec<Symbol>,
+    /// the number of enclosing blocks whose functions are visible from the declaration,
+    /// including the block the function is declared in
+    depth: usize,
+}
+
+#[derive(Debug)]
+pub struct ParamInfo {
+    pub ident: Ident,
+    pub default: Option<P<Expr>>,
+}
+
+impl FnInfo {
+    fn new(ident: Ident, func: &Fn, module: Vec<Symbol>, depth: usize) -> FnInfo {
+        let params = func
+            .sig
+            .inputs
+            .iter()
+            .map(|param| ParamInfo { ident: param.ident, default: param.default.clone() })
+            .collect();
+        FnInfo { ident, params, module, depth }
+    }
+
+    pub fn param_index(&self, name: Symbol) -> Option<usize> {
+        self.params.iter().position(|param| param.ident.name == name)
+    }
+}
+
+type Scope = HashMap<Symbol, Rc<FnInfo>>;
+
+/// Keeps track of the scopes while a pass walks the crate
+///
+/// the pass has to call the `enter_*` and `exit_*` methods for every module and block
+#[derive(Default)]
+pub struct Resolver {
+    /// the functions declared in each module, by the path of the module
+    modules: HashMap<Vec<Symbol>, Scope>,
+    module: Vec<Symbol>,
+    /// the functions declared in the enclosing blocks, the innermost block is last
+    blocks: Vec<Scope>,
+}
+
+impl Resolver {
+    pub fn new(krate: &Crate) -> Resolver {
+        let mut resolver = Resolver::default();
+        resolver.collect_module(Vec::new(), &krate.items);
+        resolver
+    }
+
+    fn collect_module(&mut self, path: Vec<Symbol>, items: &[P<Item>]) {
+        let mut scope = Scope::new();
+        for item in items {
+            match &item.kind {
+                ItemKind::Fn(func) => {
+                    scope.insert(item.ident.name, Rc::new(FnInfo::new(item.ident, func, path.clone(), 0)));
+                }
+                ItemKind::Mod(ModKind::Loaded(items)) => {
+                    let mut path = path.clone();
+                    path.push(item.ident.name);
+                    self.collect_module(path, items);
+                }
+                ItemKind::Mod(ModKind::Unloaded) | ItemKind::Err => {}
+            }
+        }
+        self.modules.insert(path, scope);
+    }
+
+    pub fn enter_module(&mut self, name: Symbol) {
+        self.module.push(name);
+    }
+
+    pub fn exit_module(&mut self) {
+        self.module.pop();
+    }
+
+    /// the functions of a block can be called from everywhere in the block
+    pub fn enter_block(&mut self, block: &Block) {
+        let depth = self.blocks.len() + 1;
+        let scope = block
+            .stmts
+            .iter()
+            .filter_map(|stmt| match &stmt.kind {
+                StmtKind::Item(item) => match &item.kind {
+                    ItemKind::Fn(func) => {
+                        let info = FnInfo::new(item.ident, func, self.module.clone(), depth);
+                        Some((item.ident.name, Rc::new(info)))
+                    }
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+        self.blocks.push(scope);
+    }
+
+    pub fn exit_block(&mut self) {
+        self.blocks.pop();
+    }
+
+    /// the function declared at `path`, `None` if it is not a function of the crate
+    pub fn resolve_fn(&self, path: &Path) -> Option<Rc<FnInfo>> {
+        self.lookup(&self.module, &self.blocks, path).map(|(info, _)| info)
+    }
+
+    /// the module of the function `path` refers to when it is written at the declaration of `callee`,
+    /// `None` if it is not a function of the crate or declared in a block
+    ///
+    /// `callee` has to be visible from the current scope
+    pub fn fn_module(&self, callee: &FnInfo, path: &Path) -> Option<Vec<Symbol>> {
+        let blocks = self.blocks.get(..callee.depth)?;
+        self.lookup(&callee.module, blocks, path)?.1
+    }
+
+    /// the function declared at `path` seen from `module` inside of `blocks`, together with the
+    /// module it is declared in if it is not declared in a block
+    fn lookup(&self, module: &[Symbol], blocks: &[Scope], path: &Path) -> Option<(Rc<FnInfo>, Option<Vec<Symbol>>)> {
+        let (name, modules) = path.segments.split_last()?;
+        let name = name.ident.name;
+        if modules.is_empty() {
+            if let Some(info) = blocks.iter().rev().find_map(|scope| scope.get(&name)) {
+                return Some((info.clone(), None));
+            }
+            let info = self.modules.get(module)?.get(&name)?.clone();
+            return Some((info, Some(module.to_vec())));
+        }
+
+        let mut module = module.to_vec();
+        for (idx, segment) in modules.iter().enumerate() {
+            match segment.ident.name {
+                kw::Crate if idx == 0 => module.clear(),
+                kw::SelfLower if idx == 0 => {}
+                kw::Super if modules[..idx].iter().all(|s| matches!(s.ident.name, kw::Super | kw::SelfLower)) => {
+                    module.pop()?;
+                }
+                name => module.push(name),
+            }
+        }
+        let info = self.modules.get(&module)?.get(&name)?.clone();
+        Some((info, Some(module)))
+    }
+}
@@ -0,0 +1,137 @@
+//! Lowers the sugar of return types and checks the uses of the `?` operator
+//!
+//! `T!` and `T?` become `std::option::Option<T>`, `T!E` becomes `std::result::Result<T, E>`,
+//! `!E` becomes `std::result::Result<(), E>` and a single `!` becomes the never type. The paths
+//! which are not written are located at the `!`, so errors about them point at it.
+//!
+//! `?` returns from the enclosing function, which has to return an `Option` or a `Result`. The
+//! paths are not resolved yet, so only the return types which can never be one of them are
+//! reported: `()`, `!`, tuples, references, slices and arrays.
+
+use std::mem;
+
+use ast::{
+    ast::{Crate, Expr, ExprKind, Fn, FnRetTy, GenericArgs, Ident, Param, Path, PathSegment, Ty, TyKind, P},
+    mut_visit::{self, MutVisitor},
+};
+use diagnostics::{codes, Diagnostic};
+use parse::ParseSess;
+use source_idx::{BytePos, SrcData};
+
+use crate::lang_items::LangItem;
+
+pub(crate) fn lower_ret_tys(psess: &ParseSess, krate: &mut Crate) {
+    let mut lowerer = RetTys { psess, scope: TryScope::Unknown };
+    lowerer.visit_crate(krate);
+}
+
+/// What a `?` returns from
+#[derive(Clone, Copy)]
+enum TryScope {
+    /// a function which can not return an `Option` or a `Result`, the location of the return type
+    /// and a label for it
+    Invalid(SrcData, &'static str),
+    /// a closure, the default value of a parameter or a function returning a path like
+    /// `io::Result<u8>`, which is only known after name resolution
+    Unknown,
+}
+
+struct RetTys<'a> {
+    psess: &'a ParseSess,
+    scope: TryScope,
+}
+
+impl MutVisitor for RetTys<'_> {
+    fn visit_fn(&mut self, func: &mut Fn) {
+        func.sig.inputs.iter_mut().for_each(|param| self.visit_param(param));
+        self.visit_fn_ret_ty(&mut func.sig.output);
+        let outer = mem::replace(&mut self.scope, try_scope(&func.sig.output));
+        if let Some(body) = &mut func.body {
+            self.visit_block(body);
+        }
+        self.scope = outer;
+    }
+
+    fn visit_fn_ret_ty(&mut self, ret_ty: &mut FnRetTy) {
+        mut_visit::walk_fn_ret_ty(self, ret_ty);
+        let src_data = ret_ty.src_data();
+        *ret_ty = match mem::replace(ret_ty, FnRetTy::Default(src_data)) {
+            FnRetTy::Never(bang) => FnRetTy::Ty(self.ty(TyKind::Never, bang)),
+            FnRetTy::Option { ty, bang } => FnRetTy::Ty(self.lang_item_ty(LangItem::Option, vec![ty], bang, src_data)),
+            FnRetTy::Result { ok, bang, err } => {
+                // `!E` returns nothing on success, the unit type is located at the `!`
+                let ok = ok.unwrap_or_else(|| self.ty(TyKind::Tup(Vec::new()), bang));
+                FnRetTy::Ty(self.lang_item_ty(LangItem::Result, vec![ok, err], bang, src_data))
+            }
+            ret_ty @ (FnRetTy::Default(_) | FnRetTy::Ty(_)) => ret_ty,
+        };
+    }
+
+    fn visit_param(&mut self, param: &mut Param) {
+        let outer = mem::replace(&mut self.scope, TryScope::Unknown);
+        mut_visit::walk_param(self, param);
+        self.scope = outer;
+    }
+
+    fn visit_expr(&mut self, expr: &mut Expr) {
+        match &expr.kind {
+            ExprKind::Closure(_) => {
+                let outer = mem::replace(&mut self.scope, TryScope::Unknown);
+                mut_visit::walk_expr(self, expr);
+                self.scope = outer;
+                return;
+            }
+            ExprKind::Try(_) => {
+                if let TryScope::Invalid(ret_src, label) = self.scope {
+                    self.psess.dcx.emit(
+                        Diagnostic::error(
+                            "the `?` operator can only be used in a function that returns `Option` or `Result`",
+                        )
+                        .with_code(codes::E0027)
+                        .with_primary(expr.src_data.with_lo(expr.src_data.hi() - BytePos(1)), "")
+                        .with_secondary(ret_src, label)
+                        .with_help("return `T!` for an `Option` or `T!E` for a `Result`"),
+                    );
+                }
+            }
+            _ => {}
+        }
+        mut_visit::walk_expr(self, expr);
+    }
+}
+
+impl RetTys<'_> {
+    fn ty(&self, kind: TyKind, src_data: SrcData) -> P<Ty> {
+        Box::new(Ty { id: self.psess.next_node_id(), kind, src_data })
+    }
+
+    /// `std::option::Option<args>` or `std::result::Result<args>`, the path is located at the `!`
+    fn lang_item_ty(&self, item: LangItem, args: Vec<P<Ty>>, bang: SrcData, src_data: SrcData) -> P<Ty> {
+        let [modules @ .., name] = item.path();
+        let segment = |name, args| {
+            let ident = Ident { name, src_data: bang };
+            PathSegment { ident, id: self.psess.next_node_id(), args }
+        };
+        let mut segments: Vec<_> = modules.into_iter().map(|name| segment(name, None)).collect();
+        segments.push(segment(name, Some(Box::new(GenericArgs { args, src_data }))));
+        let path = Path { segments, src_data: bang };
+        self.ty(TyKind::Path(path), src_data)
+    }
+}
+
+/// the scope of a `?` in a function with the lowered return type
+fn try_scope(ret_ty: &FnRetTy) -> TryScope {
+    match ret_ty {
+        FnRetTy::Default(src_data) => TryScope::Invalid(*src_data, "this function returns `()`"),
+        FnRetTy::Ty(ty) => match ty.kind {
+            TyKind::Path(_) | TyKind::Infer => TryScope::Unknown,
+            TyKind::Never => TryScope::Invalid(ty.src_data, "this function never returns"),
+            TyKind::Tup(..) | TyKind::Ref(..) | TyKind::Slice(_) | TyKind::Array(..) => {
+                TryScope::Invalid(ty.src_data, "this function should return `Option` or `Result`")
+            }
+        },
+        FnRetTy::Option { .. } | FnRetTy::Result { .. } | FnRetTy::Never(_) => {
+            unreachable!("the return type is lowered before the body")
+        }
+    }
+}
@@ -0,0 +1,542 @@
+use std::rc::Rc;
+
+use ast::{
+    ast::{CallArgKind, Crate, Expr, ExprKind, FnRetTy, ItemKind, NodeId, Ty, TyKind},
+    visit::{self, Visitor},
+};
+use diagnostics::{DiagCtxt, Diagnostic, Level, SilentEmitter};
+use parse::{parse_crate_from_file, ParseSess};
+use source_idx::{FileName, SourceMap};
+
+use crate::{entry, lower_crate};
+
+/// parses and lowers `src`, returns the crate, all emitted diagnostics and the source map
+fn lower(src: &str) -> (Crate, Vec<Diagnostic>, Rc<SourceMap>) {
+    let source_map = Rc::new(SourceMap::new());
+    let psess = ParseSess::with_dcx(DiagCtxt::new(Box::new(SilentEmitter)), source_map.clone());
+    let file = psess.source_map.new_source_file(FileName::Anon("test.sl".into()), src.into());
+    let mut krate = parse_crate_from_file(&psess, &file);
+    lower_crate(&psess, &mut krate);
+    (krate, psess.dcx.diagnostics(), source_map)
+}
+
+fn messages(diags: &[Diagnostic]) -> Vec<&str> {
+    diags.iter().map(|diag| diag.message.as_str()).collect()
+}
+
+/// the arguments of every call in the crate as source text, `_` for an argument which is not
+/// positional and `<err>` for an `Err` expression
+fn call_args(krate: &Crate, source_map: &SourceMap) -> Vec<Vec<String>> {
+    struct Calls<'a>(&'a SourceMap, Vec<Vec<String>>);
+
+    impl<'ast> Visitor<'ast> for Calls<'_> {
+        fn visit_expr(&mut self, expr: &'ast Expr) {
+            if let ExprKind::Call(_, args) = &expr.kind {
+                let args = args.iter().map(|arg| match &arg.kind {
+                    CallArgKind::Positional(expr) if matches!(expr.kind, ExprKind::Err) => "<err>".to_string(),
+                    CallArgKind::Positional(expr) => self.0.snippet(expr.src_data).unwrap(),
+                    CallArgKind::Named(..) | CallArgKind::Spread(_) => "_".to_string(),
+                });
+                self.1.push(args.collect());
+            }
+            visit::walk_expr(self, expr);
+        }
+    }
+
+    let mut calls = Calls(source_map, Vec::new());
+    calls.visit_crate(krate);
+    calls.1
+}
+
+#[test]
+fn expand_default_args() {
+    let (krate, diags, source_map) = lower(
+        r#"
+        fn another_function(factor: usize = 1, string: &str, offset: usize = 0) usize {}
+        fn main() {
+            another_function("Test123", ..def!());
+            another_function("Test123", offset: 2, ..def!());
+            another_function("Test123", factor: 3, ..def!());
+            another_function(1, "Test123", 0);
+        }
+        "#,
+    );
+    assert_eq!(diags, Vec::new());
+    assert_eq!(
+        call_args(&krate, &source_map),
+        [
+            ["1", "\"Test123\"", "0"],
+            ["1", "\"Test123\"", "2"],
+            ["3", "\"Test123\"", "0"],
+            ["1", "\"Test123\"", "0"],
+        ]
+    );
+
+    // the copied default values are new nodes
+    struct Ids(Vec<NodeId>);
+    impl<'ast> Visitor<'ast> for Ids {
+        fn visit_expr(&mut self, expr: &'ast Expr) {
+            self.0.push(expr.id);
+            visit::walk_expr(self, expr);
+        }
+    }
+    let mut ids = Ids(Vec::new());
+    ids.visit_crate(&krate);
+    let count = ids.0.len();
+    ids.0.sort();
+    ids.0.dedup();
+    assert_eq!(ids.0.len(), count);
+}
+
+#[test]
+fn resolve_callee() {
+    let (krate, diags, source_map) = lower(
+        "
+        mod m {
+            fn f(a: u8 = 1) {}
+            mod n { fn g() { super::f(..def!()); crate::m::f(..def!()) } }
+        }
+        fn f(b: u8 = 2) {}
+        fn main() {
+            f(..def!());
+            m::f(..def!());
+            self::m::f(..def!());
+            { fn f(c: u8 = 3) {} f(..def!()) }
+            unknown(..def!());
+        }
+        ",
+    );
+    assert_eq!(messages(&diags), ["cannot find the function called with `..def!()`"]);
+    assert_eq!(
+        call_args(&krate, &source_map),
+        [vec!["1"], vec!["1"], vec!["2"], vec!["1"], vec!["1"], vec!["3"], vec!["_"]]
+    );
+}
+
+#[test]
+fn copied_defaults() {
+    // the copies mean the same as the default values at the declaration of the called function
+    let (krate, diags, source_map) = lower(
+        "
+        mod m { fn helper(x: u8) u8 { x } fn f(a: u8 = helper(x: 1)) {} }
+        fn g(a: u8 = 1) u8 { a }
+        fn f(b: u8 = g(..def!())) {}
+        fn main() { m::f(..def!()); f(..def!()) }
+        ",
+    );
+    assert_eq!(diags, Vec::new());
+    assert_eq!(
+        call_args(&krate, &source_map),
+        [
+            vec!["1"],
+            vec!["1"],
+            vec!["helper(x: 1)"],
+            vec!["1"],
+            vec!["g(..def!())"],
+            vec!["1"],
+        ]
+    );
+
+    let (_, diags, _) = lower("fn f(a: u8 = f(..def!())) u8 {} fn main() { f(..def!()) }");
+    assert_eq!(
+        messages(&diags),
+        ["the default value of parameter `a` uses itself", "the default value of parameter `a` uses itself"]
+    );
+}
+
+#[test]
+fn missing_explicit_params() {
+    let (krate, diags, source_map) = lower("fn f(a: u8, b: u8 = 1, c: u8) {} fn g() { f(..def!()); f(4, ..def!()) }");
+    assert_eq!(
+        messages(&diags),
+        [
+            "missing argument for parameter `a`",
+            "missing argument for parameter `c`",
+            "missing argument for parameter `c`",
+        ]
+    );
+    // one error for every parameter, pointing at `..def!()`
+    let def_src = diags[0].primary_src_data().unwrap();
+    assert_eq!(source_map.snippet(def_src).as_deref(), Some("..def!()"));
+    assert_eq!(call_args(&krate, &source_map), [["<err>", "1", "<err>"], ["4", "1", "<err>"]]);
+}
+
+#[test]
+fn invalid_arguments() {
+    let (_, diags, _) = lower(
+        "fn f(a: u8, b: u8 = 1) {}
+        fn g() { f(1, 2, ..def!()); f(c: 1, ..def!()); f(b: 1, b: 2, ..def!()); f(b: 1, 2, ..def!()) }",
+    );
+    assert_eq!(
+        messages(&diags),
+        [
+            "too many arguments in front of `..def!()`",
+            "no parameter named `c` in `f`",
+            "missing argument for parameter `a`",
+            "parameter `b` is passed more than once",
+            "missing argument for parameter `a`",
+            "positional argument after named arguments",
+        ]
+    );
+}
+
+#[test]
+fn misplaced_def() {
+    let (_, diags, _) = lower(
+        "fn f(a: u8 = 1) {}
+        fn g() {
+            let x = def!();
+            f(def!());
+            f(..def!(), 1);
+            f(..(1,), ..def!());
+            f(a: def!());
+            f(explicit_param!());
+            x.f(..def!());
+            f(..def!(1));
+        }",
+    );
+    assert_eq!(
+        messages(&diags),
+        [
+            "`def!()` can only be used in the arguments of a function call",
+            "`def!()` has to be spread into the arguments",
+            "`..def!()` has to be the last argument",
+            "`..def!()` can not be combined with other spread arguments",
+            "`def!()` can only be used in the arguments of a function call",
+            "`explicit_param!()` can not be written",
+            "`..def!()` can not be used in method calls",
+            "`def!()` takes no arguments",
+        ]
+    );
+    assert_eq!(diags[1].suggestions[0].parts[0].replacement, "..");
+}
+
+#[test]
+fn named_and_spread_args() {
+    let (krate, diags, source_map) = lower(
+        r#"
+        fn f(a: u8, b: u8, c: u8) {}
+        fn another_function(factor: usize = 1, string: &str, offset: usize = 0) usize {}
+        fn main() {
+            f(1, c: 3, b: 2);
+            f(..(1, 2), 3);
+            f(1, ..(2, 3));
+            f(c: 3, a: 1, b: 2);
+            another_function(..(1, "Test123", 0));
+        }
+        "#,
+    );
+    assert_eq!(diags, Vec::new());
+    assert_eq!(
+        call_args(&krate, &source_map),
+        [
+            ["1", "2", "3"],
+            ["1", "2", "3"],
+            ["1", "2", "3"],
+            ["1", "2", "3"],
+            ["1", "\"Test123\"", "0"],
+        ]
+    );
+}
+
+#[test]
+fn invalid_named_and_spread_args() {
+    let (_, diags, _) = lower(
+        "fn f(a: u8, b: u8 = 0) {}
+        fn g() {
+            f(1, a: 2);
+            f(1, d: 2);
+            f(b: 1, 2);
+            f(1, ..x);
+            x.m(a: 1);
+            unknown(..(1, 2));
+        }",
+    );
+    assert_eq!(
+        messages(&diags),
+        [
+            "parameter `a` is passed more than once",
+            "missing argument for parameter `b`",
+            "no parameter named `d` in `f`",
+            "missing argument for parameter `b`",
+            "positional argument after named arguments",
+            "only tuple expressions can be spread into the arguments",
+            "methods can not be called with named or spread arguments",
+            "cannot find the function called with named or spread arguments",
+        ]
+    );
+    assert_eq!(
+        diags[1].children[0].message,
+        "pass the default values of the parameters with `..def!()`"
+    );
+}
+
+#[test]
+fn arity() {
+    let (krate, diags, source_map) = lower(
+        "fn f(a: u8, b: u8) {}
+        fn g() {
+            f(1, 2, 3, 4);
+            f(1, ..(2, 3, 4));
+            f(1);
+            f(..(1,));
+            f(..(1, 2, 3), 4);
+        }",
+    );
+    assert_eq!(
+        messages(&diags),
+        [
+            "function `f` takes 2 arguments but 4 were given",
+            "the spread tuple has more elements than parameters are left",
+            "missing argument for parameter `b`",
+            "missing argument for parameter `b`",
+            "the spread tuple has more elements than parameters are left",
+        ]
+    );
+    let labels = |diag: &Diagnostic| -> Vec<String> {
+        diag.labels.iter().map(|label| label.label.clone().unwrap_or_default()).collect()
+    };
+    assert_eq!(labels(&diags[0]), ["unexpected argument", "unexpected argument", "function declared here"]);
+    assert_eq!(labels(&diags[1]), ["3 elements, but only 1 parameter is left", "function declared here"]);
+    assert_eq!(
+        labels(&diags[3]),
+        ["`b` is not passed", "parameter declared here", "the spread tuple has only 1 element"]
+    );
+    // the elements in front of the tuple are counted, the argument behind it is reported as well
+    assert_eq!(
+        labels(&diags[4]),
+        ["3 elements, but only 2 parameters are left", "unexpected argument", "function declared here"]
+    );
+    assert_eq!(
+        call_args(&krate, &source_map),
+        [["1", "2"], ["1", "2"], ["1", "<err>"], ["1", "<err>"], ["1", "2"]]
+    );
+}
+
+/// the return types of the functions in the crate, written without sugar
+fn ret_tys(krate: &Crate) -> Vec<String> {
+    fn ty_to_string(ty: &Ty) -> String {
+        match &ty.kind {
+            TyKind::Path(path) => {
+                let segments = path.segments.iter().map(|segment| match &segment.args {
+                    Some(args) => {
+                        let args: Vec<_> = args.args.iter().map(|ty| ty_to_string(ty)).collect();
+                        format!("{}<{}>", segment.ident.name, args.join(", "))
+                    }
+                    None => segment.ident.name.to_string(),
+                });
+                segments.collect::<Vec<_>>().join("::")
+            }
+            TyKind::Tup(tys) if tys.is_empty() => "()".to_string(),
+            TyKind::Never => "!".to_string(),
+            kind => format!("{kind:?}"),
+        }
+    }
+
+    let fns = krate.items.iter().filter_map(|item| match &item.kind {
+        ItemKind::Fn(func) => Some(&func.sig.output),
+        _ => None,
+    });
+    fns.map(|ret_ty| match ret_ty {
+        FnRetTy::Default(_) => "()".to_string(),
+        FnRetTy::Ty(ty) => ty_to_string(ty),
+        ret_ty => panic!("return type sugar is not lowered: {ret_ty:?}"),
+    })
+    .collect()
+}
+
+#[test]
+fn ret_ty_sugar() {
+    let (krate, diags, source_map) =
+        lower("fn a() String! {} fn b() String? {} fn c() String!Error {} fn d() !Error {} fn e() ! {} fn f() u8 {} fn g() {}");
+    assert_eq!(diags, Vec::new());
+    assert_eq!(
+        ret_tys(&krate),
+        [
+            "std::option::Option<String>",
+            "std::option::Option<String>",
+            "std::result::Result<String, Error>",
+            "std::result::Result<(), Error>",
+            "!",
+            "u8",
+            "()",
+        ]
+    );
+
+    // the paths which are not written are located at the `!`
+    let ItemKind::Fn(func) = &krate.items[2].kind else { panic!() };
+    let FnRetTy::Ty(ty) = &func.sig.output else { panic!() };
+    let TyKind::Path(path) = &ty.kind else { panic!() };
+    assert_eq!(source_map.snippet(path.src_data).as_deref(), Some("!"));
+    assert_eq!(source_map.snippet(ty.src_data).as_deref(), Some("String!Error"));
+}
+
+#[test]
+fn try_operator() {
+    let (_, diags, source_map) = lower(
+        "fn a() String! { x? }
+        fn b() !Error { x?; let y = || z?; }
+        fn c() Option<u8> { x? }
+        fn d() &u8 { x ? }
+        fn e() ! { x? }
+        fn f() { fn g() u8! { x? } x? }
+        fn h(a: u8 = x?) u8 { || x? }
+        fn i() io::Result<u8> { io::read()? }
+        fn j() std::fmt::Result { k()? }",
+    );
+    assert_eq!(
+        messages(&diags),
+        [
+            "the `?` operator can only be used in a function that returns `Option` or `Result`",
+            "the `?` operator can only be used in a function that returns `Option` or `Result`",
+            "the `?` operator can only be used in a function that returns `Option` or `Result`",
+        ]
+    );
+    let snippets = |diag: &Diagnostic| -> Vec<String> {
+        diag.labels.iter().map(|label| source_map.snippet(label.src_data).unwrap()).collect()
+    };
+    assert_eq!(snippets(&diags[0]), ["?", "&u8"]);
+    assert_eq!(snippets(&diags[1]), ["?", "!"]);
+    assert_eq!(diags[1].labels[1].label.as_deref(), Some("this function never returns"));
+    assert_eq!(diags[2].labels[1].label.as_deref(), Some("this function returns `()`"));
+}
+
+/// the name of the entry function and the messages of all diagnostics
+fn entry(src: &str) -> (Option<String>, Vec<String>) {
+    let (krate, _, source_map) = lower(src);
+    let psess = ParseSess::with_dcx(DiagCtxt::new(Box::new(SilentEmitter)), source_map);
+    let entry_fn = entry::entry_fn(&psess, &krate).map(|entry_fn| entry_fn.ident.name.to_string());
+    (entry_fn, psess.dcx.diagnostics().into_iter().map(|diag| diag.message).collect())
+}
+
+#[test]
+fn entry_point() {
+    let none = Vec::<String>::new();
+    assert_eq!(entry("#[entry] fn start() {} fn main() {}"), (Some("start".to_string()), none.clone()));
+    assert_eq!(entry("fn main() {} mod m { fn main() {} }"), (Some("main".to_string()), none.clone()));
+    assert_eq!(entry("#[entry] fn main() {}"), (Some("main".to_string()), none));
+
+    assert_eq!(entry("#[entry] fn a() {} #[entry] fn b() {}"), (None, vec!["multiple entry points".to_string()]));
+    assert_eq!(
+        entry("fn main() {} mod m { #[entry] fn a() {} }"),
+        (None, vec!["entry point in non-root module".to_string()])
+    );
+    assert_eq!(
+        entry("fn f() { #[entry] fn a() {} }"),
+        (None, vec!["entry point in non-root module".to_string()])
+    );
+    assert_eq!(
+        entry("mod m { fn main() {} } fn f() { fn main() {} }"),
+        (None, vec!["no entry point for binary".to_string()])
+    );
+}
+
+#[test]
+fn builtin_attributes() {
+    let (_, diags, _) = lower(
+        r#"
+        #![allow(warnings, unknown_attributes)]
+        #![doc = "the crate"]
+        /// docs
+        #[entry]
+        #[inline(always)]
+        #[test]
+        #[deny(warnings)]
+        fn main() {}
+        #[cfg(test)]
+        #[doc(hidden)]
+        mod m { #![warn(unknown_attributes)] }
+        "#,
+    );
+    assert_eq!(diags, Vec::new());
+}
+
+#[test]
+fn lint_level_attributes() {
+    let (_, diags, _) = lower(
+        r#"
+        #![allow(unknown_attributes)]
+        #[frob]
+        fn main() {}
+        #[warn(unknown_attributes)]
+        mod m {
+            #[frob]
+            fn f() {}
+            #[deny(warnings)]
+            #[frob]
+            fn g() {}
+            #[frob]
+            fn h() {}
+        }
+        #[frob]
+        fn i() {}
+        "#,
+    );
+    let levels: Vec<_> = diags.iter().map(|diag| (diag.message.as_str(), diag.level)).collect();
+    assert_eq!(
+        levels,
+        [
+            ("unknown attribute `frob`", Level::Warning),
+            ("unknown attribute `frob`", Level::Error),
+            ("unknown attribute `frob`", Level::Warning),
+        ]
+    );
+    assert!(diags.iter().all(|diag| diag.children.is_empty()));
+}
+
+#[test]
+fn invalid_attributes() {
+    let (_, diags, _) = lower(
+        r#"
+        #![entry]
+        #[inline(sometimes)]
+        #[inline = "always"]
+        #[entry(main)]
+        #[doc = 1]
+        #[allow(a::b)]
+        #[warn(unknown_lint)]
+        #[frobnicate]
+        #[tool::attr]
+        fn main() {}
+        #[derive(Debug)]
+        mod m { #![cfg] }
+        #[doc(inline)]
+        #[cfg(a, b)]
+        #[cfg()]
+        mod n {}
+        #[cfg(feature = "x")]
+        #[cfg(not(test))]
+        #[doc(hidden, hidden)]
+        fn f() {}
+        "#,
+    );
+    assert_eq!(
+        messages(&diags),
+        [
+            "`#[entry]` can not be applied to a crate",
+            "malformed `inline` attribute input",
+            "malformed `inline` attribute input",
+            "malformed `entry` attribute input",
+            "malformed `doc` attribute input",
+            "malformed `allow` attribute input",
+            "unknown lint: `unknown_lint`",
+            "unknown attribute `frobnicate`",
+            "unknown attribute `tool::attr`",
+            "`#[derive]` can not be applied to a module",
+            "malformed `cfg` attribute input",
+            "malformed `doc` attribute input",
+            "malformed `cfg` attribute input",
+            "malformed `cfg` attribute input",
+            "malformed `doc` attribute input",
+        ]
+    );
+    assert_eq!(
+        diags[1].children[0].message,
+        "the attribute has to be written as `#[inline]` or `#[inline(always|never)]`"
+    );
+    assert_eq!(diags[10].children[0].message, "the attribute has to be written as `#![cfg(predicate)]`");
+    assert_eq!(diags[0].children[0].message, "`#[entry]` can only be applied to functions");
+    // the unknown attributes are a lint
+    assert!(!diags[7].is_error());
+    assert_eq!(diags[7].children[0].message, "`#[warn(unknown_attributes)]` on by default");
+}
@@ -0,0 +1,78 @@
+//! Error codes of the compiler
+//!
+//! codes are never reused, even if the error can not occur anymore
+
+use crate::ErrCode;
+
+/// a character which can not start any token, e.g. `€`
+pub const E0001: ErrCode = ErrCode(1);
+/// a digit which is not valid for the base of an integer literal, e.g. `0b102`
+pub const E0002: ErrCode = ErrCode(2);
+/// a float literal with a base other than decimal, e.g. `0x1.5`
+pub const E0003: ErrCode = ErrCode(3);
+/// an invalid escape in a char or string literal, e.g. `'\q'`
+pub const E0004: ErrCode = ErrCode(4);
+/// a block comment which is never closed, e.g. `/* ...`
+pub const E0005: ErrCode = ErrCode(5);
+/// a char or string literal which is never closed, e.g. `"abc`
+pub const E0006: ErrCode = ErrCode(6);
+/// a number literal without digits, e.g. `0x` or `1e`
+pub const E0007: ErrCode = ErrCode(7);
+/// an identifier directly in front of a literal, which is no valid prefix, e.g. `x"abc"`
+pub const E0008: ErrCode = ErrCode(8);
+/// an identifier containing emoji, e.g. `ident🦀`
+pub const E0009: ErrCode = ErrCode(9);
+
+/// an opening delimiter without a closing one
+pub const E0010: ErrCode = ErrCode(10);
+/// a closing delimiter which does not match the opening one, e.g. `( ]`
+pub const E0011: ErrCode = ErrCode(11);
+/// a closing delimiter without an opening one
+pub const E0012: ErrCode = ErrCode(12);
+/// invalid `#` delimiters of a raw string, e.g. `r#~"abc"#`
+pub const E0013: ErrCode = ErrCode(13);
+/// a literal with a suffix which is not allowed for it, e.g. `12u7` or `"abc"suffix`
+pub const E0014: ErrCode = ErrCode(14);
+/// a token which is not allowed at this position, e.g. `fn (`
+pub const E0015: ErrCode = ErrCode(15);
+/// an integer or float literal which does not fit into its type, e.g. `256u8`
+pub const E0016: ErrCode = ErrCode(16);
+
+/// `def!()` which is not spread as the last argument of a function call, e.g. `f(def!(), 1)`,
+/// or a written `explicit_param!()`
+pub const E0017: ErrCode = ErrCode(17);
+/// a parameter without a default value which is not passed to a call with `..def!()`
+pub const E0018: ErrCode = ErrCode(18);
+/// a function called with `..def!()` which can not be found, e.g. `(f)(..def!())`
+pub const E0019: ErrCode = ErrCode(19);
+/// a named argument without a parameter of that name, e.g. `f(unknown: 1)`
+pub const E0020: ErrCode = ErrCode(20);
+/// an argument for a parameter which was already passed, e.g. `f(1, a: 2)` for `fn f(a: u8)`
+pub const E0021: ErrCode = ErrCode(21);
+/// more arguments than the function has parameters
+pub const E0022: ErrCode = ErrCode(22);
+/// a positional argument after a named one, e.g. `f(a: 1, 2)`
+pub const E0023: ErrCode = ErrCode(23);
+/// fewer arguments than the function has parameters
+pub const E0024: ErrCode = ErrCode(24);
+/// a spread argument which is not a tuple expression, e.g. `f(..args)`
+pub const E0025: ErrCode = ErrCode(25);
+/// named or spread arguments for a function which can not be found, e.g. `x.f(a: 1)`
+pub const E0026: ErrCode = ErrCode(26);
+/// the `?` operator in a function which does not return `Option` or `Result`, e.g. `fn f() u8 { x? }`
+pub const E0027: ErrCode = ErrCode(27);
+
+/// more than one function marked with `#[entry]`
+pub const E0028: ErrCode = ErrCode(28);
+/// a function marked with `#[entry]` which is not in the root module of the crate
+pub const E0029: ErrCode = ErrCode(29);
+/// a binary without a function marked with `#[entry]` or a `main` function in the root module
+pub const E0030: ErrCode = ErrCode(30);
+
+/// a built-in attribute which is not written in one of its forms, e.g. `#[inline(sometimes)]`
+pub const E0031: ErrCode = ErrCode(31);
+/// a built-in attribute applied to an item it is not meant for, e.g. `#[entry] mod m {}`
+pub const E0032: ErrCode = ErrCode(32);
+
+/// a default value of a parameter which uses itself through `..def!()`, e.g. `fn f(a: u8 = f(..def!()))`
+pub const E0033: ErrCode = ErrCode(33);
@@ -0,0 +1,205 @@
+use std::fmt;
+
+use source_idx::SrcData;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Level {
+    /// an internal compiler error
+    Bug,
+    Error,
+    Warning,
+    Note,
+    Help,
+}
+
+impl Level {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Level::Bug => "error: internal compiler error",
+            Level::Error => "error",
+            Level::Warning => "warning",
+            Level::Note => "note",
+            Level::Help => "help",
+        }
+    }
+
+    pub fn is_error(self) -> bool {
+        matches!(self, Level::Bug | Level::Error)
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// error code of a diagnostic, displayed as `E0001`
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct ErrCode(pub u32);
+
+impl fmt::Display for ErrCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "E{:04}", self.0)
+    }
+}
+
+/// A source location with an optional message attached to it
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SpanLabel {
+    pub src_data: SrcData,
+    pub label: Option<String>,
+    /// the primary location is where the problem is,
+    /// secondary locations add some context
+    pub is_primary: bool,
+}
+
+/// A note or help message which belongs to a [`Diagnostic`]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SubDiagnostic {
+    pub level: Level,
+    pub message: String,
+    pub src_data: Option<SrcData>,
+}
+
+/// How confident the compiler is that a [`Suggestion`] is correct
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Applicability {
+    /// can be applied by tools without looking at it
+    MachineApplicable,
+    /// may be what the user intended, but could also be wrong
+    MaybeIncorrect,
+    /// contains placeholders like `(...)` which have to be filled in by the user
+    HasPlaceholders,
+    Unspecified,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SuggestionPart {
+    pub src_data: SrcData,
+    pub replacement: String,
+}
+
+/// A proposed change to the source code
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Suggestion {
+    pub message: String,
+    pub parts: Vec<SuggestionPart>,
+    pub applicability: Applicability,
+}
+
+/// A message for the user about a problem in the source code.
+///
+/// Diagnostics are created with the builder methods and then passed to a [`crate::DiagCtxt`]
+/// ```ignore
+/// dcx.emit(
+///     Diagnostic::error("unterminated string literal")
+///         .with_code(codes::E0006)
+///         .with_primary(src_data, "")
+///         .with_help("add a `\"` at the end of the string"),
+/// );
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Diagnostic {
+    pub level: Level,
+    pub code: Option<ErrCode>,
+    pub message: String,
+    pub labels: Vec<SpanLabel>,
+    pub children: Vec<SubDiagnostic>,
+    pub suggestions: Vec<Suggestion>,
+}
+
+impl Diagnostic {
+    pub fn new(level: Level, message: impl Into<String>) -> Diagnostic {
+        Diagnostic {
+            level,
+            code: None,
+            message: message.into(),
+            labels: Vec::new(),
+            children: Vec::new(),
+            suggestions: Vec::new(),
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Diagnostic {
+        Diagnostic::new(Level::Error, message)
+    }
+
+    pub fn warning(message: impl Into<String>) -> Diagnostic {
+        Diagnostic::new(Level::Warning, message)
+    }
+
+    pub fn bug(message: impl Into<String>) -> Diagnostic {
+        Diagnostic::new(Level::Bug, message)
+    }
+
+    pub fn with_code(mut self, code: ErrCode) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /// adds the location where the problem is, an empty `label` is not displayed
+    pub fn with_primary(mut self, src_data: SrcData, label: impl Into<String>) -> Self {
+        self.labels.push(SpanLabel { src_data, label: non_empty(label.into()), is_primary: true });
+        self
+    }
+
+    /// adds a location which helps understanding the problem
+    pub fn with_secondary(mut self, src_data: SrcData, label: impl Into<String>) -> Self {
+        self.labels.push(SpanLabel { src_data, label: non_empty(label.into()), is_primary: false });
+        self
+    }
+
+    pub fn with_note(mut self, message: impl Into<String>) -> Self {
+        self.children.push(SubDiagnostic { level: Level::Note, message: message.into(), src_data: None });
+        self
+    }
+
+    pub fn with_help(mut self, message: impl Into<String>) -> Self {
+        self.children.push(SubDiagnostic { level: Level::Help, message: message.into(), src_data: None });
+        self
+    }
+
+    pub fn with_src_note(mut self, src_data: SrcData, message: impl Into<String>) -> Self {
+        self.children.push(SubDiagnostic {
+            level: Level::Note,
+            message: message.into(),
+            src_data: Some(src_data),
+        });
+        self
+    }
+
+    /// suggests to replace the source at `src_data` with `replacement`
+    pub fn with_suggestion(
+        self,
+        src_data: SrcData,
+        message: impl Into<String>,
+        replacement: impl Into<String>,
+        applicability: Applicability,
+    ) -> Self {
+        let part = SuggestionPart { src_data, replacement: replacement.into() };
+        self.with_multipart_suggestion(message, vec![part], applicability)
+    }
+
+    pub fn with_multipart_suggestion(
+        mut self,
+        message: impl Into<String>,
+        parts: Vec<SuggestionPart>,
+        applicability: Applicability,
+    ) -> Self {
+        self.suggestions.push(Suggestion { message: message.into(), parts, applicability });
+        self
+    }
+
+    pub fn primary_src_data(&self) -> Option<SrcData> {
+        self.labels.iter().find(|label| label.is_primary).map(|label| label.src_data)
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.level.is_error()
+    }
+}
+
+fn non_empty(label: String) -> Option<String> {
+    (!label.is_empty()).then_some(label)
+}
@@ -0,0 +1,226 @@
+use std::{fmt::Write as _, io::Write, rc::Rc};
+
+use source_idx::{SourceFile, SourceMap, SrcData};
+
+use crate::diagnostic::{Diagnostic, Level, SpanLabel, Suggestion};
+
+/// Receives every diagnostic passed to a [`crate::DiagCtxt`]
+pub trait Emitter {
+    fn emit_diagnostic(&mut self, diag: &Diagnostic);
+}
+
+/// Does not emit anything, the diagnostics are still collected by the [`crate::DiagCtxt`]
+pub struct SilentEmitter;
+
+impl Emitter for SilentEmitter {
+    fn emit_diagnostic(&mut self, _diag: &Diagnostic) {}
+}
+
+/// Renders diagnostics for humans in the style of rustc
+/// ```text
+/// error[E0002]: invalid digit for a base 2 literal
+///  --> main.sl:2:7
+///   |
+/// 2 |     0b102
+///   |         ^
+/// ```
+pub struct HumanEmitter {
+    source_map: Rc<SourceMap>,
+    dst: Box<dyn Write>,
+}
+
+impl HumanEmitter {
+    pub fn new(source_map: Rc<SourceMap>, dst: Box<dyn Write>) -> HumanEmitter {
+        HumanEmitter { source_map, dst }
+    }
+
+    pub fn stderr(source_map: Rc<SourceMap>) -> HumanEmitter {
+        HumanEmitter::new(source_map, Box::new(std::io::stderr()))
+    }
+
+    pub fn render(&self, diag: &Diagnostic) -> String {
+        let mut out = String::new();
+        match diag.code {
+            Some(code) => writeln!(out, "{}[{}]: {}", diag.level, code, diag.message),
+            None => writeln!(out, "{}: {}", diag.level, diag.message),
+        }
+        .unwrap();
+
+        let gutter = self.gutter_width(diag);
+        self.render_labels(&mut out, &diag.labels, gutter);
+
+        for child in &diag.children {
+            match child.src_data {
+                Some(src_data) => {
+                    writeln!(out, "{}: {}", child.level, child.message).unwrap();
+                    let label = SpanLabel { src_data, label: None, is_primary: true };
+                    self.render_labels(&mut out, &[label], gutter);
+                }
+                None => writeln!(out, "{:gutter$} = {}: {}", "", child.level, child.message).unwrap(),
+            }
+        }
+
+        for suggestion in &diag.suggestions {
+            self.render_suggestion(&mut out, suggestion, gutter);
+        }
+        out
+    }
+
+    fn gutter_width(&self, diag: &Diagnostic) -> usize {
+        let label_src = diag.labels.iter().map(|label| label.src_data);
+        let child_src = diag.children.iter().filter_map(|child| child.src_data);
+        let suggestion_src = diag.suggestions.iter().flat_map(|s| s.parts.iter().map(|p| p.src_data));
+        label_src
+            .chain(child_src)
+            .chain(suggestion_src)
+            .filter_map(|src_data| self.source_map.lookup(src_data.hi()))
+            .map(|loc| loc.line.to_string().len())
+            .max()
+            .unwrap_or(1)
+    }
+
+    fn render_labels(&self, out: &mut String, labels: &[SpanLabel], gutter: usize) {
+        let mut resolved: Vec<_> = labels
+            .iter()
+            .filter_map(|label| {
+                let file = self.source_map.lookup_file(label.src_data.lo())?;
+                let line_idx = file.lookup_line(label.src_data.lo())?;
+                Some((file, line_idx, label))
+            })
+            .collect();
+        // primary labels decide which location is displayed in the header
+        resolved.sort_by_key(|(file, line_idx, label)| (!label.is_primary, file.id, *line_idx));
+        let Some((first_file, _, first_label)) = resolved.first() else {
+            return;
+        };
+        let Some(loc) = first_file.lookup(first_label.src_data.lo()) else {
+            return;
+        };
+        writeln!(out, "{:gutter$}--> {}:{}:{}", "", first_file.name, loc.line, loc.col + 1).unwrap();
+        writeln!(out, "{:gutter$} |", "").unwrap();
+
+        resolved.sort_by_key(|(file, line_idx, label)| (file.id, *line_idx, label.src_data.lo()));
+        let mut prev: Option<(&Rc<SourceFile>, usize)> = None;
+        for (file, line_idx, label) in &resolved {
+            let same_line = prev.is_some_and(|(f, l)| f.id == file.id && l == *line_idx);
+            if !same_line {
+                match prev {
+                    Some((prev_file, _)) if prev_file.id != file.id => {
+                        let col = file.lookup(label.src_data.lo()).map_or(0, |loc| loc.col);
+                        writeln!(out, "{:gutter$}::: {}:{}:{}", "", file.name, line_idx + 1, col + 1).unwrap();
+                        writeln!(out, "{:gutter$} |", "").unwrap();
+                    }
+                    Some((_, prev_line)) if *line_idx > prev_line + 1 => {
+                        writeln!(out, "...").unwrap();
+                    }
+                    _ => {}
+                }
+                let text = file.line_text(*line_idx).unwrap_or_default();
+                writeln!(out, "{:>gutter$} | {}", line_idx + 1, expand_tabs(text)).unwrap();
+            }
+            let (start, width) = underline_range(file, *line_idx, label.src_data);
+            let marker = if label.is_primary { "^" } else { "-" };
+            write!(out, "{:gutter$} | {:start$}{}", "", "", marker.repeat(width)).unwrap();
+            match &label.label {
+                Some(text) => writeln!(out, " {}", text).unwrap(),
+                None => writeln!(out).unwrap(),
+            }
+            prev = Some((file, *line_idx));
+        }
+        writeln!(out, "{:gutter$} |", "").unwrap();
+    }
+
+    fn render_suggestion(&self, out: &mut String, suggestion: &Suggestion, gutter: usize) {
+        writeln!(out, "{}: {}", Level::Help, suggestion.message).unwrap();
+        let mut parts: Vec<_> = suggestion
+            .parts
+            .iter()
+            .filter_map(|part| {
+                let file = self.source_map.lookup_file(part.src_data.lo())?;
+                let line_idx = file.lookup_line(part.src_data.lo())?;
+                Some((file, line_idx, part))
+            })
+            .collect();
+        parts.sort_by_key(|(file, _, part)| (file.id, part.src_data.lo()));
+
+        writeln!(out, "{:gutter$} |", "").unwrap();
+        let mut idx = 0;
+        while idx < parts.len() {
+            let (file, line_idx, _) = &parts[idx];
+            let line_idx = *line_idx;
+            let line_start = file.line_start(line_idx);
+            let mut text = file.line_text(line_idx).unwrap_or_default().to_string();
+            // apply all parts of this line back to front to keep the offsets valid
+            let line_parts: Vec<_> = parts[idx..]
+                .iter()
+                .take_while(|(f, l, _)| f.id == file.id && *l == line_idx)
+                .collect();
+            for (_, _, part) in line_parts.iter().rev() {
+                let lo = (part.src_data.lo() - line_start).to_usize().min(text.len());
+                let hi = (part.src_data.hi() - line_start).to_usize().clamp(lo, text.len());
+                text.replace_range(lo..hi, &part.replacement);
+            }
+            writeln!(out, "{:>gutter$} | {}", line_idx + 1, expand_tabs(&text)).unwrap();
+
+            let mut markers = String::new();
+            let mut shift = 0isize;
+            for (_, _, part) in &line_parts {
+                let lo = (part.src_data.lo() - line_start).to_usize() as isize + shift;
+                let start = text.get(..lo.max(0) as usize).map(display_width).unwrap_or_default();
+                let replaced_width = text_width(file, part.src_data);
+                let (marker, width) = if part.replacement.is_empty() {
+                    ("-", replaced_width)
+                } else if part.src_data.lo() == part.src_data.hi() {
+                    ("+", display_width(&part.replacement))
+                } else {
+                    ("~", display_width(&part.replacement))
+                };
+                let width = width.max(1);
+                let pad = start.saturating_sub(display_width(&markers));
+                write!(markers, "{:pad$}{}", "", marker.repeat(width)).unwrap();
+                let replaced = (part.src_data.hi() - part.src_data.lo()).to_usize() as isize;
+                shift += part.replacement.len() as isize - replaced;
+            }
+            writeln!(out, "{:gutter$} | {}", "", markers).unwrap();
+            idx += line_parts.len();
+        }
+        writeln!(out, "{:gutter$} |", "").unwrap();
+    }
+}
+
+impl Emitter for HumanEmitter {
+    fn emit_diagnostic(&mut self, diag: &Diagnostic) {
+        let rendered = self.render(diag);
+        // there is nothing we could do if writing the diagnostic itself fails
+        let _ = writeln!(self.dst, "{}", rendered);
+        let _ = self.dst.flush();
+    }
+}
+
+const TAB_WIDTH: usize = 4;
+
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+fn display_width(text: &str) -> usize {
+    text.chars().map(|c| if c == '\t' { TAB_WIDTH } else { 1 }).sum()
+}
+
+fn text_width(file: &SourceFile, src_data: SrcData) -> usize {
+    let lo = (src_data.lo() - file.start_pos).to_usize();
+    let hi = (src_data.hi() - file.start_pos).to_usize();
+    file.src.get(lo..hi).map(display_width).unwrap_or_default()
+}
+
+/// start column and width of the underline of `src_data` in the line `line_idx`
+fn underline_range(file: &SourceFile, line_idx: usize, src_data: SrcData) -> (usize, usize) {
+    let text = file.line_text(line_idx).unwrap_or_default();
+    let line_start = file.line_start(line_idx);
+    let lo = (src_data.lo() - line_start).to_usize().min(text.len());
+    // spans reaching over multiple lines are underlined until the end of the first line
+    let hi = src_data.hi().to_usize().saturating_sub(line_start.to_usize()).clamp(lo, text.len());
+    let start = text.get(..lo).map(display_width).unwrap_or_default();
+    let width = text.get(lo..hi).map(display_width).unwrap_or_default();
+    (start, width.max(1))
+}
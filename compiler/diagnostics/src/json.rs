@@ -0,0 +1,268 @@
+//! Renders diagnostics as JSON for editors and other tools
+//!
+//! every diagnostic is written as a single line containing one object,
+//! the fields are always present and in this order, values which do not exist are `null`
+//! ```text
+//! Diagnostic {
+//!     "level": "bug" | "error" | "warning" | "note" | "help",
+//!     "code": "E0002" | null,
+//!     "message": string,
+//!     "spans": [Span],
+//!     "children": [{ "level": string, "message": string, "spans": [Span] }],
+//!     "suggestions": [{
+//!         "message": string,
+//!         "applicability": "MachineApplicable" | "MaybeIncorrect" | "HasPlaceholders" | "Unspecified",
+//!         "parts": [{ "span": Span, "replacement": string }],
+//!     }],
+//!     // the diagnostic as it is rendered for humans
+//!     "rendered": string,
+//! }
+//!
+//! Span {
+//!     "file_name": string,
+//!     // 0-based byte offsets into the file, `byte_end` is exclusive
+//!     "byte_start": number,
+//!     "byte_end": number,
+//!     // 1-based lines and columns, columns are counted in chars and `column_end` is exclusive
+//!     "line_start": number,
+//!     "line_end": number,
+//!     "column_start": number,
+//!     "column_end": number,
+//!     "is_primary": bool,
+//!     "label": string | null,
+//!     // every line the span covers, highlighted from the 1-based char columns
+//!     // `highlight_start` up to `highlight_end` exclusive
+//!     "text": [{ "text": string, "highlight_start": number, "highlight_end": number }],
+//! }
+//! ```
+//! locations which do not belong to a file of the source map are left out of `spans`
+
+use std::{fmt, io::Write, rc::Rc};
+
+use source_idx::SourceMap;
+
+use crate::{
+    diagnostic::{Applicability, Diagnostic, Level, SpanLabel},
+    emitter::{Emitter, HumanEmitter},
+};
+
+pub struct JsonEmitter {
+    source_map: Rc<SourceMap>,
+    dst: Box<dyn Write>,
+    /// renders the `rendered` field, it never writes anything
+    human: HumanEmitter,
+}
+
+impl JsonEmitter {
+    pub fn new(source_map: Rc<SourceMap>, dst: Box<dyn Write>) -> JsonEmitter {
+        let human = HumanEmitter::new(source_map.clone(), Box::new(std::io::sink()));
+        JsonEmitter { source_map, dst, human }
+    }
+
+    pub fn stderr(source_map: Rc<SourceMap>) -> JsonEmitter {
+        JsonEmitter::new(source_map, Box::new(std::io::stderr()))
+    }
+
+    /// the diagnostic as a JSON object without a trailing newline
+    pub fn render(&self, diag: &Diagnostic) -> String {
+        let spans = diag.labels.iter().filter_map(|label| self.span(label)).collect();
+        let children = diag
+            .children
+            .iter()
+            .map(|child| {
+                let spans = child
+                    .src_data
+                    .and_then(|src_data| self.span(&SpanLabel { src_data, label: None, is_primary: true }))
+                    .into_iter()
+                    .collect();
+                Json::Object(vec![
+                    ("level", Json::from(level_name(child.level))),
+                    ("message", Json::from(child.message.as_str())),
+                    ("spans", Json::Array(spans)),
+                ])
+            })
+            .collect();
+        let suggestions = diag
+            .suggestions
+            .iter()
+            .map(|suggestion| {
+                let parts = suggestion
+                    .parts
+                    .iter()
+                    .map(|part| {
+                        let label = SpanLabel { src_data: part.src_data, label: None, is_primary: true };
+                        Json::Object(vec![
+                            ("span", self.span(&label).unwrap_or(Json::Null)),
+                            ("replacement", Json::from(part.replacement.as_str())),
+                        ])
+                    })
+                    .collect();
+                Json::Object(vec![
+                    ("message", Json::from(suggestion.message.as_str())),
+                    ("applicability", Json::from(applicability_name(suggestion.applicability))),
+                    ("parts", Json::Array(parts)),
+                ])
+            })
+            .collect();
+        Json::Object(vec![
+            ("level", Json::from(level_name(diag.level))),
+            ("code", diag.code.map_or(Json::Null, |code| Json::String(code.to_string()))),
+            ("message", Json::from(diag.message.as_str())),
+            ("spans", Json::Array(spans)),
+            ("children", Json::Array(children)),
+            ("suggestions", Json::Array(suggestions)),
+            ("rendered", Json::String(self.human.render(diag))),
+        ])
+        .to_string()
+    }
+
+    fn span(&self, label: &SpanLabel) -> Option<Json> {
+        let file = self.source_map.lookup_file(label.src_data.lo())?;
+        if !file.contains(label.src_data.hi()) {
+            return None;
+        }
+        let start = file.lookup(label.src_data.lo())?;
+        let end = file.lookup(label.src_data.hi())?;
+        let text = (start.line..=end.line)
+            .map(|line| {
+                let text = file.line_text(line - 1).unwrap_or_default();
+                let highlight_start = if line == start.line { start.col + 1 } else { 1 };
+                let highlight_end = if line == end.line { end.col + 1 } else { text.chars().count() + 1 };
+                Json::Object(vec![
+                    ("text", Json::from(text)),
+                    ("highlight_start", Json::Number(highlight_start)),
+                    ("highlight_end", Json::Number(highlight_end)),
+                ])
+            })
+            .collect();
+        Some(Json::Object(vec![
+            ("file_name", Json::String(file.name.to_string())),
+            // the offsets in the file as it is on disk, with `\r\n` line breaks
+            ("byte_start", Json::Number(file.original_offset(label.src_data.lo()))),
+            ("byte_end", Json::Number(file.original_offset(label.src_data.hi()))),
+            ("line_start", Json::Number(start.line)),
+            ("line_end", Json::Number(end.line)),
+            ("column_start", Json::Number(start.col + 1)),
+            ("column_end", Json::Number(end.col + 1)),
+            ("is_primary", Json::Bool(label.is_primary)),
+            ("label", label.label.as_deref().map_or(Json::Null, Json::from)),
+            ("text", Json::Array(text)),
+        ]))
+    }
+}
+
+impl Emitter for JsonEmitter {
+    fn emit_diagnostic(&mut self, diag: &Diagnostic) {
+        let rendered = self.render(diag);
+        // there is nothing we could do if writing the diagnostic itself fails
+        let _ = writeln!(self.dst, "{}", rendered);
+        let _ = self.dst.flush();
+    }
+}
+
+fn level_name(level: Level) -> &'static str {
+    match level {
+        Level::Bug => "bug",
+        Level::Error => "error",
+        Level::Warning => "warning",
+        Level::Note => "note",
+        Level::Help => "help",
+    }
+}
+
+fn applicability_name(applicability: Applicability) -> &'static str {
+    match applicability {
+        Applicability::MachineApplicable => "MachineApplicable",
+        Applicability::MaybeIncorrect => "MaybeIncorrect",
+        Applicability::HasPlaceholders => "HasPlaceholders",
+        Applicability::Unspecified => "Unspecified",
+    }
+}
+
+/// A JSON value, objects keep the order of their fields
+enum Json {
+    Null,
+    Bool(bool),
+    Number(usize),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Json {
+        Json::String(value.to_string())
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            Json::Number(value) => write!(f, "{}", value),
+            Json::String(value) => write_escaped(f, value),
+            Json::Array(values) => {
+                f.write_str("[")?;
+                for (idx, value) in values.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                f.write_str("]")
+            }
+            Json::Object(fields) => {
+                f.write_str("{")?;
+                for (idx, (name, value)) in fields.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(",")?;
+                    }
+                    write_escaped(f, name)?;
+                    write!(f, ":{}", value)?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+fn write_escaped(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in value.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    f.write_str("\"")
+}
+
+#[cfg(test)]
+mod test {
+    use source_idx::{BytePos, FileName, SrcData};
+
+    use super::*;
+    use crate::codes;
+
+    #[test]
+    fn render_json() {
+        let sm = Rc::new(SourceMap::new());
+        let file = sm.new_source_file(FileName::Anon("test".into()), "fn main() {\n    \"ä\" 0b102\n}\n".into());
+        let src = |lo: u32, hi: u32| SrcData::with_root_ctxt(file.start_pos + BytePos(lo), file.start_pos + BytePos(hi));
+        let diag = Diagnostic::error("invalid digit for a base 2 literal")
+            .with_code(codes::E0002)
+            .with_primary(src(25, 26), "not a binary digit")
+            .with_src_note(src(0, 13), "in this function")
+            .with_suggestion(src(21, 23), "use a decimal literal instead", "", Applicability::MaybeIncorrect);
+        let json = JsonEmitter::new(sm.clone(), Box::new(std::io::sink())).render(&diag);
+        let rendered = HumanEmitter::new(sm, Box::new(std::io::sink())).render(&diag).replace('\n', "\\n");
+        let expected = r#"{"level":"error","code":"E0002","message":"invalid digit for a base 2 literal","spans":[{"file_name":"<test>","byte_start":25,"byte_end":26,"line_start":2,"line_end":2,"column_start":13,"column_end":14,"is_primary":true,"label":"not a binary digit","text":[{"text":"    \"ä\" 0b102","highlight_start":13,"highlight_end":14}]}],"children":[{"level":"note","message":"in this function","spans":[{"file_name":"<test>","byte_start":0,"byte_end":13,"line_start":1,"line_end":2,"column_start":1,"column_end":2,"is_primary":true,"label":null,"text":[{"text":"fn main() {","highlight_start":1,"highlight_end":12},{"text":"    \"ä\" 0b102","highlight_start":1,"highlight_end":2}]}]}],"suggestions":[{"message":"use a decimal literal instead","applicability":"MaybeIncorrect","parts":[{"span":{"file_name":"<test>","byte_start":21,"byte_end":23,"line_start":2,"line_end":2,"column_start":9,"column_end":11,"is_primary":true,"label":null,"text":[{"text":"    \"ä\" 0b102","highlight_start":9,"highlight_end":11}]},"replacement":""}]}],"rendered":"RENDERED"}"#;
+        assert_eq!(json, expected.replace("RENDERED", &rendered.replace('"', "\\\"")));
+    }
+}
@@ -0,0 +1,171 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use source_idx::SrcData;
+
+pub mod codes;
+mod diagnostic;
+pub mod emitter;
+mod json;
+pub mod lint;
+
+pub use diagnostic::{
+    Applicability, Diagnostic, ErrCode, Level, SpanLabel, SubDiagnostic, Suggestion, SuggestionPart,
+};
+pub use emitter::{Emitter, HumanEmitter, SilentEmitter};
+pub use json::JsonEmitter;
+pub use lint::{Lint, LintLevel};
+
+/// Collects all diagnostics of a session and passes them to the [`Emitter`]
+pub struct DiagCtxt {
+    inner: RefCell<DiagCtxtInner>,
+}
+
+struct DiagCtxtInner {
+    emitter: Box<dyn Emitter>,
+    diagnostics: Vec<Diagnostic>,
+    err_count: usize,
+    warn_count: usize,
+    /// the levels set on the command line, by the name of the lint or [`lint::WARNINGS`]
+    lint_levels: HashMap<String, LintLevel>,
+    /// the levels set by the lint level attributes of the items around the current one, innermost last
+    attr_lint_levels: Vec<HashMap<String, LintLevel>>,
+}
+
+impl DiagCtxt {
+    pub fn new(emitter: Box<dyn Emitter>) -> DiagCtxt {
+        DiagCtxt {
+            inner: RefCell::new(DiagCtxtInner {
+                emitter,
+                diagnostics: Vec::new(),
+                err_count: 0,
+                warn_count: 0,
+                lint_levels: HashMap::new(),
+                attr_lint_levels: Vec::new(),
+            }),
+        }
+    }
+
+    pub fn emit(&self, diag: Diagnostic) {
+        let mut inner = self.inner.borrow_mut();
+        match diag.level {
+            Level::Bug | Level::Error => inner.err_count += 1,
+            Level::Warning => inner.warn_count += 1,
+            Level::Note | Level::Help => {}
+        }
+        inner.emitter.emit_diagnostic(&diag);
+        inner.diagnostics.push(diag);
+    }
+
+    /// shorthand for emitting an error with a single primary location
+    pub fn err(&self, src_data: SrcData, message: impl Into<String>) {
+        self.emit(Diagnostic::error(message).with_primary(src_data, ""));
+    }
+
+    /// shorthand for emitting a warning with a single primary location
+    pub fn warn(&self, src_data: SrcData, message: impl Into<String>) {
+        self.emit(Diagnostic::warning(message).with_primary(src_data, ""));
+    }
+
+    pub fn set_lint_level(&self, name: impl Into<String>, level: LintLevel) {
+        self.inner.borrow_mut().lint_levels.insert(name.into(), level);
+    }
+
+    /// sets the levels of the lint level attributes of an item until [`DiagCtxt::pop_lint_levels`]
+    pub fn push_lint_levels(&self, levels: HashMap<String, LintLevel>) {
+        self.inner.borrow_mut().attr_lint_levels.push(levels);
+    }
+
+    pub fn pop_lint_levels(&self) {
+        self.inner.borrow_mut().attr_lint_levels.pop();
+    }
+
+    /// the level of a lint is the one set by the innermost lint level attribute, then the one set on
+    /// the command line, and its default level otherwise. At each of these places the level set for
+    /// its name comes before the one set for all lints
+    pub fn lint_level(&self, lint: &Lint) -> LintLevel {
+        self.set_lint_level_of(lint).unwrap_or(lint.default_level)
+    }
+
+    /// the level of a lint if it is set by an attribute or on the command line
+    fn set_lint_level_of(&self, lint: &Lint) -> Option<LintLevel> {
+        let inner = self.inner.borrow();
+        let level = inner
+            .attr_lint_levels
+            .iter()
+            .rev()
+            .chain([&inner.lint_levels])
+            .find_map(|levels| levels.get(lint.name).or_else(|| levels.get(lint::WARNINGS)))
+            .copied();
+        level
+    }
+
+    /// emits the diagnostic as a warning or an error depending on the level of the lint
+    pub fn emit_lint(&self, lint: &Lint, mut diag: Diagnostic) {
+        let set_level = self.set_lint_level_of(lint);
+        let level = set_level.unwrap_or(lint.default_level);
+        diag.level = match level {
+            LintLevel::Allow => return,
+            LintLevel::Warn => Level::Warning,
+            LintLevel::Deny => Level::Error,
+        };
+        if set_level.is_none() {
+            diag = diag.with_note(format!("`#[{}({})]` on by default", level, lint.name));
+        }
+        self.emit(diag);
+    }
+
+    pub fn err_count(&self) -> usize {
+        self.inner.borrow().err_count
+    }
+
+    pub fn warn_count(&self) -> usize {
+        self.inner.borrow().warn_count
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.err_count() > 0
+    }
+
+    /// all diagnostics which were emitted so far
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.inner.borrow().diagnostics.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use source_idx::{BytePos, FileName, SourceMap};
+
+    use super::*;
+
+    #[test]
+    fn render_human() {
+        let sm = Rc::new(SourceMap::new());
+        let file = sm.new_source_file(FileName::Anon("test".into()), "fn main() {\n    0b102\n}\n".into());
+        let src = |lo: u32, hi: u32| SrcData::with_root_ctxt(file.start_pos + BytePos(lo), file.start_pos + BytePos(hi));
+        let diag = Diagnostic::error("invalid digit for a base 2 literal")
+            .with_code(codes::E0002)
+            .with_primary(src(20, 21), "not a binary digit")
+            .with_secondary(src(16, 18), "base 2 because of this prefix")
+            .with_suggestion(src(16, 18), "use a decimal literal instead", "", Applicability::MaybeIncorrect);
+        let rendered = HumanEmitter::new(sm, Box::new(std::io::sink())).render(&diag);
+        assert_eq!(
+            rendered,
+            "error[E0002]: invalid digit for a base 2 literal
+ --> <test>:2:9
+  |
+2 |     0b102
+  |     -- base 2 because of this prefix
+  |         ^ not a binary digit
+  |
+help: use a decimal literal instead
+  |
+2 |     102
+  |     --
+  |
+"
+        );
+    }
+}
@@ -0,0 +1,67 @@
+//! Lints are warnings which can be allowed, warned about or denied by name
+//!
+//! the level of a lint is its default level, unless it is changed on the command line
+//! e.g. with `-A name` or by an attribute like `#[allow(name)]` for the item it is applied to,
+//! the name `warnings` changes the level of all lints at once
+
+use std::fmt;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum LintLevel {
+    /// the lint is not emitted
+    Allow,
+    /// the lint is emitted as a warning
+    Warn,
+    /// the lint is emitted as an error
+    Deny,
+}
+
+impl LintLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LintLevel::Allow => "allow",
+            LintLevel::Warn => "warn",
+            LintLevel::Deny => "deny",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<LintLevel> {
+        match name {
+            "allow" => Some(LintLevel::Allow),
+            "warn" => Some(LintLevel::Warn),
+            "deny" => Some(LintLevel::Deny),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug)]
+pub struct Lint {
+    /// the name used on the command line, in snake case e.g. `unknown_attributes`
+    pub name: &'static str,
+    pub default_level: LintLevel,
+    pub desc: &'static str,
+}
+
+/// the name which stands for all lints
+pub const WARNINGS: &str = "warnings";
+
+/// an attribute which is not built into the compiler, e.g. `#[frobnicate]`
+pub static UNKNOWN_ATTRIBUTES: Lint = Lint {
+    name: "unknown_attributes",
+    default_level: LintLevel::Warn,
+    desc: "detects attributes which are not built into the compiler",
+};
+
+/// all lints of the compiler
+pub const BUILTIN_LINTS: &[&Lint] = &[&UNKNOWN_ATTRIBUTES];
+
+pub fn find_lint(name: &str) -> Option<&'static Lint> {
+    BUILTIN_LINTS.iter().copied().find(|lint| lint.name == name)
+}
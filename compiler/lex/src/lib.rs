@@ -4,8 +4,8 @@ pub mod token;
 pub mod unescape;
 
 pub use cursor::Cursor;
-pub use token::{Token, TokenKind};
-pub use literal::{LiteralKind, Base};
+pub use token::{DocStyle, Token, TokenKind};
+pub use literal::{LiteralKind, Base, RawStrError};
 
 /// allows files to start with a shebang '#!/bin/slangc' which can be useful on Unix based systems
 /// though it is ignored by the compiler
@@ -15,6 +15,8 @@ pub fn strip_shebang(src: &str) -> Option<usize> {
             !matches!(
                 t,
                 TokenKind::Whitespace
+                    | TokenKind::LineComment { doc_style: None }
+                    | TokenKind::BlockComment { doc_style: None, .. }
             )
         });
         // the next token is an `[` it can be an crate attribut and is valid code
@@ -27,6 +29,17 @@ pub fn strip_shebang(src: &str) -> Option<usize> {
     None
 }
 
+/// lexes the raw string at the start of `input` again to find out why it is invalid,
+/// `prefix_len` is the length of the prefix before the `#`s e.g. `2` for `br`
+pub fn validate_raw_str(input: &str, prefix_len: u32) -> Result<(), RawStrError> {
+    debug_assert!(!input.is_empty());
+    let mut cursor = Cursor::new(input);
+    for _ in 0..prefix_len {
+        cursor.take_char();
+    }
+    cursor.raw_double_quoted_string(prefix_len).map(|_| ())
+}
+
 pub fn tokenize(src: &str) -> impl Iterator<Item = Token> + '_ {
     let mut cursor = Cursor::new(src);
     std::iter::from_fn(move || {
@@ -49,4 +62,104 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn error_tokens() {
+        use crate::{Base, LiteralKind, TokenKind};
+        let kinds = |src| crate::tokenize(src).map(|t| t.kind).collect::<Vec<_>>();
+
+        let lit = |kind, suffix_start| TokenKind::Literal { kind, suffix_start };
+        assert_eq!(kinds("\"abc"), [lit(LiteralKind::Str { terminated: false }, 4)]);
+        assert_eq!(kinds("'a"), [lit(LiteralKind::Char { terminated: false }, 2)]);
+        assert_eq!(kinds("0b"), [lit(LiteralKind::Int { base: Base::Binary, empty_int: true }, 2)]);
+        assert_eq!(kinds("1e"), [lit(LiteralKind::Float { base: Base::Decimal, empty_exponent: true }, 2)]);
+        assert_eq!(kinds("x🦀"), [TokenKind::InvalidIdent]);
+        assert_eq!(kinds("x#")[0], TokenKind::UnknownPrefix);
+        assert_eq!(kinds("/* /* */"), [TokenKind::BlockComment { doc_style: None, terminated: false }]);
+    }
+
+    #[test]
+    fn number_bases() {
+        use crate::{Base, LiteralKind, TokenKind};
+        let kind = |src| match crate::tokenize(src).next().map(|t| t.kind) {
+            Some(TokenKind::Literal { kind, suffix_start }) => {
+                assert_eq!(suffix_start as usize, src.len(), "{:?} is not a single literal", src);
+                kind
+            }
+            kind => panic!("expected a literal, found {:?}", kind),
+        };
+        let int = |base| LiteralKind::Int { base, empty_int: false };
+        let float = |base| LiteralKind::Float { base, empty_exponent: false };
+
+        assert_eq!(kind("0xff"), int(Base::Hex));
+        assert_eq!(kind("0xDEAD_beef"), int(Base::Hex));
+        assert_eq!(kind("0o17"), int(Base::Octal));
+        assert_eq!(kind("0b1010"), int(Base::Binary));
+        assert_eq!(kind("1_000"), int(Base::Decimal));
+        // the invalid digits are part of the literal
+        assert_eq!(kind("0b102"), int(Base::Binary));
+        assert_eq!(kind("0o19"), int(Base::Octal));
+        // `e` is a hex digit and not an exponent
+        assert_eq!(kind("0x1e5"), int(Base::Hex));
+        assert_eq!(kind("0x1.5"), float(Base::Hex));
+        assert_eq!(kind("0b1e5"), float(Base::Binary));
+        assert_eq!(kind("1e5"), float(Base::Decimal));
+        assert_eq!(kind("0x"), LiteralKind::Int { base: Base::Hex, empty_int: true });
+    }
+
+    #[test]
+    fn literal_suffixes() {
+        use crate::TokenKind;
+        let suffix = |src: &str| match crate::tokenize(src).next().map(|t| t.kind) {
+            Some(TokenKind::Literal { suffix_start, .. }) => src[suffix_start as usize..].to_string(),
+            kind => panic!("expected a literal, found {:?}", kind),
+        };
+
+        assert_eq!(suffix("12u8"), "u8");
+        assert_eq!(suffix("1foo"), "foo");
+        assert_eq!(suffix("1_000_i64"), "i64");
+        assert_eq!(suffix("2.5f32"), "f32");
+        assert_eq!(suffix("1e5f64"), "f64");
+        // hex digits are part of the number
+        assert_eq!(suffix("0x1f32"), "");
+        assert_eq!(suffix("0x1_u8"), "u8");
+        assert_eq!(suffix("0b1f32"), "f32");
+        assert_eq!(suffix("\"s\"x"), "x");
+        assert_eq!(suffix("'c'_y"), "_y");
+        assert_eq!(suffix("b\"s\"suffix"), "suffix");
+        assert_eq!(suffix("r#\"s\"#x"), "x");
+        assert_eq!(suffix("12"), "");
+    }
+
+    #[test]
+    fn literal_prefixes() {
+        use crate::{LiteralKind, TokenKind};
+        let kinds = |src| {
+            crate::tokenize(src)
+                .filter_map(|t| match t.kind {
+                    TokenKind::Literal { kind, .. } => Some(kind),
+                    TokenKind::Whitespace => None,
+                    kind => panic!("unexpected token {:?}", kind),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            kinds(r###"b'a' b"a" c"a" r"a" r#"a"# br##"a"## cr"a""###),
+            [
+                LiteralKind::Byte { terminated: true },
+                LiteralKind::ByteStr { terminated: true },
+                LiteralKind::CStr { terminated: true },
+                LiteralKind::RawStr { n_hashes: Some(0) },
+                LiteralKind::RawStr { n_hashes: Some(1) },
+                LiteralKind::RawByteStr { n_hashes: Some(2) },
+                LiteralKind::RawCStr { n_hashes: Some(0) },
+            ]
+        );
+        assert_eq!(kinds(r###"r#"a""###), [LiteralKind::RawStr { n_hashes: None }]);
+        assert_eq!(
+            crate::validate_raw_str(r###"r##"a"#"###, 1),
+            Err(crate::RawStrError::NoTerminator { expected: 2, found: 1, possible_terminator_offset: Some(6) })
+        );
+    }
 }
@@ -1,3 +1,4 @@
+/// The base of a number literal, given by its prefix `0b`, `0o` or `0x`
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Base {
     Binary = 2,
@@ -6,10 +7,66 @@ pub enum Base {
     Hex = 16,
 }
 
+impl Base {
+    pub fn radix(self) -> u32 {
+        self as u32
+    }
+
+    /// the length of the prefix in front of the digits e.g. `0x`
+    pub fn prefix_len(self) -> usize {
+        match self {
+            Base::Decimal => 0,
+            Base::Binary | Base::Octal | Base::Hex => 2,
+        }
+    }
+
+    pub fn is_digit(self, c: char) -> bool {
+        c.is_digit(self.radix())
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum LiteralKind {
-    Int { base: Base },
-    Float { base: Base },
-    Char,
-    Str,
+    /// `12`, `0b1010`, `0x` (`empty_int` if there are no digits after the base prefix)
+    ///
+    /// binary and octal literals contain all decimal digits e.g. `0b102`,
+    /// so that the invalid ones can be reported. `e` is a hex digit,
+    /// so `0x1e5` is the int `0x1e5` and not the float `0x1` times `10^5`
+    Int { base: Base, empty_int: bool },
+    /// `1.0`, `1e5`, `1e` (`empty_exponent` if there are no digits after the `e`)
+    ///
+    /// the base is only known to report floats which are not decimal e.g. `0x1.5` or `0b1e5`
+    Float { base: Base, empty_exponent: bool },
+    /// `'a'`, `'b` (unterminated)
+    Char { terminated: bool },
+    /// `b'a'`, `b'a` (unterminated)
+    Byte { terminated: bool },
+    /// `"abc"`, `"abc` (unterminated)
+    Str { terminated: bool },
+    /// `b"abc"`, `b"abc` (unterminated)
+    ByteStr { terminated: bool },
+    /// `c"abc"`, `c"abc` (unterminated)
+    CStr { terminated: bool },
+    /// `r"abc"`, `r#"abc"#`, `r###"abc"#` (invalid, `n_hashes` is `None`)
+    RawStr { n_hashes: Option<u8> },
+    /// `br"abc"`, `br#"abc"#`, `br###"abc"#` (invalid, `n_hashes` is `None`)
+    RawByteStr { n_hashes: Option<u8> },
+    /// `cr"abc"`, `cr#"abc"#`, `cr###"abc"#` (invalid, `n_hashes` is `None`)
+    RawCStr { n_hashes: Option<u8> },
+}
+
+/// Why a raw string could not be lexed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RawStrError {
+    /// something other than `#` or `"` after the `r` e.g. `r#~"abc"#`
+    InvalidStarter { bad_char: char },
+    /// the closing `"` followed by enough `#`s was not found
+    NoTerminator {
+        expected: u32,
+        found: u32,
+        /// offset of the closing `"` with the most `#`s, to suggest adding the missing ones
+        possible_terminator_offset: Option<u32>,
+    },
+    /// more than 255 `#`s
+    TooManyDelimiters { found: u32 },
 }
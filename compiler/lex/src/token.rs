@@ -1,6 +1,6 @@
 use crate::{
     cursor::Cursor,
-    literal::{Base, LiteralKind},
+    literal::{Base, LiteralKind, RawStrError},
 };
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -15,18 +15,34 @@ impl Token {
     }
 }
 
+/// the item a doc comment belongs to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DocStyle {
+    /// `///` and `/** */` document the following item
+    Outer,
+    /// `//!` and `/*! */` document the enclosing item
+    Inner,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TokenKind {
-    /// `// comment`
-    LineComment,
-    /// `/* block comment */`
-    BlockComment,
+    /// `// comment`, `/// outer doc` or `//! inner doc`
+    LineComment { doc_style: Option<DocStyle> },
+    /// `/* block comment */`, `/** outer doc */` or `/*! inner doc */`
+    BlockComment { doc_style: Option<DocStyle>, terminated: bool },
     Whitespace,
     /// any identifier or keyword
     Ident,
+    /// an identifier which contains invalid characters e.g. emoji `🦀ident`
+    InvalidIdent,
+    /// an identifier directly followed by a `#`, `"` or `'` which is not a known literal prefix,
+    /// only the identifier is part of the token e.g. `prefix` in `prefix"text"`
+    UnknownPrefix,
     /// literal value e.g. `12u8` , `1.0e-4`, `b"test"`
     Literal {
         kind: LiteralKind,
+        /// offset of the suffix inside of the token, equal to the length if there is no suffix
+        suffix_start: u32,
     },
     /// `;`
     Semi,
@@ -132,6 +148,7 @@ pub fn is_id_countinue(c: char) -> bool {
 impl<'a> Cursor<'a> {
     // advances curser and returns the token
     pub fn next_token(&mut self) -> Token {
+        use unicode_properties::UnicodeEmoji;
         use TokenKind::*;
         let Some(first_char) = self.take_char() else {
             return Token::new(TokenKind::Eof, 0);
@@ -144,12 +161,38 @@ impl<'a> Cursor<'a> {
             },
             c if is_whitespace(c) => self.whitespace(),
 
+            // byte literals, byte strings and raw byte strings
+            'b' => self.c_or_byte_string(
+                |terminated| LiteralKind::ByteStr { terminated },
+                |n_hashes| LiteralKind::RawByteStr { n_hashes },
+                Some(|terminated| LiteralKind::Byte { terminated }),
+            ),
+
+            // C strings and raw C strings
+            'c' => self.c_or_byte_string(
+                |terminated| LiteralKind::CStr { terminated },
+                |n_hashes| LiteralKind::RawCStr { n_hashes },
+                None,
+            ),
+
+            // raw strings
+            'r' => match self.peek_first_char() {
+                '#' | '"' => {
+                    let res = self.raw_double_quoted_string(1);
+                    self.literal(LiteralKind::RawStr { n_hashes: res.ok() })
+                }
+                _ => self.ident_or_unknown_prefix(),
+            },
+
             // handle prefixes here
-            c if is_id_start(c) => self.ident_or_unhandled_prefix(),
+            c if is_id_start(c) => self.ident_or_unknown_prefix(),
+
+            // an identifier which starts with an emoji
+            c if !c.is_ascii() && c.is_emoji_char() => self.invalid_ident(),
 
             c @ '0'..='9' => {
                 let literal_kind = self.number(c);
-                TokenKind::Literal { kind: literal_kind }
+                self.literal(literal_kind)
             }
 
             // One-symbol tokens
@@ -183,12 +226,8 @@ impl<'a> Cursor<'a> {
             '\'' => self.char(),
 
             '"' => {
-                if !self.double_quoted_string() {
-                    todo!("handle non-terminated string")
-                }
-                Literal {
-                    kind: LiteralKind::Str,
-                }
+                let terminated = self.double_quoted_string();
+                self.literal(LiteralKind::Str { terminated })
             }
             _ => Unknown,
         };
@@ -197,15 +236,37 @@ impl<'a> Cursor<'a> {
         res
     }
 
+    /// finishes a literal by taking its suffix e.g. `u8` in `12u8`
+    fn literal(&mut self, kind: LiteralKind) -> TokenKind {
+        let suffix_start = self.pos_in_token();
+        if is_id_start(self.peek_first_char()) {
+            self.take_char();
+            self.take_while(is_id_countinue);
+        }
+        TokenKind::Literal { kind, suffix_start }
+    }
+
     fn line_comment(&mut self) -> TokenKind {
         self.take_char();
 
+        let doc_style = match self.peek_first_char() {
+            '!' => Some(DocStyle::Inner),
+            // `////` is a plain comment again
+            '/' if self.peek_second_char() != '/' => Some(DocStyle::Outer),
+            _ => None,
+        };
         self.take_while(|c| c != '\n');
-        TokenKind::LineComment
+        TokenKind::LineComment { doc_style }
     }
 
     fn block_comment(&mut self) -> TokenKind {
         self.take_char();
+        let doc_style = match self.peek_first_char() {
+            '!' => Some(DocStyle::Inner),
+            // `/***` and the empty comment `/**/` are plain comments
+            '*' if !matches!(self.peek_second_char(), '*' | '/') => Some(DocStyle::Outer),
+            _ => None,
+        };
         let mut depth = 1usize;
         while let Some(c) = self.take_char() {
             match c {
@@ -224,7 +285,7 @@ impl<'a> Cursor<'a> {
                 _ => {}
             }
         }
-        TokenKind::BlockComment
+        TokenKind::BlockComment { doc_style, terminated: depth == 0 }
     }
 
     fn whitespace(&mut self) -> TokenKind {
@@ -232,18 +293,113 @@ impl<'a> Cursor<'a> {
         TokenKind::Whitespace
     }
 
-    fn ident_or_unhandled_prefix(&mut self) -> TokenKind {
+    fn ident_or_unknown_prefix(&mut self) -> TokenKind {
         use unicode_properties::UnicodeEmoji;
 
         self.take_while(is_id_countinue);
 
         match self.peek_first_char() {
-            '#' | '"' | '\'' => todo!("handle unkonwn prefix"),
-            c if !c.is_ascii() && c.is_emoji_char() => todo!("handle invalid idents"),
+            '#' | '"' | '\'' => TokenKind::UnknownPrefix,
+            c if !c.is_ascii() && c.is_emoji_char() => self.invalid_ident(),
             _ => TokenKind::Ident,
         }
     }
 
+    /// takes the rest of an identifier which contains emoji
+    fn invalid_ident(&mut self) -> TokenKind {
+        use unicode_properties::UnicodeEmoji;
+
+        // the zero width joiner is used to combine emoji
+        const ZERO_WIDTH_JOINER: char = '\u{200d}';
+        self.take_while(|c| {
+            is_id_countinue(c) || (!c.is_ascii() && c.is_emoji_char()) || c == ZERO_WIDTH_JOINER
+        });
+        TokenKind::InvalidIdent
+    }
+
+    fn c_or_byte_string(
+        &mut self,
+        mk_kind: impl FnOnce(bool) -> LiteralKind,
+        mk_kind_raw: impl FnOnce(Option<u8>) -> LiteralKind,
+        single_quoted: Option<fn(bool) -> LiteralKind>,
+    ) -> TokenKind {
+        let kind = match (self.peek_first_char(), self.peek_second_char(), single_quoted) {
+            ('\'', _, Some(mk_kind)) => {
+                self.take_char();
+                let terminated = self.single_queted_string();
+                mk_kind(terminated)
+            }
+            ('"', _, _) => {
+                self.take_char();
+                let terminated = self.double_quoted_string();
+                mk_kind(terminated)
+            }
+            ('r', '"', _) | ('r', '#', _) => {
+                self.take_char();
+                let res = self.raw_double_quoted_string(2);
+                mk_kind_raw(res.ok())
+            }
+            _ => return self.ident_or_unknown_prefix(),
+        };
+        self.literal(kind)
+    }
+
+    /// lexes the `#`s and the quoted string of a raw string
+    /// and returns the number of `#`s on both sides
+    pub(crate) fn raw_double_quoted_string(&mut self, prefix_len: u32) -> Result<u8, RawStrError> {
+        let n_hashes = self.raw_string_unvalidated(prefix_len)?;
+        u8::try_from(n_hashes).map_err(|_| RawStrError::TooManyDelimiters { found: n_hashes })
+    }
+
+    fn raw_string_unvalidated(&mut self, prefix_len: u32) -> Result<u32, RawStrError> {
+        let start_pos = self.pos_in_token();
+        let mut possible_terminator_offset = None;
+        let mut max_hashes = 0;
+
+        let mut n_start_hashes = 0;
+        while self.peek_first_char() == '#' {
+            n_start_hashes += 1;
+            self.take_char();
+        }
+
+        match self.take_char() {
+            Some('"') => {}
+            c => {
+                let bad_char = c.unwrap_or(crate::cursor::EOF_CHAR);
+                return Err(RawStrError::InvalidStarter { bad_char });
+            }
+        }
+
+        loop {
+            self.take_while(|c| c != '"');
+            if self.is_eof() {
+                return Err(RawStrError::NoTerminator {
+                    expected: n_start_hashes,
+                    found: max_hashes,
+                    possible_terminator_offset,
+                });
+            }
+            // the closing `"`
+            self.take_char();
+
+            let mut n_end_hashes = 0;
+            while self.peek_first_char() == '#' && n_end_hashes < n_start_hashes {
+                n_end_hashes += 1;
+                self.take_char();
+            }
+
+            if n_end_hashes == n_start_hashes {
+                return Ok(n_start_hashes);
+            } else if n_end_hashes > max_hashes {
+                // keep track of the possible terminator for better diagnostics
+                possible_terminator_offset =
+                    Some(self.pos_in_token() - start_pos - n_end_hashes + prefix_len);
+                max_hashes = n_end_hashes;
+            }
+        }
+    }
+
+    /// binary and octal digits are checked later, so all decimal digits are taken for them
     fn number(&mut self, first_digit: char) -> LiteralKind {
         let mut base = Base::Decimal;
         if first_digit == '0' {
@@ -252,21 +408,22 @@ impl<'a> Cursor<'a> {
                     base = Base::Binary;
                     self.take_char();
                     if !self.take_decimal_digits() {
-                        todo!("handle empty int")
+                        return LiteralKind::Int { base, empty_int: true };
                     }
                 }
                 'o' => {
                     base = Base::Octal;
                     self.take_char();
                     if !self.take_decimal_digits() {
-                        todo!("handle empty int")
+                        return LiteralKind::Int { base, empty_int: true };
                     }
                 }
+                // `e` is a hex digit, so an exponent can never follow
                 'x' => {
-                    base = Base::Octal;
+                    base = Base::Hex;
                     self.take_char();
                     if !self.take_hexadecimal_digits() {
-                        todo!("handle empty int")
+                        return LiteralKind::Int { base, empty_int: true };
                     }
                 }
                 // Not a base prefix, take the other digits
@@ -277,7 +434,7 @@ impl<'a> Cursor<'a> {
                 '.' | 'e' | 'E' => {}
 
                 // just a 0
-                _ => return LiteralKind::Int { base },
+                _ => return LiteralKind::Int { base, empty_int: false },
             }
         } else {
             self.take_decimal_digits();
@@ -288,39 +445,32 @@ impl<'a> Cursor<'a> {
             // Attention: do not parse as float if it could be a range or a filed/member access
             '.' if self.peek_second_char() != '.' && !is_id_start(self.peek_second_char()) => {
                 self.take_char();
+                let mut empty_exponent = false;
                 if self.peek_first_char().is_ascii_digit() {
                     // has decimal digits after `.`
                     self.take_decimal_digits();
                     match self.peek_first_char() {
                         'e' | 'E' => {
                             self.take_char();
-                            if !self.take_float_exponent() {
-                                todo!("handle empty float exponent")
-                            }
+                            empty_exponent = !self.take_float_exponent();
                         }
                         _ => {}
                     }
                 }
-                LiteralKind::Float { base }
+                LiteralKind::Float { base, empty_exponent }
             }
             'e' | 'E' => {
                 self.take_char();
-                if !self.take_float_exponent() {
-                    todo!("handle empty float exponent")
-                }
-                LiteralKind::Float { base }
+                let empty_exponent = !self.take_float_exponent();
+                LiteralKind::Float { base, empty_exponent }
             }
-            _ => LiteralKind::Int { base },
+            _ => LiteralKind::Int { base, empty_int: false },
         }
     }
 
     fn char(&mut self) -> TokenKind {
-        if !self.single_queted_string() {
-            todo!("Handle non-termitated char")
-        }
-        TokenKind::Literal {
-            kind: LiteralKind::Char,
-        }
+        let terminated = self.single_queted_string();
+        self.literal(LiteralKind::Char { terminated })
     }
 
     fn single_queted_string(&mut self) -> bool {
@@ -1,12 +1,61 @@
 use std::{ops::Range, str::Chars};
 
-#[derive(Clone, Copy, Debug)]
+/// The kind of literal which is unescaped
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Mode {
+    /// `'a'`
     Char,
+    /// `b'a'`
+    Byte,
+    /// `"abc"`
     Str,
+    /// `b"abc"`
+    ByteStr,
+    /// `r"abc"`, `r#"abc"#`
+    RawStr,
+    /// `br"abc"`, `br#"abc"#`
+    RawByteStr,
+    /// `c"abc"`
+    CStr,
+    /// `cr"abc"`, `cr#"abc"#`
+    RawCStr,
 }
 
-#[derive(Debug)]
+impl Mode {
+    pub fn in_double_quotes(self) -> bool {
+        !matches!(self, Mode::Char | Mode::Byte)
+    }
+
+    /// true if the literal allows `\x80` to `\xff` escapes
+    pub fn allow_high_bytes(self) -> bool {
+        matches!(self, Mode::Byte | Mode::ByteStr | Mode::CStr)
+    }
+
+    /// true if the literal may contain non-ASCII characters
+    pub fn allow_unicode_chars(self) -> bool {
+        !matches!(self, Mode::Byte | Mode::ByteStr | Mode::RawByteStr)
+    }
+
+    /// true if the literal allows `\u{...}` escapes
+    pub fn allow_unicode_escapes(self) -> bool {
+        matches!(self, Mode::Char | Mode::Str | Mode::CStr)
+    }
+
+    pub fn is_raw(self) -> bool {
+        matches!(self, Mode::RawStr | Mode::RawByteStr | Mode::RawCStr)
+    }
+
+    /// the prefix of the literal without the `r` of raw literals
+    pub fn prefix_noraw(self) -> &'static str {
+        match self {
+            Mode::Char | Mode::Str | Mode::RawStr => "",
+            Mode::Byte | Mode::ByteStr | Mode::RawByteStr => "b",
+            Mode::CStr | Mode::RawCStr => "c",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EscapeError {
     ZeroChars,
     EscapeOnlyChar,
@@ -15,24 +64,98 @@ pub enum EscapeError {
     LonlyBackSlash,
     TooShortHexEscape,
     InvalidCharInHexEscape,
+    /// `\x80` to `\xff` in a literal which does not allow high bytes
+    OutOfRangeHexEscape,
     InvalidEscape,
+    /// `\u` without a `{` e.g. `\u1F600`
     NoBraceInUnicodeEscape,
+    /// `\u{1F600` without the closing `}`
+    UnclosedUnicodeEscape,
+    /// `\u{}`
+    EmptyUnicodeEscape,
+    /// `\u{_1F600}`
+    LeadingUnderscoreUnicodeEscape,
+    /// `\u{1F6x0}`
+    InvalidCharInUnicodeEscape,
+    /// more than 6 hex digits e.g. `\u{0001F600}`
+    OverlongUnicodeEscape,
+    /// `\u{D800}` to `\u{DFFF}`
+    LoneSurrogateUnicodeEscape,
+    /// a value above `\u{10FFFF}`
+    OutOfRangeUnicodeEscape,
+    /// `\u{...}` in a byte or byte string literal
+    UnicodeEscapeInByte,
     NonAsciiCharInByte,
+    /// `\0` or a raw nul character inside of a C string
+    NulInCStr,
     MultipleSkippedLinesWarning,
 }
 
+impl EscapeError {
+    pub fn is_fatal(&self) -> bool {
+        !matches!(self, EscapeError::MultipleSkippedLinesWarning)
+    }
+}
+
+/// A unit of a C string, which can be either a char or a single byte above `0x7f`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MixedUnit {
+    Char(char),
+    HighByte(u8),
+}
+
+impl From<char> for MixedUnit {
+    fn from(c: char) -> Self {
+        MixedUnit::Char(c)
+    }
+}
+
+impl From<u8> for MixedUnit {
+    fn from(byte: u8) -> Self {
+        if byte.is_ascii() {
+            MixedUnit::Char(byte as char)
+        } else {
+            MixedUnit::HighByte(byte)
+        }
+    }
+}
+
+/// unescapes the content of every literal except C strings, see [`unescape_mixed`] for those
+///
+/// bytes are returned as the char with the same value
 pub fn unescape_unicode(
     s: &str,
     mode: Mode,
     cb: &mut impl FnMut(Range<usize>, Result<char, EscapeError>),
 ) {
     match mode {
-        Mode::Char => {
+        Mode::Char | Mode::Byte => {
             let mut chars = s.chars();
             let res = unescape_char(&mut chars, mode);
             cb(0..(s.len() - chars.as_str().len()), res);
         }
-        Mode::Str => unescape_str(s, Mode::Str, cb),
+        Mode::Str | Mode::ByteStr => unescape_str(s, mode, cb),
+        Mode::RawStr | Mode::RawByteStr => unescape_raw_str(s, mode, cb),
+        Mode::CStr | Mode::RawCStr => panic!("use `unescape_mixed` for C strings"),
+    }
+}
+
+/// unescapes the content of C strings
+pub fn unescape_mixed(
+    s: &str,
+    mode: Mode,
+    cb: &mut impl FnMut(Range<usize>, Result<MixedUnit, EscapeError>),
+) {
+    let mut cb = |range, res: Result<MixedUnit, EscapeError>| {
+        cb(range, res.and_then(|unit| match unit {
+            MixedUnit::Char('\0') => Err(EscapeError::NulInCStr),
+            unit => Ok(unit),
+        }))
+    };
+    match mode {
+        Mode::CStr => unescape_str(s, mode, &mut cb),
+        Mode::RawCStr => unescape_raw_str(s, mode, &mut cb),
+        _ => panic!("use `unescape_unicode` for non C strings"),
     }
 }
 
@@ -42,7 +165,7 @@ fn unescape_char(chars: &mut Chars, mode: Mode) -> Result<char, EscapeError> {
         '\\' => scan_escape(chars, mode),
         '\n' | '\t' | '\'' => Err(EscapeError::EscapeOnlyChar),
         '\r' => Err(EscapeError::RawCarrigeReturn),
-        _ => ascii_check(c, true),
+        _ => ascii_check(c, mode.allow_unicode_chars()),
     }?;
     if chars.next().is_some() {
         return Err(EscapeError::MoreThanOneChar);
@@ -75,11 +198,14 @@ fn scan_escape<T: From<char> + From<u8>>(chars: &mut Chars, mode: Mode) -> Resul
             let lo = lo.to_digit(16).ok_or(EscapeError::InvalidCharInHexEscape)?;
 
             let value = ((hi << 4) + lo) as u8;
+            if !mode.allow_high_bytes() && !value.is_ascii() {
+                return Err(EscapeError::OutOfRangeHexEscape);
+            }
 
             return Ok(T::from(value));
         }
         'u' => {
-            return scan_unicode(chars, true);
+            return scan_unicode(chars, mode.allow_unicode_escapes()).map(T::from);
         }
         _ => return Err(EscapeError::InvalidEscape),
     };
@@ -87,15 +213,49 @@ fn scan_escape<T: From<char> + From<u8>>(chars: &mut Chars, mode: Mode) -> Resul
     Ok(T::from(res))
 }
 
-fn scan_unicode<T: From<char> + From<u8>>(
-    chars: &mut Chars,
-    allow_unicode: bool,
-) -> Result<T, EscapeError> {
-    if chars.next() != Some('{') {
+/// scans the `{...}` part of an unicode escape, the whole escape is always consumed
+/// if it is closed, so that the callbacks receive the range of the complete escape
+fn scan_unicode(chars: &mut Chars, allow_unicode_escapes: bool) -> Result<char, EscapeError> {
+    if chars.clone().next() != Some('{') {
         return Err(EscapeError::NoBraceInUnicodeEscape);
     }
+    chars.next();
 
-    todo!("unicode escape not yet implemented")
+    let mut n_digits = 1;
+    let mut value: u32 = match chars.next().ok_or(EscapeError::UnclosedUnicodeEscape)? {
+        '_' => return Err(EscapeError::LeadingUnderscoreUnicodeEscape),
+        '}' => return Err(EscapeError::EmptyUnicodeEscape),
+        c => c.to_digit(16).ok_or(EscapeError::InvalidCharInUnicodeEscape)?,
+    };
+
+    loop {
+        match chars.next() {
+            None => return Err(EscapeError::UnclosedUnicodeEscape),
+            Some('_') => continue,
+            Some('}') => {
+                if n_digits > 6 {
+                    return Err(EscapeError::OverlongUnicodeEscape);
+                }
+                if !allow_unicode_escapes {
+                    return Err(EscapeError::UnicodeEscapeInByte);
+                }
+                return char::from_u32(value).ok_or(if value > 0x10FFFF {
+                    EscapeError::OutOfRangeUnicodeEscape
+                } else {
+                    EscapeError::LoneSurrogateUnicodeEscape
+                });
+            }
+            Some(c) => {
+                let digit = c.to_digit(16).ok_or(EscapeError::InvalidCharInUnicodeEscape)?;
+                n_digits += 1;
+                if n_digits > 6 {
+                    // keep going to find the closing brace, the value can not be valid anymore
+                    continue;
+                }
+                value = value * 16 + digit;
+            }
+        }
+    }
 }
 
 fn unescape_str<T: From<char> + From<u8>>(
@@ -117,7 +277,26 @@ fn unescape_str<T: From<char> + From<u8>>(
             },
             '"' => Err(EscapeError::EscapeOnlyChar),
             '\r' => Err(EscapeError::RawCarrigeReturn),
-            _ => ascii_check(c, true).map(T::from)
+            _ => ascii_check(c, mode.allow_unicode_chars()).map(T::from)
+        };
+        let end = s.len() - chars.as_str().len();
+        cb(start..end, res);
+    }
+}
+
+/// raw strings do not have any escapes, only a bare `\r` is not allowed
+fn unescape_raw_str<T: From<char> + From<u8>>(
+    s: &str,
+    mode: Mode,
+    cb: &mut impl FnMut(Range<usize>, Result<T, EscapeError>),
+) {
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        let start = s.len() - chars.as_str().len() - c.len_utf8();
+        let res = match c {
+            '\r' => Err(EscapeError::RawCarrigeReturn),
+            _ => ascii_check(c, mode.allow_unicode_chars()).map(T::from),
         };
         let end = s.len() - chars.as_str().len();
         cb(start..end, res);
@@ -132,7 +311,7 @@ fn skip_ascii_whitespace(
     let tail = chars.as_str();
     let first_non_space = tail
         .bytes()
-        .position(|b| b.is_ascii_whitespace())
+        .position(|b| !b.is_ascii_whitespace())
         .unwrap_or(tail.len());
     if tail[1..first_non_space].contains('\n') {
         let end = start + first_non_space + 1;
@@ -141,3 +320,32 @@ fn skip_ascii_whitespace(
     let tail = &tail[first_non_space..];
     *chars = tail.chars();
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn unescape(s: &str, mode: Mode) -> Vec<(Range<usize>, Result<char, EscapeError>)> {
+        let mut res = Vec::new();
+        unescape_unicode(s, mode, &mut |range, c| res.push((range, c)));
+        res
+    }
+
+    #[test]
+    fn unicode_escapes() {
+        assert_eq!(unescape(r"\u{1F600}", Mode::Char), [(0..9, Ok('😀'))]);
+        assert_eq!(unescape(r"\u{1_f_6_0_0}", Mode::Char), [(0..13, Ok('😀'))]);
+        assert_eq!(unescape(r"a\u{41}", Mode::Str), [(0..1, Ok('a')), (1..7, Ok('A'))]);
+
+        let err = |s, mode| unescape(s, mode).into_iter().find(|(_, res)| res.is_err()).unwrap();
+        assert_eq!(err(r"\u41", Mode::Str), (0..2, Err(EscapeError::NoBraceInUnicodeEscape)));
+        assert_eq!(err(r"\u{41", Mode::Str), (0..5, Err(EscapeError::UnclosedUnicodeEscape)));
+        assert_eq!(err(r"\u{}", Mode::Str), (0..4, Err(EscapeError::EmptyUnicodeEscape)));
+        assert_eq!(err(r"\u{_41}", Mode::Str), (0..4, Err(EscapeError::LeadingUnderscoreUnicodeEscape)));
+        assert_eq!(err(r"\u{4x}", Mode::Str), (0..5, Err(EscapeError::InvalidCharInUnicodeEscape)));
+        assert_eq!(err(r"\u{0000041}", Mode::Str), (0..11, Err(EscapeError::OverlongUnicodeEscape)));
+        assert_eq!(err(r"\u{D800}", Mode::Str), (0..8, Err(EscapeError::LoneSurrogateUnicodeEscape)));
+        assert_eq!(err(r"\u{110000}", Mode::Str), (0..10, Err(EscapeError::OutOfRangeUnicodeEscape)));
+        assert_eq!(err(r"\u{41}", Mode::ByteStr), (0..6, Err(EscapeError::UnicodeEscapeInByte)));
+    }
+}
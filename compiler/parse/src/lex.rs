@@ -1,20 +1,31 @@
 use ast::{
-    token::{LitKind, Token, TokenKind},
+    ast::AttrStyle,
+    token::{CommentKind, LitKind, Token, TokenKind},
     tokenstream::TokenStream,
 };
+use diagnostics::{codes, Applicability, DiagCtxt, Diagnostic};
 use lex::{unescape::Mode, Cursor};
-use source_idx::{BytePos, SrcData, Symbol};
+use source_idx::{sym, BytePos, SrcData, Symbol};
 
-use crate::tokentrees;
+use crate::{tokentrees, ParseSess};
+
+#[cfg(test)]
+mod tests;
+mod unescape_error_reporting;
 
 pub struct StringReader<'a> {
+    psess: &'a ParseSess,
     cursor: lex::Cursor<'a>,
     src: &'a str,
     pos: BytePos,
     start_pos: BytePos,
 }
 
-pub fn parse_token_trees(mut source: &str, mut start_pos: BytePos) -> Option<TokenStream> {
+pub fn parse_token_trees(
+    psess: &ParseSess,
+    mut source: &str,
+    mut start_pos: BytePos,
+) -> TokenStream {
     if let Some(shebang_len) = lex::strip_shebang(source) {
         source = &source[shebang_len..];
         start_pos = start_pos + BytePos::from_usize(shebang_len);
@@ -23,14 +34,13 @@ pub fn parse_token_trees(mut source: &str, mut start_pos: BytePos) -> Option<Tok
     let cursor = Cursor::new(source);
 
     let string_reader = StringReader {
+        psess,
         cursor,
         src: source,
         pos: start_pos,
         start_pos,
     };
-    let (stream, res) = tokentrees::TokenTreesReader::parse_all_token_trees(string_reader);
-    res?;
-    Some(stream)
+    tokentrees::TokenTreesReader::parse_all_token_trees(string_reader)
 }
 
 impl<'a> StringReader<'a> {
@@ -43,17 +53,74 @@ impl<'a> StringReader<'a> {
             self.pos = self.pos + BytePos(token.len);
 
             let kind = match token.kind {
-                lex::TokenKind::LineComment => TokenKind::Comment(ast::token::CommentType::Line),
-                lex::TokenKind::BlockComment => TokenKind::Comment(ast::token::CommentType::Block),
+                lex::TokenKind::LineComment { doc_style } => match doc_style {
+                    Some(style) => {
+                        // the text behind `///` or `//!`
+                        let text = self.str_from_to(start + BytePos(3), self.pos);
+                        TokenKind::DocComment(CommentKind::Line, attr_style(style), Symbol::get_or_store(text))
+                    }
+                    None => TokenKind::Comment(CommentKind::Line),
+                },
+                lex::TokenKind::BlockComment { doc_style, terminated } => {
+                    if !terminated {
+                        self.dcx().emit(
+                            Diagnostic::error("unterminated block comment")
+                                .with_code(codes::E0005)
+                                .with_primary(self.make_src_data(start, start + BytePos(2)), ""),
+                        );
+                    }
+                    match doc_style {
+                        Some(style) => {
+                            // the text between `/**` or `/*!` and `*/`
+                            let end = if terminated { self.pos - BytePos(2) } else { self.pos };
+                            let text = self.str_from_to(start + BytePos(3), end);
+                            TokenKind::DocComment(CommentKind::Block, attr_style(style), Symbol::get_or_store(text))
+                        }
+                        None => TokenKind::Comment(CommentKind::Block),
+                    }
+                }
                 lex::TokenKind::Whitespace => {
                     preceeded_by_whitespace = true;
                     continue;
                 }
                 lex::TokenKind::Ident => self.ident(start),
-                lex::TokenKind::Literal { kind } => {
-                    let (kind, symbol) =
-                        self.lexer_literal(start, start + BytePos(token.len), kind);
-                    TokenKind::Literal(ast::token::Lit { kind, symbol })
+                lex::TokenKind::InvalidIdent => {
+                    let src_data = self.make_src_data(start, self.pos);
+                    self.dcx().emit(
+                        Diagnostic::error(format!(
+                            "identifiers cannot contain emoji: `{}`",
+                            self.str_from(start)
+                        ))
+                        .with_code(codes::E0009)
+                        .with_primary(src_data, ""),
+                    );
+                    self.ident(start)
+                }
+                lex::TokenKind::UnknownPrefix => {
+                    let src_data = self.make_src_data(start, self.pos);
+                    let after_prefix = self.make_src_data(self.pos, self.pos);
+                    self.dcx().emit(
+                        Diagnostic::error(format!("prefix `{}` is unknown", self.str_from(start)))
+                            .with_code(codes::E0008)
+                            .with_primary(src_data, "unknown prefix")
+                            .with_suggestion(
+                                after_prefix,
+                                "consider inserting whitespace here",
+                                " ",
+                                Applicability::MaybeIncorrect,
+                            ),
+                    );
+                    self.ident(start)
+                }
+                lex::TokenKind::Literal { kind: lex_kind, suffix_start } => {
+                    let suffix_start = start + BytePos(suffix_start);
+                    let (kind, symbol) = self.lexer_literal(start, suffix_start, lex_kind);
+                    let (kind, suffix) = if suffix_start < self.pos {
+                        self.lexer_suffix(kind, lex_kind, suffix_start)
+                    } else {
+                        (kind, None)
+                    };
+                    TokenKind::Literal(ast::token::Lit { kind, symbol, suffix })
                 }
                 lex::TokenKind::Semi => TokenKind::Semi,
                 lex::TokenKind::Comma => TokenKind::Comma,
@@ -89,7 +156,12 @@ impl<'a> StringReader<'a> {
                 lex::TokenKind::Peak => TokenKind::BinOp(ast::token::BinOpToken::Peak),
                 lex::TokenKind::Percent => TokenKind::BinOp(ast::token::BinOpToken::Percent),
                 lex::TokenKind::Unknown => {
-                    eprintln!("Unknown token occured");
+                    let c = self.str_from(start).chars().next().unwrap_or_default();
+                    self.dcx().emit(
+                        Diagnostic::error(format!("unknown start of token: {}", c.escape_debug()))
+                            .with_code(codes::E0001)
+                            .with_primary(self.make_src_data(start, self.pos), ""),
+                    );
                     continue;
                 }
                 lex::TokenKind::Eof => TokenKind::Eof,
@@ -99,6 +171,10 @@ impl<'a> StringReader<'a> {
         }
     }
 
+    pub(crate) fn dcx(&self) -> &'a DiagCtxt {
+        &self.psess.dcx
+    }
+
     #[inline]
     fn src_index(&self, index: BytePos) -> usize {
         (index - self.start_pos).to_usize()
@@ -111,6 +187,13 @@ impl<'a> StringReader<'a> {
         &self.src[self.src_index(start)..self.src_index(end)]
     }
 
+    /// the width of the whitespace at the start of the line containing `pos`
+    pub(crate) fn line_indent(&self, pos: BytePos) -> usize {
+        let before = &self.src[..self.src_index(pos).min(self.src.len())];
+        let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+        self.src[line_start..].chars().take_while(|&c| c == ' ' || c == '\t').count()
+    }
+
     fn make_src_data(&self, lo: BytePos, hi: BytePos) -> SrcData {
         SrcData::with_root_ctxt(lo, hi)
     }
@@ -122,30 +205,111 @@ impl<'a> StringReader<'a> {
         kind: lex::LiteralKind,
     ) -> (LitKind, Symbol) {
         match kind {
-            lex::LiteralKind::Char => self.lexer_unicode(LitKind::Char, Mode::Char, start, end, 1, 1),
-            lex::LiteralKind::Str => self.lexer_unicode(LitKind::Str, Mode::Str, start, end, 1, 1),
-            lex::LiteralKind::Int { base } => {
+            lex::LiteralKind::Char { terminated } => {
+                if !terminated {
+                    self.report_unterminated(start, "character literal");
+                    return (LitKind::Err, self.symbol_from_to(start, end));
+                }
+                self.lexer_unicode(LitKind::Char, Mode::Char, start, end, 1, 1)
+            }
+            lex::LiteralKind::Byte { terminated } => {
+                if !terminated {
+                    self.report_unterminated(start, "byte constant");
+                    return (LitKind::Err, self.symbol_from_to(start, end));
+                }
+                self.lexer_unicode(LitKind::Byte, Mode::Byte, start, end, 2, 1)
+            }
+            lex::LiteralKind::Str { terminated } => {
+                if !terminated {
+                    self.report_unterminated(start, "double quote string");
+                    return (LitKind::Err, self.symbol_from_to(start, end));
+                }
+                self.lexer_unicode(LitKind::Str, Mode::Str, start, end, 1, 1)
+            }
+            lex::LiteralKind::ByteStr { terminated } => {
+                if !terminated {
+                    self.report_unterminated(start, "double quote byte string");
+                    return (LitKind::Err, self.symbol_from_to(start, end));
+                }
+                self.lexer_unicode(LitKind::ByteStr, Mode::ByteStr, start, end, 2, 1)
+            }
+            lex::LiteralKind::CStr { terminated } => {
+                if !terminated {
+                    self.report_unterminated(start, "C string");
+                    return (LitKind::Err, self.symbol_from_to(start, end));
+                }
+                self.lexer_unicode(LitKind::CStr, Mode::CStr, start, end, 2, 1)
+            }
+            lex::LiteralKind::RawStr { n_hashes } => self.lexer_raw_str(
+                LitKind::StrRaw,
+                Mode::RawStr,
+                start,
+                end,
+                1,
+                n_hashes,
+            ),
+            lex::LiteralKind::RawByteStr { n_hashes } => self.lexer_raw_str(
+                LitKind::ByteStrRaw,
+                Mode::RawByteStr,
+                start,
+                end,
+                2,
+                n_hashes,
+            ),
+            lex::LiteralKind::RawCStr { n_hashes } => self.lexer_raw_str(
+                LitKind::CStrRaw,
+                Mode::RawCStr,
+                start,
+                end,
+                2,
+                n_hashes,
+            ),
+            lex::LiteralKind::Int { empty_int: true, .. } => {
+                self.dcx().emit(
+                    Diagnostic::error("no valid digits found for number")
+                        .with_code(codes::E0007)
+                        .with_primary(self.make_src_data(start, end), ""),
+                );
+                (LitKind::Err, self.symbol_from_to(start, end))
+            }
+            lex::LiteralKind::Int { base, .. } => {
                 let mut kind = LitKind::Int;
-                if let lex::Base::Binary | lex::Base::Octal = base {
-                    let base = base as u32;
-                    let s = self.str_from_to(start + BytePos(2), end);
-                    for (idx, c) in s.char_indices() {
-                        let _src_data = self.make_src_data(
-                            start + BytePos::from_usize(2 + idx),
-                            start + BytePos::from_usize(2 + idx + c.len_utf8()),
-                        );
-                        if c != '_' && c.to_digit(base).is_none() {
-                            eprintln!("Invalid Digit Literal");
-                            kind = LitKind::Err;
-                        }
+                // the lexer takes all decimal digits for binary and octal literals,
+                // every digit which is too large for the base is reported on its own
+                let digits_start = start + BytePos::from_usize(base.prefix_len());
+                let prefix = self.make_src_data(start, digits_start);
+                for (idx, c) in self.str_from_to(digits_start, end).char_indices() {
+                    if c == '_' || base.is_digit(c) {
+                        continue;
                     }
+                    let digit_start = digits_start + BytePos::from_usize(idx);
+                    let src_data = self.make_src_data(digit_start, digit_start + BytePos::from_usize(c.len_utf8()));
+                    self.dcx().emit(
+                        Diagnostic::error(format!("invalid digit for a base {} literal", base.radix()))
+                            .with_code(codes::E0002)
+                            .with_primary(src_data, format!("not a {} digit", base_name(base)))
+                            .with_secondary(prefix, format!("base {} because of this prefix", base.radix())),
+                    );
+                    kind = LitKind::Err;
                 }
                 (kind, self.symbol_from_to(start, end))
             }
-            lex::LiteralKind::Float { base } => {
+            lex::LiteralKind::Float { empty_exponent: true, .. } => {
+                self.dcx().emit(
+                    Diagnostic::error("expected at least one digit in exponent")
+                        .with_code(codes::E0007)
+                        .with_primary(self.make_src_data(start, end), ""),
+                );
+                (LitKind::Err, self.symbol_from_to(start, end))
+            }
+            lex::LiteralKind::Float { base, .. } => {
                 let mut kind = LitKind::Float;
                 if let lex::Base::Binary | lex::Base::Octal | lex::Base::Hex = base {
-                    eprintln!("Unsupported Float Base");
+                    self.dcx().emit(
+                        Diagnostic::error(format!("{} float literal is not supported", base_name(base)))
+                            .with_code(codes::E0003)
+                            .with_primary(self.make_src_data(start, end), ""),
+                    );
                     kind = LitKind::Err;
                 }
                 (kind, self.symbol_from_to(start, end))
@@ -153,13 +317,153 @@ impl<'a> StringReader<'a> {
         }
     }
 
+    /// validates the suffix of a literal which starts at `suffix_start` and ends at the current position
+    fn lexer_suffix(
+        &self,
+        kind: LitKind,
+        lex_kind: lex::LiteralKind,
+        suffix_start: BytePos,
+    ) -> (LitKind, Option<Symbol>) {
+        const INT_SUFFIXES: &[Symbol] = &[
+            sym::u8, sym::u16, sym::u32, sym::u64, sym::u128, sym::usize,
+            sym::i8, sym::i16, sym::i32, sym::i64, sym::i128, sym::isize,
+        ];
+        const FLOAT_SUFFIXES: &[Symbol] = &[sym::f32, sym::f64];
+
+        let suffix = self.symbol_from_to(suffix_start, self.pos);
+        let src_data = self.make_src_data(suffix_start, self.pos);
+        let diag = match lex_kind {
+            // the literal is already invalid, do not report anything else
+            _ if kind == LitKind::Err => return (kind, Some(suffix)),
+            lex::LiteralKind::Int { base, .. } => {
+                if INT_SUFFIXES.contains(&suffix) {
+                    return (kind, Some(suffix));
+                }
+                if FLOAT_SUFFIXES.contains(&suffix) {
+                    if base == lex::Base::Decimal {
+                        return (kind, Some(suffix));
+                    }
+                    Diagnostic::error(format!("{} float literal is not supported", base_name(base)))
+                        .with_code(codes::E0003)
+                        .with_primary(src_data, "")
+                } else {
+                    Diagnostic::error(format!("invalid suffix `{}` for number literal", suffix))
+                        .with_code(codes::E0014)
+                        .with_primary(src_data, format!("invalid suffix `{}`", suffix))
+                        .with_help("the suffix must be one of the numeric types (`u32`, `isize`, `f32`, etc.)")
+                }
+            }
+            lex::LiteralKind::Float { .. } => {
+                if FLOAT_SUFFIXES.contains(&suffix) {
+                    return (kind, Some(suffix));
+                }
+                Diagnostic::error(format!("invalid suffix `{}` for float literal", suffix))
+                    .with_code(codes::E0014)
+                    .with_primary(src_data, format!("invalid suffix `{}`", suffix))
+                    .with_help("valid suffixes are `f32` and `f64`")
+            }
+            _ => {
+                let what = match kind {
+                    LitKind::Char => "char",
+                    LitKind::Byte => "byte",
+                    LitKind::CStr | LitKind::CStrRaw(_) => "C string",
+                    LitKind::ByteStr | LitKind::ByteStrRaw(_) => "byte string",
+                    _ => "string",
+                };
+                Diagnostic::error(format!("suffixes on {} literals are invalid", what))
+                    .with_code(codes::E0014)
+                    .with_primary(src_data, format!("invalid suffix `{}`", suffix))
+            }
+        };
+        self.dcx().emit(diag);
+        (LitKind::Err, Some(suffix))
+    }
+
+    /// `prefix_len` is the length of the prefix before the `#`s e.g. `2` for `br`
+    fn lexer_raw_str(
+        &self,
+        mk_kind: fn(u8) -> LitKind,
+        mode: Mode,
+        start: BytePos,
+        end: BytePos,
+        prefix_len: u32,
+        n_hashes: Option<u8>,
+    ) -> (LitKind, Symbol) {
+        match n_hashes {
+            Some(n_hashes) => {
+                let n = n_hashes as u32;
+                self.lexer_unicode(mk_kind(n_hashes), mode, start, end, prefix_len + n + 1, n + 1)
+            }
+            None => {
+                self.report_raw_str_error(start, prefix_len);
+                (LitKind::Err, self.symbol_from_to(start, end))
+            }
+        }
+    }
+
+    fn report_raw_str_error(&self, start: BytePos, prefix_len: u32) {
+        let Err(err) = lex::validate_raw_str(self.str_from(start), prefix_len) else {
+            return;
+        };
+        let diag = match err {
+            lex::RawStrError::InvalidStarter { bad_char } => {
+                let bad_pos = start + BytePos(prefix_len) + BytePos::from_usize(
+                    self.str_from(start + BytePos(prefix_len)).find(|c| c != '#').unwrap_or(0),
+                );
+                Diagnostic::error(format!(
+                    "found invalid character; only `#` is allowed in raw string delimitation: {}",
+                    bad_char.escape_debug()
+                ))
+                .with_code(codes::E0013)
+                .with_primary(self.make_src_data(bad_pos, bad_pos + BytePos(1)), "")
+            }
+            lex::RawStrError::TooManyDelimiters { found } => Diagnostic::error(format!(
+                "too many `#` symbols: raw strings may be delimited by up to 255 `#` symbols, but found {}",
+                found
+            ))
+            .with_code(codes::E0013)
+            .with_primary(self.make_src_data(start, self.pos), ""),
+            lex::RawStrError::NoTerminator { expected, found, possible_terminator_offset } => {
+                let mut diag = Diagnostic::error("unterminated raw string")
+                    .with_code(codes::E0006)
+                    .with_primary(
+                        self.make_src_data(start, start + BytePos(prefix_len)),
+                        "unterminated raw string",
+                    )
+                    .with_note(format!(
+                        "this raw string should be terminated with `\"{}`",
+                        "#".repeat(expected as usize)
+                    ));
+                if let Some(offset) = possible_terminator_offset {
+                    let pos = start + BytePos(offset + found);
+                    diag = diag.with_suggestion(
+                        self.make_src_data(pos, pos),
+                        "consider terminating the string here",
+                        "#".repeat((expected - found) as usize),
+                        Applicability::MaybeIncorrect,
+                    );
+                }
+                diag
+            }
+        };
+        self.dcx().emit(diag);
+    }
+
+    fn report_unterminated(&self, start: BytePos, what: &str) {
+        self.dcx().emit(
+            Diagnostic::error(format!("unterminated {}", what))
+                .with_code(codes::E0006)
+                .with_primary(self.make_src_data(start, start + BytePos(1)), ""),
+        );
+    }
+
     fn symbol_from_to(&self, start: BytePos, end: BytePos) -> Symbol {
         Symbol::get_or_store(self.str_from_to(start, end))
     }
 
     fn lexer_unicode(
         &self,
-        kind: LitKind,
+        mut kind: LitKind,
         mode: Mode,
         start: BytePos,
         end: BytePos,
@@ -169,15 +473,37 @@ impl<'a> StringReader<'a> {
         let content_start = start + BytePos(prefix_len);
         let content_end = end - BytePos(postfix_len);
         let lit_content = self.str_from_to(content_start, content_end);
-        lex::unescape::unescape_unicode(lit_content, mode, &mut |range, res| {
-            if let Err(err) = res {
-                let (line, col) = get_line_of_char(start.to_usize(), self.src).unwrap();
-                eprintln!(
-                    "Unicode Escape Error: {:?} @ pos:{}:{} + {:?}",
-                    err, line, col, range
-                )
-            }
-        });
+        let mut report = |range: std::ops::Range<usize>, err| {
+            let range_src = self.make_src_data(
+                content_start + BytePos::from_usize(range.start),
+                content_start + BytePos::from_usize(range.end),
+            );
+            let diag = unescape_error_reporting::escape_error_diagnostic(
+                lit_content,
+                range,
+                range_src,
+                mode,
+                err,
+            );
+            if diag.is_error() {
+                kind = LitKind::Err;
+            }
+            self.dcx().emit(diag);
+        };
+        match mode {
+            Mode::CStr | Mode::RawCStr => {
+                lex::unescape::unescape_mixed(lit_content, mode, &mut |range, res| {
+                    if let Err(err) = res {
+                        report(range, err);
+                    }
+                })
+            }
+            _ => lex::unescape::unescape_unicode(lit_content, mode, &mut |range, res| {
+                if let Err(err) = res {
+                    report(range, err);
+                }
+            }),
+        }
         let sym = if let LitKind::Err = kind {
             self.symbol_from_to(start, end)
         } else {
@@ -196,25 +522,22 @@ impl<'a> StringReader<'a> {
                 Symbol::get_or_store(&normalized_str)
             }
         };
-        let src_data = self.make_src_data(start, self.pos);
-        // TODO: store ident
         TokenKind::Ident(sym)
     }
 }
 
-#[test]
-fn parse_tt() {
-    let source = include_str!("../../../mock.sl");
-    let token_stream = parse_token_trees(source, BytePos(0)).unwrap();
-    println!("{:#?}", token_stream);
+fn attr_style(style: lex::DocStyle) -> AttrStyle {
+    match style {
+        lex::DocStyle::Outer => AttrStyle::Outer,
+        lex::DocStyle::Inner => AttrStyle::Inner,
+    }
 }
 
-fn get_line_of_char(idx: usize, src: &str) -> Option<(usize, usize)> {
-    let preceding = src.get(..idx)?;
-    let mut line_idx_offset = 0;
-    let lines = preceding
-        .lines()
-        .inspect(|line| line_idx_offset += line.len())
-        .count();
-    Some((lines, idx - line_idx_offset))
+fn base_name(base: lex::Base) -> &'static str {
+    match base {
+        lex::Base::Binary => "binary",
+        lex::Base::Octal => "octal",
+        lex::Base::Decimal => "decimal",
+        lex::Base::Hex => "hexadecimal",
+    }
 }
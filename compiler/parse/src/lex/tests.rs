@@ -0,0 +1,78 @@
+use ast::{
+    lit::{LitIntType, LitValue},
+    token::{Lit, LitKind, Token, TokenKind},
+    tokenstream::TokenTree,
+};
+use source_idx::{sym, Symbol};
+
+use crate::test_utils::{primary_spans, token_trees};
+
+/// the literals of `src`, which may only contain literals
+fn lits(src: &str) -> Vec<Lit> {
+    let (stream, diags) = token_trees(src);
+    assert_eq!(diags, Vec::new());
+    stream
+        .trees()
+        .map(|tree| match tree {
+            TokenTree::SingleToken(Token { kind: TokenKind::Literal(lit), .. }, _) => *lit,
+            tree => panic!("expected a literal, found {:?}", tree),
+        })
+        .collect()
+}
+
+#[test]
+fn parse_tt() {
+    let (stream, diags) = token_trees(include_str!("../../../../mock.sl"));
+    assert_eq!(diags, Vec::new());
+    assert!(!stream.is_empty());
+}
+
+#[test]
+fn literal_suffixes() {
+    let lit = |kind, symbol, suffix| Lit { kind, symbol: Symbol::get_or_store(symbol), suffix };
+    assert_eq!(
+        lits("1u8 2.5f32 3f64 0x1f32 '_'"),
+        [
+            lit(LitKind::Int, "1", Some(sym::u8)),
+            lit(LitKind::Float, "2.5", Some(sym::f32)),
+            lit(LitKind::Int, "3", Some(sym::f64)),
+            // `f32` only consists of hex digits, so there is no suffix
+            lit(LitKind::Int, "0x1f32", None),
+            lit(LitKind::Char, "_", None),
+        ]
+    );
+    // a decimal int with a float suffix is a float
+    assert!(matches!(LitValue::from_token_lit(lits("3f64")[0]), Ok(LitValue::Float(..))));
+    assert!(matches!(LitValue::from_token_lit(lits("0x1f32")[0]), Ok(LitValue::Int(0x1f32, LitIntType::Unsuffixed))));
+
+    // every invalid suffix is reported at the suffix itself
+    let (_, diags) = token_trees("1foo 0b1f32 0o7f64 2.0u8 \"s\"x 'c'y");
+    assert_eq!(
+        primary_spans(&diags),
+        [
+            ("invalid suffix `foo` for number literal", 1, 4),
+            ("binary float literal is not supported", 8, 11),
+            ("octal float literal is not supported", 15, 18),
+            ("invalid suffix `u8` for float literal", 22, 24),
+            ("suffixes on string literals are invalid", 28, 29),
+            ("suffixes on char literals are invalid", 33, 34),
+        ]
+    );
+    assert_eq!(diags[0].labels[0].label.as_deref(), Some("invalid suffix `foo`"));
+}
+
+#[test]
+fn unicode_escape_errors() {
+    // the digits or the brace of the escape are reported, not the whole escape
+    let (_, diags) = token_trees(r#""\u{0000041}" "\u{D800}" '\u{110000}' "\u{41""#);
+    assert_eq!(
+        primary_spans(&diags),
+        [
+            ("overlong unicode escape", 4, 11),
+            ("invalid unicode character escape", 18, 22),
+            ("invalid unicode character escape", 29, 35),
+            ("unterminated unicode escape", 41, 42),
+        ]
+    );
+    assert_eq!(diags[3].labels[0].label.as_deref(), Some("this `{` is missing a closing `}`"));
+}
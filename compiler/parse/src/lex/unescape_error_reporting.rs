@@ -0,0 +1,163 @@
+//! turns the errors of [`lex::unescape`] into diagnostics
+
+use diagnostics::{codes, Applicability, Diagnostic};
+use lex::unescape::{EscapeError, Mode};
+use source_idx::{BytePos, SrcData};
+
+/// `lit` is the content of the literal without the quotes,
+/// `range_src` points at the part of the content which is described by `err`
+pub(crate) fn escape_error_diagnostic(
+    lit: &str,
+    range: std::ops::Range<usize>,
+    range_src: SrcData,
+    mode: Mode,
+    err: EscapeError,
+) -> Diagnostic {
+    let escaped = &lit[range.clone()];
+    let first_char = escaped.chars().next().unwrap_or_default();
+    let last_char = escaped.chars().next_back().unwrap_or_default();
+    let last_char_src = SrcData::with_root_ctxt(
+        range_src.hi() - BytePos::from_usize(last_char.len_utf8()),
+        range_src.hi(),
+    );
+    // the range of a `\u{...}` escape starts at the `\`, it ends behind the `}` if there is one
+    let open_brace_src = SrcData::with_root_ctxt(range_src.lo() + BytePos(2), range_src.lo() + BytePos(3));
+    let unicode_digits_src = SrcData::with_root_ctxt(range_src.lo() + BytePos(3), range_src.hi() - BytePos(1));
+    let diag = match err {
+        EscapeError::ZeroChars => Diagnostic::error("empty character literal")
+            .with_primary(range_src, "empty character literal"),
+        EscapeError::EscapeOnlyChar => {
+            let kind = match mode {
+                Mode::Char => "character",
+                Mode::Byte => "byte",
+                _ => "string",
+            };
+            Diagnostic::error(format!("{} constant must be escaped: `{}`", kind, first_char.escape_default()))
+                .with_primary(range_src, "")
+                .with_suggestion(
+                    range_src,
+                    "escape the character",
+                    first_char.escape_default().to_string(),
+                    Applicability::MachineApplicable,
+                )
+        }
+        EscapeError::RawCarrigeReturn => Diagnostic::error("bare CR not allowed in literal")
+            .with_primary(range_src, "")
+            .with_help("use `\\r` instead"),
+        EscapeError::MoreThanOneChar => {
+            // for chars the range covers the whole content, so the quotes are right next to it
+            let lit_src = SrcData::with_root_ctxt(range_src.lo() - BytePos(1), range_src.hi() + BytePos(1));
+            Diagnostic::error("character literal may only contain one codepoint")
+                .with_primary(range_src, "")
+                .with_suggestion(
+                    lit_src,
+                    "if you meant to write a string literal, use double quotes",
+                    format!("\"{}\"", lit),
+                    Applicability::MachineApplicable,
+                )
+        }
+        EscapeError::LonlyBackSlash => Diagnostic::error("invalid trailing slash in literal")
+            .with_primary(range_src, ""),
+        EscapeError::TooShortHexEscape => Diagnostic::error("numeric character escape is too short")
+            .with_primary(range_src, ""),
+        EscapeError::InvalidCharInHexEscape => {
+            Diagnostic::error("invalid character in numeric character escape")
+                .with_primary(range_src, "")
+        }
+        EscapeError::OutOfRangeHexEscape => {
+            Diagnostic::error("out of range hex escape")
+                .with_primary(range_src, "must be a character in the range [\\x00-\\x7f]")
+        }
+        EscapeError::InvalidEscape => {
+            let escaped_char = escaped.chars().nth(1).unwrap_or_default();
+            Diagnostic::error(format!("unknown character escape: `{}`", escaped_char.escape_default()))
+                .with_primary(range_src, "unknown character escape")
+        }
+        EscapeError::NoBraceInUnicodeEscape => {
+            let diag = Diagnostic::error("incorrect unicode escape sequence")
+                .with_primary(range_src, "incorrect unicode escape sequence");
+            // suggest braces around the hex digits following the `\u`
+            let digits = lit[range.end..]
+                .char_indices()
+                .take_while(|(_, c)| c.is_ascii_hexdigit())
+                .last()
+                .map(|(idx, c)| idx + c.len_utf8())
+                .unwrap_or(0);
+            if digits > 0 && digits <= 6 {
+                let digits_src = SrcData::with_root_ctxt(range_src.lo(), range_src.hi() + BytePos::from_usize(digits));
+                diag.with_suggestion(
+                    digits_src,
+                    "format of unicode escape sequences uses braces",
+                    format!("\\u{{{}}}", &lit[range.end..range.end + digits]),
+                    Applicability::MachineApplicable,
+                )
+            } else {
+                diag.with_help("format of unicode escape sequences is `\\u{...}`")
+            }
+        }
+        EscapeError::UnclosedUnicodeEscape => {
+            let end = SrcData::with_root_ctxt(range_src.hi(), range_src.hi());
+            Diagnostic::error("unterminated unicode escape")
+                .with_primary(open_brace_src, "this `{` is missing a closing `}`")
+                .with_suggestion(
+                    end,
+                    "terminate the unicode escape",
+                    "}",
+                    Applicability::MaybeIncorrect,
+                )
+        }
+        EscapeError::EmptyUnicodeEscape => Diagnostic::error("empty unicode escape")
+            .with_primary(range_src, "this escape must have at least 1 hex digit"),
+        EscapeError::LeadingUnderscoreUnicodeEscape => {
+            Diagnostic::error("invalid start of unicode escape: `_`")
+                .with_primary(last_char_src, "invalid start of unicode escape")
+        }
+        EscapeError::InvalidCharInUnicodeEscape => {
+            Diagnostic::error(format!("invalid character in unicode escape: `{}`", last_char.escape_default()))
+                .with_primary(last_char_src, "invalid character in unicode escape")
+        }
+        EscapeError::OverlongUnicodeEscape => Diagnostic::error("overlong unicode escape")
+            .with_primary(unicode_digits_src, "must have at most 6 hex digits"),
+        EscapeError::LoneSurrogateUnicodeEscape => {
+            Diagnostic::error("invalid unicode character escape")
+                .with_primary(unicode_digits_src, "invalid escape")
+                .with_help("unicode escape must not be a surrogate")
+        }
+        EscapeError::OutOfRangeUnicodeEscape => {
+            Diagnostic::error("invalid unicode character escape")
+                .with_primary(unicode_digits_src, "invalid escape")
+                .with_help("unicode escape must be at most 10FFFF")
+        }
+        EscapeError::UnicodeEscapeInByte => {
+            Diagnostic::error("unicode escape in byte string")
+                .with_primary(range_src, "unicode escape in byte string")
+                .with_help("unicode escape sequences cannot be used as a byte or in a byte string")
+        }
+        EscapeError::NonAsciiCharInByte => {
+            let kind = match mode {
+                Mode::Byte => "byte literal",
+                Mode::ByteStr => "byte string literal",
+                _ => "raw byte string literal",
+            };
+            let diag = Diagnostic::error(format!("non-ASCII character in {}", kind))
+                .with_primary(range_src, "must be ASCII");
+            if mode.allow_high_bytes() && (first_char as u32) <= 0xff {
+                diag.with_suggestion(
+                    range_src,
+                    "if you meant to use the unicode code point, use a \\xHH escape",
+                    format!("\\x{:X}", first_char as u32),
+                    Applicability::MaybeIncorrect,
+                )
+            } else {
+                diag
+            }
+        }
+        EscapeError::NulInCStr => Diagnostic::error("null characters in C string literals are not supported")
+            .with_primary(range_src, ""),
+        EscapeError::MultipleSkippedLinesWarning => {
+            return Diagnostic::warning("multiple lines skipped by escaped newline")
+                .with_primary(range_src, "skipping everything up to and including this point");
+        }
+    };
+    diag.with_code(codes::E0004)
+}
@@ -1,3 +1,48 @@
+use std::{cell::Cell, rc::Rc};
+
+use ast::ast::NodeId;
+use diagnostics::{DiagCtxt, HumanEmitter};
+use source_idx::{Edition, SourceFile, SourceMap};
+
 pub mod lex;
 pub mod parse;
-pub mod tokentrees;
\ No newline at end of file
+pub mod tokentrees;
+#[cfg(test)]
+mod test_utils;
+
+/// state which is shared while parsing all files of a session
+pub struct ParseSess {
+    pub dcx: DiagCtxt,
+    pub source_map: Rc<SourceMap>,
+    pub edition: Edition,
+    next_node_id: Cell<u32>,
+}
+
+impl ParseSess {
+    /// creates a session which renders diagnostics to stderr
+    pub fn new(source_map: Rc<SourceMap>) -> ParseSess {
+        let emitter = HumanEmitter::stderr(source_map.clone());
+        ParseSess::with_dcx(DiagCtxt::new(Box::new(emitter)), source_map)
+    }
+
+    pub fn with_dcx(dcx: DiagCtxt, source_map: Rc<SourceMap>) -> ParseSess {
+        ParseSess { dcx, source_map, edition: Edition::DEFAULT, next_node_id: Cell::new(0) }
+    }
+
+    /// a new id for a node of the AST
+    pub fn next_node_id(&self) -> NodeId {
+        let id = self.next_node_id.get();
+        self.next_node_id.set(id + 1);
+        NodeId::from_u32(id)
+    }
+}
+
+/// lexes and parses a whole file, all errors are emitted to the [`DiagCtxt`] of the session
+///
+/// the parser recovers from syntax errors, so there is always a crate,
+/// with `Err` nodes where the source could not be parsed
+pub fn parse_crate_from_file(psess: &ParseSess, file: &SourceFile) -> ast::ast::Crate {
+    let stream = lex::parse_token_trees(psess, &file.src, file.start_pos);
+    let mut parser = parse::Parser::new(psess, stream);
+    parser.parse_crate_mod()
+}
\ No newline at end of file
@@ -0,0 +1,242 @@
+//! Recursive descent parser turning a [`TokenStream`] into an AST
+
+use ast::{
+    ast::{DelimArgs, Expr, ExprKind, Ident, Pat, PatKind, Ty, TyKind, P},
+    pprust,
+    token::{Delimiter, Token, TokenKind},
+    tokenstream::{TokenStream, TokenStreamCursor},
+};
+use diagnostics::{codes, Diagnostic};
+use source_idx::{kw, SrcData, Symbol};
+
+use crate::ParseSess;
+
+mod attr;
+mod expr;
+mod item;
+mod path;
+mod pat;
+mod recover;
+mod stmt;
+#[cfg(test)]
+mod tests;
+mod ty;
+
+pub use path::PathStyle;
+pub use recover::MAX_SYNTAX_ERRORS;
+
+/// the error is not emitted yet, so the caller can decide what to do with it
+pub type PResult<T> = Result<T, Diagnostic>;
+
+pub struct Parser<'a> {
+    pub psess: &'a ParseSess,
+    /// the current token
+    pub token: Token,
+    /// the previous token, used for the end of the source of nodes
+    pub prev_token: Token,
+    token_cursor: TokenCursor,
+    /// the number of syntax errors the parser recovered from
+    syntax_errors: usize,
+    /// the primary source of the last syntax error, errors at the same source are not reported again
+    last_error: Option<SrcData>,
+}
+
+/// The tokens the parser looks at, comments and invisible delimiters are skipped
+#[derive(Clone)]
+struct TokenCursor(TokenStreamCursor);
+
+impl TokenCursor {
+    fn next(&mut self) -> Token {
+        loop {
+            let (token, _) = self.0.next_token();
+            match token.kind {
+                TokenKind::Comment(_)
+                | TokenKind::OpenDelim(Delimiter::Invisible)
+                | TokenKind::CloseDelim(Delimiter::Invisible) => continue,
+                _ => return token,
+            }
+        }
+    }
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(psess: &'a ParseSess, stream: TokenStream) -> Parser<'a> {
+        let mut parser = Parser {
+            psess,
+            token: Token::dummy(),
+            prev_token: Token::dummy(),
+            token_cursor: TokenCursor(stream.cursor()),
+            syntax_errors: 0,
+            last_error: None,
+        };
+        parser.bump();
+        parser
+    }
+
+    /// advances to the next token
+    pub fn bump(&mut self) {
+        let mut next = self.token_cursor.next();
+        if next.kind == TokenKind::Eof {
+            // the end of the file is right after the last token
+            next.src_data = self.token.src_data.shrink_to_hi();
+        }
+        self.prev_token = std::mem::replace(&mut self.token, next);
+    }
+
+    /// calls `f` with the token `dist` tokens ahead, `0` is the current token
+    pub fn look_ahead<R>(&self, dist: usize, f: impl FnOnce(&Token) -> R) -> R {
+        if dist == 0 {
+            return f(&self.token);
+        }
+        let mut cursor = self.token_cursor.clone();
+        let mut token = self.token;
+        for _ in 0..dist {
+            if token.kind == TokenKind::Eof {
+                break;
+            }
+            token = cursor.next();
+        }
+        f(&token)
+    }
+
+    pub fn check(&self, kind: TokenKind) -> bool {
+        self.token.kind == kind
+    }
+
+    /// consumes the current token if it is `kind`
+    pub fn eat(&mut self, kind: TokenKind) -> bool {
+        let present = self.check(kind);
+        if present {
+            self.bump();
+        }
+        present
+    }
+
+    pub fn expect(&mut self, kind: TokenKind) -> PResult<()> {
+        if self.eat(kind) {
+            Ok(())
+        } else {
+            Err(self.expected(&format!("`{}`", kind.punct_str().unwrap_or_default())))
+        }
+    }
+
+    /// like [`Parser::check`], but also true for a glued token starting with `kind` e.g. `>` in `>>`
+    pub fn check_break(&self, kind: TokenKind) -> bool {
+        self.check(kind) || matches!(self.token.kind.split_double_token(), Some((first, _)) if first == kind)
+    }
+
+    /// like [`Parser::eat`], but splits a glued token starting with `kind`
+    ///
+    /// the rest of the glued token becomes the current token,
+    /// e.g. the second `>` of `Vec<Vec<u8>>` with the source of only that `>`
+    pub fn break_and_eat(&mut self, kind: TokenKind) -> bool {
+        if self.eat(kind) {
+            return true;
+        }
+        match self.token.split_double() {
+            Some((first, second)) if first.kind == kind => {
+                self.prev_token = first;
+                self.token = second;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// like [`Parser::expect`], but splits a glued token starting with `kind`
+    pub fn expect_break(&mut self, kind: TokenKind) -> PResult<()> {
+        if self.break_and_eat(kind) {
+            Ok(())
+        } else {
+            Err(self.expected(&format!("`{}`", kind.punct_str().unwrap_or_default())))
+        }
+    }
+
+    pub fn check_keyword(&self, keyword: Symbol) -> bool {
+        self.token.kind == TokenKind::Ident(keyword)
+    }
+
+    pub fn eat_keyword(&mut self, keyword: Symbol) -> bool {
+        let present = self.check_keyword(keyword);
+        if present {
+            self.bump();
+        }
+        present
+    }
+
+    /// true if the current token is an identifier which is not a reserved keyword
+    pub fn check_ident(&self) -> bool {
+        matches!(self.token.kind, TokenKind::Ident(name) if is_ident_name(name))
+    }
+
+    pub fn parse_ident(&mut self) -> PResult<Ident> {
+        match self.token.kind {
+            TokenKind::Ident(name) if is_ident_name(name) => {
+                self.bump();
+                Ok(Ident::new(name, self.prev_token.src_data))
+            }
+            TokenKind::Ident(name) if name.is_reserved() => Err(self
+                .expected("identifier")
+                .with_note(format!("`{}` is a reserved keyword", name))),
+            _ => Err(self.expected("identifier")),
+        }
+    }
+
+    /// an error `expected {what}, found {current token}` at the current token
+    pub fn expected(&self, what: &str) -> Diagnostic {
+        let found = token_descr(&self.token);
+        Diagnostic::error(format!("expected {}, found {}", what, found))
+            .with_code(codes::E0015)
+            .with_primary(self.token.src_data, format!("expected {}", what))
+    }
+
+    pub fn mk_expr(&self, kind: ExprKind, src_data: SrcData) -> P<Expr> {
+        Box::new(Expr { id: self.psess.next_node_id(), kind, src_data })
+    }
+
+    pub fn mk_ty(&self, kind: TyKind, src_data: SrcData) -> P<Ty> {
+        Box::new(Ty { id: self.psess.next_node_id(), kind, src_data })
+    }
+
+    pub fn mk_pat(&self, kind: PatKind, src_data: SrcData) -> P<Pat> {
+        Box::new(Pat { id: self.psess.next_node_id(), kind, src_data })
+    }
+
+    /// parses a delimited group like `(a b c)` without looking at the tokens inside of it
+    pub fn parse_delim_args(&mut self) -> PResult<DelimArgs> {
+        let TokenKind::OpenDelim(delim) = self.token.kind else {
+            return Err(self.expected("one of `(`, `[` or `{`"));
+        };
+        // the cursor already entered the group of the current open delimiter
+        let (_, dspan, tokens) = self.token_cursor.0.current_group().expect("an open delimiter is always inside of a group");
+        let tokens = tokens.clone();
+        self.token_cursor.0.skip_group();
+        // moves to the closing delimiter and then behind it
+        self.bump();
+        self.bump();
+        Ok(DelimArgs { dspan, delim, tokens })
+    }
+}
+
+/// describes a token for error messages e.g. ``keyword `fn` ``
+pub fn token_descr(token: &Token) -> String {
+    match token.kind {
+        TokenKind::Ident(name) if name.is_reserved() => format!("keyword `{}`", name),
+        TokenKind::Ident(name) => format!("`{}`", name),
+        TokenKind::Literal(lit) => format!("literal `{}`", pprust::lit_to_string(lit)),
+        TokenKind::Comment(_) => "comment".to_string(),
+        TokenKind::DocComment(..) => "doc comment".to_string(),
+        TokenKind::Eof => "end of file".to_string(),
+        ref kind => format!("`{}`", kind.punct_str().unwrap_or_default()),
+    }
+}
+
+/// true if `name` can be used as an identifier
+fn is_ident_name(name: Symbol) -> bool {
+    !name.is_reserved() && name != kw::Underscore
+}
+
+/// true for keywords which can be a segment of a path e.g. `self` in `self::a`
+fn is_path_segment_keyword(name: Symbol) -> bool {
+    name == kw::SelfLower || name == kw::SelfUpper || name == kw::Super || name == kw::Crate
+}
@@ -0,0 +1,108 @@
+use ast::{
+    ast::{AttrArgs, AttrItem, AttrKind, AttrStyle, Attribute},
+    token::{Delimiter, TokenKind},
+};
+use diagnostics::{codes, Applicability, Diagnostic};
+
+use super::{PResult, Parser, PathStyle};
+
+impl<'a> Parser<'a> {
+    /// parses all `#[attr]`s and `///` doc comments in front of an item
+    pub fn parse_outer_attributes(&mut self) -> PResult<Vec<Attribute>> {
+        let mut attrs = Vec::new();
+        loop {
+            if self.check_inner_attribute() {
+                // only the attributes at the start of a file or module can be inner attributes
+                let bang = self.look_ahead(1, |token| token.src_data);
+                let attr = self.parse_attribute(AttrStyle::Inner)?;
+                self.emit_recovered(
+                    Diagnostic::error("an inner attribute is not permitted in this context")
+                        .with_code(codes::E0015)
+                        .with_primary(attr.src_data, "")
+                        .with_note("inner attributes annotate the file or module they are written at the start of")
+                        .with_suggestion(
+                            bang,
+                            "to annotate the following item, use an outer attribute",
+                            "",
+                            Applicability::MachineApplicable,
+                        ),
+                );
+            } else if self.check(TokenKind::Hashtag) {
+                attrs.push(self.parse_attribute(AttrStyle::Outer)?);
+            } else if let Some(attr) = self.eat_doc_comment(AttrStyle::Outer) {
+                attrs.push(attr);
+            } else {
+                return Ok(attrs);
+            }
+        }
+    }
+
+    /// parses the `#![attr]`s and `//!` doc comments at the start of a file or module
+    pub fn parse_inner_attributes(&mut self) -> Vec<Attribute> {
+        let mut attrs = Vec::new();
+        loop {
+            if self.check_inner_attribute() {
+                let start = self.recovery_start();
+                match self.parse_attribute(AttrStyle::Inner) {
+                    Ok(attr) => attrs.push(attr),
+                    Err(err) => {
+                        self.emit_recovered(err);
+                        self.recover(start, TokenKind::CloseDelim(Delimiter::Bracket));
+                    }
+                }
+            } else if let Some(attr) = self.eat_doc_comment(AttrStyle::Inner) {
+                attrs.push(attr);
+            } else {
+                return attrs;
+            }
+        }
+    }
+
+    /// true at the `#!` of `#![attr]`
+    fn check_inner_attribute(&self) -> bool {
+        self.check(TokenKind::Hashtag) && self.look_ahead(1, |token| token.kind == TokenKind::Not)
+    }
+
+    fn eat_doc_comment(&mut self, style: AttrStyle) -> Option<Attribute> {
+        let TokenKind::DocComment(comment_kind, doc_style, text) = self.token.kind else {
+            return None;
+        };
+        if doc_style != style {
+            return None;
+        }
+        self.bump();
+        Some(Attribute {
+            kind: AttrKind::DocComment(comment_kind, text),
+            style,
+            src_data: self.prev_token.src_data,
+        })
+    }
+
+    /// `#[path]`, `#[path(tokens)]` or `#[path = value]` and the same with `#!` for inner attributes
+    fn parse_attribute(&mut self, style: AttrStyle) -> PResult<Attribute> {
+        let lo = self.token.src_data;
+        self.expect(TokenKind::Hashtag)?;
+        if style == AttrStyle::Inner {
+            self.expect(TokenKind::Not)?;
+        }
+        self.expect(TokenKind::OpenDelim(Delimiter::Bracket))?;
+        let item = self.parse_attr_item()?;
+        self.expect(TokenKind::CloseDelim(Delimiter::Bracket))?;
+        Ok(Attribute { kind: AttrKind::Normal(Box::new(item)), style, src_data: lo.to(self.prev_token.src_data) })
+    }
+
+    fn parse_attr_item(&mut self) -> PResult<AttrItem> {
+        let path = self.parse_path(PathStyle::Mod)?;
+        let args = match self.token.kind {
+            TokenKind::OpenDelim(Delimiter::Parenthesis | Delimiter::Bracket | Delimiter::Brace) => {
+                AttrArgs::Delimited(self.parse_delim_args()?)
+            }
+            TokenKind::Eq => {
+                self.bump();
+                AttrArgs::Eq(self.prev_token.src_data, self.parse_expr()?)
+            }
+            _ => AttrArgs::Empty,
+        };
+        Ok(AttrItem { path, args })
+    }
+}
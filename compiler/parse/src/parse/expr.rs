@@ -0,0 +1,408 @@
+use ast::{
+    ast::{
+        BinOp, CallArg, CallArgKind, Closure, ClosureParam, Expr, ExprKind, Ident, MacCall, MethodCall, Mutability,
+        RangeLimits, UnOp, P,
+    },
+    lit::{LitError, LitIntType, LitValue},
+    token::{self, BinOpToken, Delimiter, TokenKind},
+    util::parser::{AssocOp, Fixity},
+};
+use diagnostics::{codes, Diagnostic};
+use source_idx::{kw, SrcData};
+
+use super::{PResult, Parser, PathStyle};
+
+impl<'a> Parser<'a> {
+    pub fn parse_expr(&mut self) -> PResult<P<Expr>> {
+        self.parse_assoc_expr(0)
+    }
+
+    /// parses an expression whose infix operators bind at least as strong as `min_prec`
+    ///
+    /// this is a precedence climbing parser, the precedences are defined by [`AssocOp`]
+    fn parse_assoc_expr(&mut self, min_prec: u8) -> PResult<P<Expr>> {
+        let mut lhs = if self.check_range_op() {
+            self.parse_prefix_range_expr()?
+        } else {
+            self.parse_prefix_expr()?
+        };
+
+        while let Some(op) = AssocOp::from_token(&self.token) {
+            let prec = op.precedence();
+            if prec < min_prec {
+                break;
+            }
+            let op_src_data = self.token.src_data;
+            self.bump();
+            if let ExprKind::Binary(lhs_op, ..) = &lhs.kind {
+                if op.fixity() == Fixity::None && AssocOp::Binary(lhs_op.kind).precedence() == prec {
+                    self.report_chained_comparison(lhs_op.src_data, op_src_data);
+                }
+            }
+
+            if let AssocOp::Range(limits) = op {
+                let end = if self.check_expr_start() { Some(self.parse_assoc_expr(prec + 1)?) } else { None };
+                let src_data = lhs.src_data.to(self.prev_token.src_data);
+                lhs = self.mk_expr(ExprKind::Range(Some(lhs), end, limits), src_data);
+                continue;
+            }
+
+            let rhs = match op.fixity() {
+                Fixity::Right => self.parse_assoc_expr(prec)?,
+                Fixity::Left | Fixity::None => self.parse_assoc_expr(prec + 1)?,
+            };
+            let src_data = lhs.src_data.to(rhs.src_data);
+            let kind = match op {
+                AssocOp::Binary(kind) => ExprKind::Binary(BinOp { kind, src_data: op_src_data }, lhs, rhs),
+                AssocOp::AssignOp(kind) => ExprKind::AssignOp(BinOp { kind, src_data: op_src_data }, lhs, rhs),
+                AssocOp::Assign => ExprKind::Assign(lhs, rhs, op_src_data),
+                AssocOp::Range(_) => unreachable!("ranges are handled above"),
+            };
+            lhs = self.mk_expr(kind, src_data);
+        }
+        Ok(lhs)
+    }
+
+    fn report_chained_comparison(&mut self, first: SrcData, second: SrcData) {
+        self.emit_recovered(
+            Diagnostic::error("comparison operators cannot be chained")
+                .with_code(codes::E0015)
+                .with_primary(first, "")
+                .with_primary(second, "")
+                .with_help("split the comparison into two, e.g. `a < b && b < c`, or use parentheses"),
+        );
+    }
+
+    fn check_range_op(&self) -> bool {
+        matches!(self.token.kind, TokenKind::DotDot | TokenKind::DotDotEq)
+    }
+
+    /// `..end`, `..=end` or `..`
+    fn parse_prefix_range_expr(&mut self) -> PResult<P<Expr>> {
+        let lo = self.token.src_data;
+        let limits = if self.eat(TokenKind::DotDotEq) { RangeLimits::Closed } else { RangeLimits::HalfOpen };
+        if limits == RangeLimits::HalfOpen {
+            self.expect(TokenKind::DotDot)?;
+        }
+        let prec = AssocOp::Range(limits).precedence();
+        let end = if self.check_expr_start() { Some(self.parse_assoc_expr(prec + 1)?) } else { None };
+        Ok(self.mk_expr(ExprKind::Range(None, end, limits), lo.to(self.prev_token.src_data)))
+    }
+
+    /// `!expr`, `-expr`, `~expr`, `*expr`, `&expr`, `&mut expr` or an expression without prefix
+    fn parse_prefix_expr(&mut self) -> PResult<P<Expr>> {
+        let lo = self.token.src_data;
+        let kind = match self.token.kind {
+            TokenKind::Not => {
+                self.bump();
+                ExprKind::Unary(UnOp::Not, self.parse_prefix_expr()?)
+            }
+            TokenKind::Tilde => {
+                self.bump();
+                ExprKind::Unary(UnOp::BitNot, self.parse_prefix_expr()?)
+            }
+            TokenKind::BinOp(BinOpToken::Star) => {
+                self.bump();
+                ExprKind::Unary(UnOp::Deref, self.parse_prefix_expr()?)
+            }
+            TokenKind::BinOp(BinOpToken::Minus) => {
+                self.bump();
+                let operand = match self.token.kind {
+                    // the minimum of a signed integer is only valid if it is negated e.g. `-128i8`
+                    TokenKind::Literal(lit) => {
+                        let lit_src_data = self.token.src_data;
+                        self.bump();
+                        let value = self.lit_value(lit, lit_src_data, true);
+                        let expr = self.mk_expr(ExprKind::Lit(value), lit_src_data);
+                        self.parse_dot_or_call_expr_with(expr)?
+                    }
+                    _ => self.parse_prefix_expr()?,
+                };
+                ExprKind::Unary(UnOp::Neg, operand)
+            }
+            TokenKind::BinOp(BinOpToken::And) | TokenKind::AndAnd => {
+                // `&&x` is `& &x`
+                self.break_and_eat(TokenKind::BinOp(BinOpToken::And));
+                let mutbl = if self.eat_keyword(kw::Mut) { Mutability::Mut } else { Mutability::Not };
+                ExprKind::AddrOf(mutbl, self.parse_prefix_expr()?)
+            }
+            _ => return self.parse_dot_or_call_expr(),
+        };
+        Ok(self.mk_expr(kind, lo.to(self.prev_token.src_data)))
+    }
+
+    /// true if the current token can start an expression, used for the optional end of ranges
+    fn check_expr_start(&self) -> bool {
+        match self.token.kind {
+            TokenKind::Literal(_)
+            | TokenKind::OpenDelim(Delimiter::Parenthesis | Delimiter::Bracket)
+            | TokenKind::Not
+            | TokenKind::Tilde
+            | TokenKind::BinOp(BinOpToken::Minus | BinOpToken::Star | BinOpToken::And | BinOpToken::Or)
+            | TokenKind::AndAnd
+            | TokenKind::OrOr => true,
+            TokenKind::Ident(name) => {
+                !name.is_reserved()
+                    || name.is_bool_lit()
+                    || [kw::Return, kw::Break, kw::Continue, kw::If, kw::While, kw::Loop].contains(&name)
+                    || self.check_path()
+            }
+            _ => false,
+        }
+    }
+
+    /// an expression followed by calls, field accesses, indexing and `?`
+    fn parse_dot_or_call_expr(&mut self) -> PResult<P<Expr>> {
+        let expr = self.parse_bottom_expr()?;
+        self.parse_dot_or_call_expr_with(expr)
+    }
+
+    fn parse_dot_or_call_expr_with(&mut self, mut expr: P<Expr>) -> PResult<P<Expr>> {
+        loop {
+            let lo = expr.src_data;
+            let kind = if self.eat(TokenKind::Dot) {
+                let ident = self.parse_field_name()?;
+                if self.check(TokenKind::OpenDelim(Delimiter::Parenthesis)) {
+                    let args = self.parse_call_args()?;
+                    ExprKind::MethodCall(Box::new(MethodCall { ident, receiver: expr, args }))
+                } else {
+                    ExprKind::Field(expr, ident)
+                }
+            } else if self.check(TokenKind::OpenDelim(Delimiter::Parenthesis)) {
+                ExprKind::Call(expr, self.parse_call_args()?)
+            } else if self.eat(TokenKind::OpenDelim(Delimiter::Bracket)) {
+                let index = self.parse_expr()?;
+                self.expect(TokenKind::CloseDelim(Delimiter::Bracket))?;
+                ExprKind::Index(expr, index)
+            } else if self.eat(TokenKind::Question) {
+                ExprKind::Try(expr)
+            } else {
+                return Ok(expr);
+            };
+            expr = self.mk_expr(kind, lo.to(self.prev_token.src_data));
+        }
+    }
+
+    /// the name behind a `.`, either an identifier or the index of a tuple field
+    fn parse_field_name(&mut self) -> PResult<Ident> {
+        if let TokenKind::Literal(token::Lit { kind: token::LitKind::Int, symbol, suffix: None }) = self.token.kind {
+            if symbol.as_str().bytes().all(|b| b.is_ascii_digit()) {
+                self.bump();
+                return Ok(Ident::new(symbol, self.prev_token.src_data));
+            }
+        }
+        self.parse_ident()
+    }
+
+    /// `(arg, name: arg, ..spread)`
+    fn parse_call_args(&mut self) -> PResult<Vec<CallArg>> {
+        self.expect(TokenKind::OpenDelim(Delimiter::Parenthesis))?;
+        let mut args = Vec::new();
+        while !self.check(TokenKind::CloseDelim(Delimiter::Parenthesis)) {
+            let lo = self.token.src_data;
+            let kind = if self.eat(TokenKind::DotDot) {
+                CallArgKind::Spread(self.parse_expr()?)
+            } else if self.check_ident() && self.look_ahead(1, |t| t.kind == TokenKind::Colon) {
+                let ident = self.parse_ident()?;
+                self.bump();
+                CallArgKind::Named(ident, self.parse_expr()?)
+            } else {
+                CallArgKind::Positional(self.parse_expr()?)
+            };
+            args.push(CallArg { kind, src_data: lo.to(self.prev_token.src_data) });
+            if !self.eat(TokenKind::Comma) {
+                break;
+            }
+        }
+        self.expect(TokenKind::CloseDelim(Delimiter::Parenthesis))?;
+        Ok(args)
+    }
+
+    /// literals, paths, groups and other expressions which do not start with an operator
+    pub(super) fn parse_bottom_expr(&mut self) -> PResult<P<Expr>> {
+        let lo = self.token.src_data;
+        let kind = match self.token.kind {
+            TokenKind::Literal(lit) => {
+                self.bump();
+                ExprKind::Lit(self.lit_value(lit, lo, false))
+            }
+            TokenKind::Ident(name) if name.is_bool_lit() => {
+                self.bump();
+                ExprKind::Lit(LitValue::Bool(name == kw::True))
+            }
+            TokenKind::Ident(kw::Return) => {
+                self.bump();
+                ExprKind::Ret(self.parse_opt_expr()?)
+            }
+            TokenKind::Ident(kw::Break) => {
+                self.bump();
+                ExprKind::Break(self.parse_opt_expr()?)
+            }
+            TokenKind::Ident(kw::Continue) => {
+                self.bump();
+                ExprKind::Continue
+            }
+            TokenKind::Ident(kw::If) => return self.parse_if_expr(),
+            TokenKind::Ident(kw::While) => {
+                self.bump();
+                let cond = self.parse_expr()?;
+                ExprKind::While(cond, self.parse_block()?)
+            }
+            TokenKind::Ident(kw::Loop) => {
+                self.bump();
+                ExprKind::Loop(self.parse_block()?)
+            }
+            TokenKind::BinOp(BinOpToken::Or) | TokenKind::OrOr => ExprKind::Closure(self.parse_closure()?),
+            TokenKind::OpenDelim(Delimiter::Parenthesis) => {
+                self.bump();
+                let (mut exprs, trailing_comma) = self.parse_expr_list(Delimiter::Parenthesis)?;
+                if exprs.len() == 1 && !trailing_comma {
+                    ExprKind::Paren(exprs.pop().unwrap())
+                } else {
+                    ExprKind::Tup(exprs)
+                }
+            }
+            TokenKind::OpenDelim(Delimiter::Bracket) => {
+                self.bump();
+                ExprKind::Array(self.parse_expr_list(Delimiter::Bracket)?.0)
+            }
+            TokenKind::OpenDelim(Delimiter::Brace) => ExprKind::Block(self.parse_block()?),
+            _ if self.check_path() => {
+                let path = self.parse_path(PathStyle::Expr)?;
+                let is_mac_call = self.check(TokenKind::Not)
+                    && self.look_ahead(1, |t| matches!(t.kind, TokenKind::OpenDelim(_)));
+                if is_mac_call {
+                    self.bump();
+                    let args = self.parse_delim_args()?;
+                    ExprKind::MacCall(Box::new(MacCall { path, args }))
+                } else {
+                    ExprKind::Path(path)
+                }
+            }
+            _ => return Err(self.expected("expression")),
+        };
+        Ok(self.mk_expr(kind, lo.to(self.prev_token.src_data)))
+    }
+
+    /// `if cond { then } else if cond { then } else { els }`
+    fn parse_if_expr(&mut self) -> PResult<P<Expr>> {
+        let lo = self.token.src_data;
+        self.bump();
+        let cond = self.parse_expr()?;
+        let then = self.parse_block()?;
+        let els = if self.eat_keyword(kw::Else) {
+            if self.check_keyword(kw::If) {
+                Some(self.parse_if_expr()?)
+            } else {
+                let block = self.parse_block()?;
+                let src_data = block.src_data;
+                Some(self.mk_expr(ExprKind::Block(block), src_data))
+            }
+        } else {
+            None
+        };
+        Ok(self.mk_expr(ExprKind::If(cond, then, els), lo.to(self.prev_token.src_data)))
+    }
+
+    /// `|params| body`
+    fn parse_closure(&mut self) -> PResult<P<Closure>> {
+        // `||` is split, so the loop ends at the second `|`
+        self.expect_break(TokenKind::BinOp(BinOpToken::Or))?;
+        let mut params = Vec::new();
+        while !self.check(TokenKind::BinOp(BinOpToken::Or)) {
+            let lo = self.token.src_data;
+            let pat = self.parse_pat()?;
+            let ty = if self.eat(TokenKind::Colon) { Some(self.parse_ty()?) } else { None };
+            let src_data = lo.to(self.prev_token.src_data);
+            params.push(ClosureParam { id: self.psess.next_node_id(), pat, ty, src_data });
+            if !self.eat(TokenKind::Comma) {
+                break;
+            }
+        }
+        self.expect(TokenKind::BinOp(BinOpToken::Or))?;
+        let body = self.parse_expr()?;
+        Ok(Box::new(Closure { params, body }))
+    }
+
+    /// the optional value of `return` and `break`
+    fn parse_opt_expr(&mut self) -> PResult<Option<P<Expr>>> {
+        if self.check_expr_end() {
+            Ok(None)
+        } else {
+            self.parse_expr().map(Some)
+        }
+    }
+
+    /// comma separated expressions until the closing `delim`, the opening one is already eaten
+    ///
+    /// also returns if there was a trailing comma
+    fn parse_expr_list(&mut self, delim: Delimiter) -> PResult<(Vec<P<Expr>>, bool)> {
+        let mut exprs = Vec::new();
+        let mut trailing_comma = false;
+        while !self.check(TokenKind::CloseDelim(delim)) {
+            exprs.push(self.parse_expr()?);
+            trailing_comma = self.eat(TokenKind::Comma);
+            if !trailing_comma {
+                break;
+            }
+        }
+        self.expect(TokenKind::CloseDelim(delim))?;
+        Ok((exprs, trailing_comma))
+    }
+
+    /// true if the current token can not continue an expression e.g. after `return`
+    fn check_expr_end(&self) -> bool {
+        matches!(self.token.kind, TokenKind::Semi | TokenKind::Comma | TokenKind::CloseDelim(_) | TokenKind::Eof)
+    }
+
+    /// evaluates a literal token, invalid literals are reported and become [`LitValue::Err`]
+    ///
+    /// `negated` is true for a literal right behind a `-`, which can be the minimum of a signed type
+    pub(super) fn lit_value(&self, lit: token::Lit, src_data: SrcData, negated: bool) -> LitValue {
+        let err = match LitValue::from_token_lit(lit) {
+            Ok(LitValue::Int(value, ty)) if !ty.fits(value, negated) => LitError::IntOutOfRange(ty),
+            Ok(value) => return value,
+            Err(err) => err,
+        };
+        let text = || self.psess.source_map.snippet(src_data).unwrap_or_else(|| lit.symbol.to_string());
+        let diag = match err {
+            LitError::InvalidSuffix(suffix) => Diagnostic::error(format!("invalid suffix `{}` for literal", suffix))
+                .with_code(codes::E0014)
+                .with_primary(src_data, format!("invalid suffix `{}`", suffix)),
+            LitError::NonDecimalFloat(base) => {
+                Diagnostic::error(format!("base {} float literal is not supported", base))
+                    .with_code(codes::E0003)
+                    .with_primary(src_data, "")
+            }
+            LitError::IntTooLarge(_) => Diagnostic::error("integer literal is too large")
+                .with_code(codes::E0016)
+                .with_primary(src_data, "")
+                .with_note(format!("value exceeds limit of `{}`", u128::MAX)),
+            LitError::IntOutOfRange(ty) => {
+                let (min, max) = match ty {
+                    LitIntType::Signed(ty) => (format!("-{}", ty.max() + 1), ty.max()),
+                    LitIntType::Unsigned(ty) => ("0".to_string(), ty.max()),
+                    LitIntType::Unsuffixed => unreachable!("unsuffixed integers fit into `u128`"),
+                };
+                Diagnostic::error(format!("literal out of range for `{}`", ty))
+                    .with_code(codes::E0016)
+                    .with_primary(src_data, "")
+                    .with_note(format!(
+                        "the literal `{}` does not fit into the type `{}` whose range is `{}..={}`",
+                        text(),
+                        ty,
+                        min,
+                        max
+                    ))
+            }
+            LitError::FloatOutOfRange(ty) => Diagnostic::error(format!("literal out of range for `{}`", ty.name()))
+                .with_code(codes::E0016)
+                .with_primary(src_data, "")
+                .with_note(format!("the literal `{}` does not fit into the type `{}` and will be converted to infinity", text(), ty.name())),
+            // already reported by the lexer
+            LitError::InvalidEscape => return LitValue::Err,
+        };
+        self.psess.dcx.emit(diag);
+        LitValue::Err
+    }
+}
@@ -0,0 +1,184 @@
+use ast::{
+    ast::{AttrStyle, Attribute, Crate, Fn, FnRetTy, FnSig, Ident, Item, ItemKind, ModKind, Param, P},
+    token::{Delimiter, TokenKind},
+};
+use diagnostics::{codes, Applicability, Diagnostic};
+use source_idx::kw;
+
+use super::{PResult, Parser};
+
+impl<'a> Parser<'a> {
+    /// parses all items of a file, syntax errors are reported and replaced by [`ItemKind::Err`]
+    pub fn parse_crate_mod(&mut self) -> Crate {
+        let lo = self.token.src_data;
+        let attrs = self.parse_inner_attributes();
+        let items = self.parse_mod_items(TokenKind::Eof);
+        let src_data = lo.to(self.prev_token.src_data);
+        Crate { attrs, items, id: self.psess.next_node_id(), src_data }
+    }
+
+    /// parses items until `end`, which is not eaten
+    fn parse_mod_items(&mut self, end: TokenKind) -> Vec<P<Item>> {
+        let mut items = Vec::new();
+        while !self.check(end) && !self.check(TokenKind::Eof) {
+            let lo = self.token.src_data;
+            let start = self.recovery_start();
+            let err = match self.parse_item() {
+                Ok(Some(item)) => {
+                    items.push(item);
+                    continue;
+                }
+                Ok(None) => {
+                    let err = self.expected("item");
+                    // the token can not start an item, so it is never a synchronisation point
+                    self.bump();
+                    err
+                }
+                Err(err) => err,
+            };
+            self.emit_recovered(err);
+            self.recover(start, TokenKind::Semi);
+            let src_data = lo.to(self.prev_token.src_data);
+            let ident = Ident::new(kw::Empty, lo.shrink_to_lo());
+            let id = self.psess.next_node_id();
+            items.push(Box::new(Item { attrs: Vec::new(), id, ident, kind: ItemKind::Err, src_data }));
+        }
+        items
+    }
+
+    /// returns `None` if there is no item at the current token
+    pub fn parse_item(&mut self) -> PResult<Option<P<Item>>> {
+        let lo = self.token.src_data;
+        let mut attrs = self.parse_outer_attributes()?;
+        let (ident, kind) = if self.check_keyword(kw::Fn) {
+            self.parse_fn()?
+        } else if self.check_keyword(kw::Mod) {
+            self.parse_mod(&mut attrs)?
+        } else {
+            if let Some(last) = attrs.last() {
+                return Err(self
+                    .expected("item after attributes")
+                    .with_secondary(last.src_data, "attributes have to be followed by an item"));
+            }
+            return Ok(None);
+        };
+        let src_data = lo.to(self.prev_token.src_data);
+        Ok(Some(Box::new(Item { attrs, id: self.psess.next_node_id(), ident, kind, src_data })))
+    }
+
+    /// true if the current token starts an item
+    pub fn check_item_start(&self) -> bool {
+        self.check_keyword(kw::Fn)
+            || self.check_keyword(kw::Mod)
+            || self.check(TokenKind::Hashtag)
+            || matches!(self.token.kind, TokenKind::DocComment(_, AttrStyle::Outer, _))
+    }
+
+    /// `mod name { items }` or `mod name;`, the inner attributes are added to `attrs`
+    fn parse_mod(&mut self, attrs: &mut Vec<Attribute>) -> PResult<(Ident, ItemKind)> {
+        self.bump();
+        let ident = self.parse_ident()?;
+        if self.eat(TokenKind::Semi) {
+            return Ok((ident, ItemKind::Mod(ModKind::Unloaded)));
+        }
+        self.expect(TokenKind::OpenDelim(Delimiter::Brace))?;
+        attrs.extend(self.parse_inner_attributes());
+        let items = self.parse_mod_items(TokenKind::CloseDelim(Delimiter::Brace));
+        self.expect_close(Delimiter::Brace);
+        Ok((ident, ItemKind::Mod(ModKind::Loaded(items))))
+    }
+
+    /// `fn name(params) ret { body }` or `fn name(params) ret;`
+    fn parse_fn(&mut self) -> PResult<(Ident, ItemKind)> {
+        let lo = self.token.src_data;
+        self.bump();
+        let ident = self.parse_ident()?;
+
+        self.expect(TokenKind::OpenDelim(Delimiter::Parenthesis))?;
+        let mut inputs = Vec::new();
+        while !self.check(TokenKind::CloseDelim(Delimiter::Parenthesis)) {
+            let start = self.recovery_start();
+            match self.parse_param() {
+                Ok(param) => inputs.push(param),
+                Err(err) => {
+                    // the other parameters can still be parsed
+                    self.emit_recovered(err);
+                    self.recover(start, TokenKind::Comma);
+                    continue;
+                }
+            }
+            if !self.eat(TokenKind::Comma) {
+                break;
+            }
+        }
+        self.expect(TokenKind::CloseDelim(Delimiter::Parenthesis))?;
+        let output = self.parse_ret_ty()?;
+        let sig = FnSig { inputs, output, src_data: lo.to(self.prev_token.src_data) };
+
+        let body = if self.eat(TokenKind::Semi) {
+            None
+        } else if self.check(TokenKind::OpenDelim(Delimiter::Brace)) {
+            Some(self.parse_block()?)
+        } else {
+            return Err(self.expected("`;` or `{`"));
+        };
+        Ok((ident, ItemKind::Fn(Box::new(Fn { sig, body }))))
+    }
+
+    /// `ident: ty` or `ident: ty = default`
+    fn parse_param(&mut self) -> PResult<Param> {
+        let lo = self.token.src_data;
+        let ident = self.parse_ident()?;
+        self.expect(TokenKind::Colon)?;
+        let ty = self.parse_ty()?;
+        let default = if self.eat(TokenKind::Eq) { Some(self.parse_expr()?) } else { None };
+        let src_data = lo.to(self.prev_token.src_data);
+        Ok(Param { id: self.psess.next_node_id(), ident, ty, default, src_data })
+    }
+
+    /// the return type behind the parameters
+    ///
+    /// `T`, `T!`, `T?`, `T!E`, `!E`, `!` or nothing
+    fn parse_ret_ty(&mut self) -> PResult<FnRetTy> {
+        if self.check(TokenKind::RArrow) {
+            self.emit_recovered(
+                Diagnostic::error("return types are written without `->`")
+                    .with_code(codes::E0015)
+                    .with_primary(self.token.src_data, "")
+                    .with_suggestion(self.token.src_data, "remove the `->`", "", Applicability::MachineApplicable),
+            );
+            self.bump();
+        }
+
+        if self.check_fn_body_start() {
+            return Ok(FnRetTy::Default(self.prev_token.src_data.shrink_to_hi()));
+        }
+        if self.eat(TokenKind::Not) {
+            let bang = self.prev_token.src_data;
+            if self.check_fn_body_start() {
+                return Ok(FnRetTy::Never(bang));
+            }
+            let err = self.parse_ty()?;
+            return Ok(FnRetTy::Result { ok: None, bang, err });
+        }
+
+        let ty = self.parse_ty()?;
+        if self.eat(TokenKind::Question) {
+            return Ok(FnRetTy::Option { ty, bang: self.prev_token.src_data });
+        }
+        if !self.eat(TokenKind::Not) {
+            return Ok(FnRetTy::Ty(ty));
+        }
+        let bang = self.prev_token.src_data;
+        if self.check_fn_body_start() {
+            return Ok(FnRetTy::Option { ty, bang });
+        }
+        let err = self.parse_ty()?;
+        Ok(FnRetTy::Result { ok: Some(ty), bang, err })
+    }
+
+    /// true if the current token ends a function signature
+    fn check_fn_body_start(&self) -> bool {
+        self.check(TokenKind::OpenDelim(Delimiter::Brace)) || self.check(TokenKind::Semi)
+    }
+}
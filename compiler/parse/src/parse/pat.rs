@@ -0,0 +1,112 @@
+use ast::{
+    ast::{Expr, ExprKind, Mutability, Pat, PatKind, RangeLimits, P},
+    lit::LitValue,
+    token::{BinOpToken, Delimiter, TokenKind},
+};
+use source_idx::kw;
+
+use super::{PResult, Parser, PathStyle};
+
+impl<'a> Parser<'a> {
+    pub fn parse_pat(&mut self) -> PResult<P<Pat>> {
+        let lo = self.token.src_data;
+        let kind = match self.token.kind {
+            TokenKind::Ident(kw::Underscore) => {
+                self.bump();
+                PatKind::Wild
+            }
+            TokenKind::DotDot => {
+                self.bump();
+                PatKind::Rest
+            }
+            TokenKind::BinOp(BinOpToken::And) | TokenKind::AndAnd => {
+                self.break_and_eat(TokenKind::BinOp(BinOpToken::And));
+                let mutbl = if self.eat_keyword(kw::Mut) { Mutability::Mut } else { Mutability::Not };
+                PatKind::Ref(self.parse_pat()?, mutbl)
+            }
+            TokenKind::OpenDelim(Delimiter::Parenthesis) => {
+                self.bump();
+                let mut pats = Vec::new();
+                let mut trailing_comma = false;
+                while !self.check(TokenKind::CloseDelim(Delimiter::Parenthesis)) {
+                    pats.push(self.parse_pat()?);
+                    trailing_comma = self.eat(TokenKind::Comma);
+                    if !trailing_comma {
+                        break;
+                    }
+                }
+                self.expect(TokenKind::CloseDelim(Delimiter::Parenthesis))?;
+                // `(pat)` is just `pat`
+                if pats.len() == 1 && !trailing_comma {
+                    let mut pat = pats.pop().unwrap();
+                    pat.src_data = lo.to(self.prev_token.src_data);
+                    return Ok(pat);
+                }
+                PatKind::Tuple(pats)
+            }
+            TokenKind::Ident(kw::Mut) => {
+                self.bump();
+                PatKind::Ident(Mutability::Mut, self.parse_ident()?)
+            }
+            // a binding, unless it is the start of a range or a path
+            TokenKind::Ident(_)
+                if self.check_ident()
+                    && !self.look_ahead(1, |t| {
+                        matches!(t.kind, TokenKind::DoubleColon | TokenKind::DotDot | TokenKind::DotDotEq)
+                    }) =>
+            {
+                PatKind::Ident(Mutability::Not, self.parse_ident()?)
+            }
+            TokenKind::DotDotEq => {
+                self.bump();
+                PatKind::Range(None, Some(self.parse_pat_range_end()?), RangeLimits::Closed)
+            }
+            _ => {
+                let start = self.parse_pat_range_end()?;
+                match self.eat_range_limits() {
+                    Some(limits) => {
+                        let end = if self.check_pat_range_end() { Some(self.parse_pat_range_end()?) } else { None };
+                        PatKind::Range(Some(start), end, limits)
+                    }
+                    None => match start.kind {
+                        ExprKind::Path(path) => PatKind::Path(path),
+                        _ => PatKind::Lit(start),
+                    },
+                }
+            }
+        };
+        Ok(self.mk_pat(kind, lo.to(self.prev_token.src_data)))
+    }
+
+    fn eat_range_limits(&mut self) -> Option<RangeLimits> {
+        if self.eat(TokenKind::DotDotEq) {
+            Some(RangeLimits::Closed)
+        } else if self.eat(TokenKind::DotDot) {
+            Some(RangeLimits::HalfOpen)
+        } else {
+            None
+        }
+    }
+
+    fn check_pat_range_end(&self) -> bool {
+        matches!(self.token.kind, TokenKind::Literal(_)) || self.check_path()
+    }
+
+    /// a literal or a path, which can be a bound of a range pattern
+    fn parse_pat_range_end(&mut self) -> PResult<P<Expr>> {
+        let lo = self.token.src_data;
+        let kind = match self.token.kind {
+            TokenKind::Literal(lit) => {
+                self.bump();
+                ExprKind::Lit(self.lit_value(lit, lo, false))
+            }
+            TokenKind::Ident(name) if name.is_bool_lit() => {
+                self.bump();
+                ExprKind::Lit(LitValue::Bool(name == kw::True))
+            }
+            _ if self.check_path() => ExprKind::Path(self.parse_path(PathStyle::Expr)?),
+            _ => return Err(self.expected("pattern")),
+        };
+        Ok(self.mk_expr(kind, lo.to(self.prev_token.src_data)))
+    }
+}
@@ -0,0 +1,77 @@
+use ast::{
+    ast::{GenericArgs, Ident, Path, PathSegment, P},
+    token::TokenKind,
+};
+
+use super::{is_ident_name, is_path_segment_keyword, PResult, Parser};
+
+/// Where a path is parsed, this decides how generic arguments are written
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PathStyle {
+    /// `Vec::<u8>::new`, the `::` is needed because `<` could also be a comparison
+    Expr,
+    /// `Vec<u8>`
+    Type,
+    /// paths of attributes, which have no generic arguments
+    Mod,
+}
+
+impl<'a> Parser<'a> {
+    /// `a::b::c`
+    pub fn parse_path(&mut self, style: PathStyle) -> PResult<Path> {
+        let lo = self.token.src_data;
+        let mut segments = vec![self.parse_path_segment(style)?];
+        while self.eat(TokenKind::DoubleColon) {
+            if style == PathStyle::Expr && self.check(TokenKind::Lt) {
+                // the generic arguments of the previous segment e.g. `Vec::<u8>`
+                let segment = segments.last_mut().unwrap();
+                segment.args = Some(self.parse_generic_args()?);
+                if !self.eat(TokenKind::DoubleColon) {
+                    break;
+                }
+            }
+            segments.push(self.parse_path_segment(style)?);
+        }
+        Ok(Path { segments, src_data: lo.to(self.prev_token.src_data) })
+    }
+
+    fn parse_path_segment(&mut self, style: PathStyle) -> PResult<PathSegment> {
+        let ident = match self.token.kind {
+            TokenKind::Ident(name) if is_path_segment_keyword(name) => {
+                self.bump();
+                Ident::new(name, self.prev_token.src_data)
+            }
+            _ => self.parse_ident()?,
+        };
+        let args = if style == PathStyle::Type && self.check(TokenKind::Lt) {
+            Some(self.parse_generic_args()?)
+        } else {
+            None
+        };
+        Ok(PathSegment { ident, id: self.psess.next_node_id(), args })
+    }
+
+    /// `<A, B>`
+    fn parse_generic_args(&mut self) -> PResult<P<GenericArgs>> {
+        let lo = self.token.src_data;
+        self.expect(TokenKind::Lt)?;
+        let mut args = Vec::new();
+        while !self.check_break(TokenKind::Gt) {
+            args.push(self.parse_ty()?);
+            if !self.eat(TokenKind::Comma) {
+                break;
+            }
+        }
+        // `>>` and `>=` also close the arguments e.g. `Vec<Vec<u8>>`
+        self.expect_break(TokenKind::Gt)?;
+        Ok(Box::new(GenericArgs { args, src_data: lo.to(self.prev_token.src_data) }))
+    }
+
+    /// true if the current token can start a path
+    pub fn check_path(&self) -> bool {
+        match self.token.kind {
+            TokenKind::Ident(name) => is_ident_name(name) || is_path_segment_keyword(name),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,96 @@
+//! Recovery from syntax errors, so that all of them are reported in one run
+//!
+//! A failed construct is reported and skipped up to the next synchronisation point,
+//! which is a `;`, the end of the enclosing group or the start of the next item.
+//! The skipped construct is replaced by an `Err` node like [`ast::ast::ExprKind::Err`].
+
+use ast::token::{Delimiter, TokenKind};
+use diagnostics::{Diagnostic, Level};
+use source_idx::SrcData;
+
+use super::Parser;
+
+/// the parser gives up after this many syntax errors, the later ones are mostly caused by the first ones
+pub const MAX_SYNTAX_ERRORS: usize = 20;
+
+/// where the parser was before it tried to parse a construct, see [`Parser::recover`]
+#[derive(Clone, Copy)]
+pub(super) struct RecoveryStart {
+    /// the first token of the construct
+    pub(super) src_data: SrcData,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// reports an error the parser recovers from
+    ///
+    /// an error at the same source as the previous one is dropped, it is caused by the same problem.
+    /// after [`MAX_SYNTAX_ERRORS`] the rest of the file is skipped
+    pub fn emit_recovered(&mut self, diag: Diagnostic) {
+        if self.syntax_errors >= MAX_SYNTAX_ERRORS {
+            return;
+        }
+        let src_data = diag.primary_src_data();
+        if src_data.is_some() && src_data == self.last_error {
+            return;
+        }
+        self.last_error = src_data;
+        self.psess.dcx.emit(diag);
+        self.syntax_errors += 1;
+
+        if self.syntax_errors == MAX_SYNTAX_ERRORS {
+            self.psess.dcx.emit(Diagnostic::new(
+                Level::Note,
+                format!("aborting after {} syntax errors", MAX_SYNTAX_ERRORS),
+            ));
+            while !self.check(TokenKind::Eof) {
+                self.bump();
+            }
+        }
+    }
+
+    pub(super) fn recovery_start(&self) -> RecoveryStart {
+        RecoveryStart { src_data: self.token.src_data, depth: self.token_depth() }
+    }
+
+    /// skips tokens up to the next synchronisation point after a construct failed to parse
+    ///
+    /// stops behind `sep` (usually `;`), before the closing delimiter of the group `start` is in
+    /// or before the start of an item. Tokens in nested groups are always skipped.
+    pub(super) fn recover(&mut self, start: RecoveryStart, sep: TokenKind) {
+        loop {
+            let depth = self.token_depth();
+            if self.check(TokenKind::Eof) || depth < start.depth {
+                return;
+            }
+            if depth == start.depth {
+                if self.eat(sep) {
+                    return;
+                }
+                // the error can be at the item keyword itself
+                if self.check_item_start() && self.token.src_data != start.src_data {
+                    return;
+                }
+            }
+            self.bump();
+        }
+    }
+
+    /// reports that `close` is missing and continues as if it was there
+    pub(super) fn expect_close(&mut self, delim: Delimiter) {
+        if !self.eat(TokenKind::CloseDelim(delim)) {
+            let err = self.expected(&format!("`{}`", delim.close_str().unwrap_or_default()));
+            self.emit_recovered(err);
+        }
+    }
+
+    /// the number of groups the current token is in, a delimiter is outside of its group
+    fn token_depth(&self) -> usize {
+        let depth = self.token_cursor.0.depth();
+        if matches!(self.token.kind, TokenKind::OpenDelim(_)) {
+            depth - 1
+        } else {
+            depth
+        }
+    }
+}
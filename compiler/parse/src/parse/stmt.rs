@@ -0,0 +1,115 @@
+use ast::{
+    ast::{Block, Expr, ExprKind, Local, Stmt, StmtKind, Ty, P},
+    token::{Delimiter, TokenKind},
+};
+use diagnostics::{Applicability, Diagnostic};
+use source_idx::{kw, SrcData};
+
+use super::{PResult, Parser};
+
+impl<'a> Parser<'a> {
+    /// `{ stmts }`
+    pub fn parse_block(&mut self) -> PResult<P<Block>> {
+        let lo = self.token.src_data;
+        self.expect(TokenKind::OpenDelim(Delimiter::Brace))?;
+        let mut stmts = Vec::new();
+        while !self.check(TokenKind::CloseDelim(Delimiter::Brace)) && !self.check(TokenKind::Eof) {
+            let start = self.recovery_start();
+            match self.parse_stmt() {
+                Ok(stmt) => stmts.push(stmt),
+                Err(err) => {
+                    self.emit_recovered(err);
+                    self.recover(start, TokenKind::Semi);
+                    let src_data = start.src_data.to(self.prev_token.src_data);
+                    let expr = self.mk_expr(ExprKind::Err, src_data);
+                    stmts.push(self.mk_stmt(StmtKind::Expr(expr), src_data));
+                }
+            }
+        }
+        self.expect_close(Delimiter::Brace);
+        let src_data = lo.to(self.prev_token.src_data);
+        Ok(Box::new(Block { stmts, id: self.psess.next_node_id(), src_data }))
+    }
+
+    fn parse_stmt(&mut self) -> PResult<Stmt> {
+        let lo = self.token.src_data;
+        if self.eat(TokenKind::Semi) {
+            return Ok(self.mk_stmt(StmtKind::Empty, lo));
+        }
+        if self.eat_keyword(kw::Let) {
+            let local = self.parse_local(lo)?;
+            return Ok(self.mk_stmt(StmtKind::Let(local), lo.to(self.prev_token.src_data)));
+        }
+        if self.check_item_start() {
+            if let Some(item) = self.parse_item()? {
+                let src_data = item.src_data;
+                return Ok(self.mk_stmt(StmtKind::Item(item), src_data));
+            }
+        }
+
+        // a block at the start of a statement is not the left side of a binary expression
+        let starts_block_like = self.check(TokenKind::OpenDelim(Delimiter::Brace))
+            || self.check_keyword(kw::If)
+            || self.check_keyword(kw::While)
+            || self.check_keyword(kw::Loop);
+        let expr = if starts_block_like {
+            self.parse_bottom_expr()?
+        } else {
+            self.parse_expr()?
+        };
+
+        let kind = if self.eat(TokenKind::Semi) {
+            StmtKind::Semi(expr)
+        } else if self.check(TokenKind::CloseDelim(Delimiter::Brace)) || expr.is_block_like() {
+            StmtKind::Expr(expr)
+        } else {
+            // continues as if the `;` was there
+            let err = self.expected("`;` or `}`");
+            self.emit_missing_semi(err);
+            StmtKind::Semi(expr)
+        };
+        Ok(self.mk_stmt(kind, lo.to(self.prev_token.src_data)))
+    }
+
+    /// `pat: ty = init;` after the `let`
+    ///
+    /// once the pattern is parsed the statement stays a `let`, a failed type or initializer is
+    /// skipped and replaced by an `Err` initializer
+    fn parse_local(&mut self, lo: SrcData) -> PResult<P<Local>> {
+        let pat = self.parse_pat()?;
+        let mut ty = None;
+        let mut init = None;
+        let start = self.recovery_start();
+        if let Err(err) = self.parse_local_ty_init(&mut ty, &mut init) {
+            self.emit_recovered(err);
+            self.recover(start, TokenKind::Semi);
+            let src_data = start.src_data.to(self.prev_token.src_data);
+            init = Some(self.mk_expr(ExprKind::Err, src_data));
+        } else if !self.eat(TokenKind::Semi) {
+            let err = self.expected("`;`");
+            self.emit_missing_semi(err);
+        }
+        let src_data = lo.to(self.prev_token.src_data);
+        Ok(Box::new(Local { id: self.psess.next_node_id(), pat, ty, init, src_data }))
+    }
+
+    /// `: ty = init` of a `let`, the parts are stored as soon as they are parsed
+    fn parse_local_ty_init(&mut self, ty: &mut Option<P<Ty>>, init: &mut Option<P<Expr>>) -> PResult<()> {
+        if self.eat(TokenKind::Colon) {
+            *ty = Some(self.parse_ty()?);
+        }
+        if self.eat(TokenKind::Eq) {
+            *init = Some(self.parse_expr()?);
+        }
+        Ok(())
+    }
+
+    fn emit_missing_semi(&mut self, err: Diagnostic) {
+        let semi = self.prev_token.src_data.shrink_to_hi();
+        self.emit_recovered(err.with_suggestion(semi, "add `;` here", ";", Applicability::MaybeIncorrect));
+    }
+
+    fn mk_stmt(&self, kind: StmtKind, src_data: SrcData) -> Stmt {
+        Stmt { id: self.psess.next_node_id(), kind, src_data }
+    }
+}
@@ -0,0 +1,442 @@
+use std::rc::Rc;
+
+use ast::{
+    ast::{
+        AttrArgs, AttrKind, AttrStyle, BinOpKind, CallArgKind, Crate, Expr, ExprKind, Fn, FnRetTy, Item, ItemKind, ModKind, Mutability, NodeId,
+        PatKind, RangeLimits, Stmt, StmtKind, TyKind, P,
+    },
+    lit::{LitIntType, LitValue},
+    token::CommentKind,
+    visit::{self, Visitor},
+};
+use diagnostics::{DiagCtxt, Diagnostic, SilentEmitter};
+use source_idx::{sym, FileName, SourceMap, SrcData, Symbol};
+
+use super::MAX_SYNTAX_ERRORS;
+use crate::{parse_crate_from_file, test_utils::primary_spans, ParseSess};
+
+/// parses `src` and returns the crate together with all emitted diagnostics
+fn parse(src: &str) -> (Crate, Vec<Diagnostic>) {
+    let source_map = Rc::new(SourceMap::new());
+    let psess = ParseSess::with_dcx(DiagCtxt::new(Box::new(SilentEmitter)), source_map);
+    let file = psess.source_map.new_source_file(FileName::Anon("test.sl".into()), src.into());
+    let krate = parse_crate_from_file(&psess, &file);
+    (krate, psess.dcx.diagnostics())
+}
+
+fn parse_ok(src: &str) -> Crate {
+    let (krate, diags) = parse(src);
+    assert_eq!(diags, Vec::new());
+    krate
+}
+
+fn parse_err(src: &str) -> Vec<String> {
+    let (_, diags) = parse(src);
+    assert!(!diags.is_empty(), "expected errors for {:?}", src);
+    diags.into_iter().map(|diag| diag.message).collect()
+}
+
+fn fn_item(item: &Item) -> &Fn {
+    match &item.kind {
+        ItemKind::Fn(func) => func,
+        kind => panic!("expected a function, found {:?}", kind),
+    }
+}
+
+/// the statements of the body of the first function
+fn body_stmts(krate: &Crate) -> &[Stmt] {
+    &fn_item(&krate.items[0]).body.as_ref().unwrap().stmts
+}
+
+/// the values of the literals which are the statements of the first function
+fn lit_values(krate: &Crate) -> Vec<LitValue> {
+    body_stmts(krate).iter().map(|stmt| match &stmt.kind {
+        StmtKind::Semi(expr) | StmtKind::Expr(expr) => match &expr.kind {
+            ExprKind::Lit(value) => value.clone(),
+            kind => panic!("expected a literal, found {:?}", kind),
+        },
+        kind => panic!("expected an expression, found {:?}", kind),
+    }).collect()
+}
+
+fn fn_ret_ty(src: &str) -> FnRetTy {
+    let krate = parse_ok(src);
+    let item = krate.items.into_iter().next().unwrap();
+    let ItemKind::Fn(func) = item.kind else { panic!("expected a function") };
+    func.sig.output
+}
+
+fn ty_name(ty: &TyKind) -> Symbol {
+    match ty {
+        TyKind::Path(path) => path.segments.last().unwrap().ident.name,
+        _ => panic!("expected a path type, found {:?}", ty),
+    }
+}
+
+#[test]
+fn mock() {
+    let krate = parse_ok(include_str!("../../../../mock.sl"));
+    let names: Vec<_> = krate.items.iter().map(|item| item.ident.name.as_str()).collect();
+    assert_eq!(names, ["main", "another_function", "basic_operations"]);
+
+    let main = &krate.items[0];
+    // the two doc comments are attributes as well
+    assert_eq!(main.attrs.len(), 3);
+    let AttrKind::DocComment(CommentKind::Block, text) = main.attrs[1].kind else { panic!("expected a doc comment") };
+    assert_eq!(text.as_str(), " another documentation *");
+    assert!(main.attrs[2].has_name(Symbol::get_or_store("entry")));
+    let main_fn = fn_item(main);
+    assert!(matches!(main_fn.sig.output, FnRetTy::Default(_)));
+    let stmts = &main_fn.body.as_ref().unwrap().stmts;
+    assert_eq!(stmts.len(), 4);
+    // the last call is the value of the block as it has no `;`
+    assert!(stmts[..3].iter().all(|stmt| matches!(stmt.kind, StmtKind::Semi(_))));
+    let StmtKind::Expr(last) = &stmts[3].kind else { panic!("expected a trailing expression") };
+    let ExprKind::Call(_, args) = &last.kind else { panic!("expected a call") };
+    assert!(matches!(args[0].kind, CallArgKind::Positional(_)));
+    assert!(matches!(&args[1].kind, CallArgKind::Named(ident, _) if ident.name.as_str() == "offset"));
+    let CallArgKind::Spread(spread) = &args[2].kind else { panic!("expected a spread argument") };
+    assert!(matches!(&spread.kind, ExprKind::MacCall(mac) if mac.path.is_ident(Symbol::get_or_store("def"))));
+
+    let another = fn_item(&krate.items[1]);
+    let params: Vec<_> = another.sig.inputs.iter().map(|p| (p.ident.name.as_str(), p.default.is_some())).collect();
+    assert_eq!(params, [("factor", true), ("string", false), ("offset", true)]);
+    assert!(matches!(&another.sig.inputs[1].ty.kind, TyKind::Ref(_, ty) if ty_name(&ty.kind).as_str() == "str"));
+    assert!(matches!(&another.sig.output, FnRetTy::Ty(ty) if ty_name(&ty.kind) == sym::usize));
+    let body = &another.body.as_ref().unwrap().stmts;
+    let StmtKind::Expr(expr) = &body[0].kind else { panic!("expected a trailing expression") };
+    let ExprKind::Binary(op, lhs, _) = &expr.kind else { panic!("expected a binary expression") };
+    assert_eq!(op.kind, BinOpKind::Mul);
+    assert!(matches!(&lhs.kind, ExprKind::MethodCall(call) if call.ident.name.as_str() == "len"));
+}
+
+#[test]
+fn ret_ty_sugar() {
+    assert!(matches!(fn_ret_ty("fn a() {}"), FnRetTy::Default(_)));
+    assert!(matches!(fn_ret_ty("fn a() String {}"), FnRetTy::Ty(_)));
+    assert!(matches!(fn_ret_ty("fn a() String! {}"), FnRetTy::Option { .. }));
+    assert!(matches!(fn_ret_ty("fn a() String? {}"), FnRetTy::Option { .. }));
+    assert!(matches!(fn_ret_ty("fn a() String!Error {}"), FnRetTy::Result { ok: Some(_), .. }));
+    assert!(matches!(fn_ret_ty("fn a() !Error {}"), FnRetTy::Result { ok: None, .. }));
+    assert!(matches!(fn_ret_ty("fn a() ! {}"), FnRetTy::Never(_)));
+    assert!(matches!(fn_ret_ty("fn a() &[u8]!;"), FnRetTy::Option { .. }));
+}
+
+#[test]
+fn literals() {
+    let values = lit_values(&parse_ok("fn a() { 0b1111_1111_u8; 1.5; true; \"a\\tb\" }"));
+    assert!(matches!(values[0], LitValue::Int(255, LitIntType::Unsigned(_))));
+    assert!(matches!(values[1], LitValue::Float(..)));
+    assert_eq!(values[2], LitValue::Bool(true));
+    assert!(matches!(values[3], LitValue::Str(s, _) if s.as_str() == "a\tb"));
+
+    assert_eq!(parse_err("fn a() { 256u8 }"), ["literal out of range for `u8`"]);
+}
+
+#[test]
+fn int_bases() {
+    let values = lit_values(&parse_ok("fn a() { 0xff_u8; 0x1e5; 0o17; 0b1010 }"));
+    assert!(matches!(
+        values[..],
+        [LitValue::Int(0xff, _), LitValue::Int(0x1e5, _), LitValue::Int(0o17, _), LitValue::Int(0b1010, _)]
+    ));
+
+    // every invalid digit is reported at its own position
+    let (_, diags) = parse("fn a() { 0b1021_3; 0o78 }");
+    assert_eq!(
+        primary_spans(&diags),
+        [
+            ("invalid digit for a base 2 literal", 13, 14),
+            ("invalid digit for a base 2 literal", 16, 17),
+            ("invalid digit for a base 8 literal", 22, 23),
+        ]
+    );
+    assert_eq!(diags[0].labels[0].label.as_deref(), Some("not a binary digit"));
+}
+
+#[test]
+fn nested_items_and_attrs() {
+    let krate = parse_ok("#[inline(always)] #[test] fn a() { fn b(); ; (1, 2).0 }");
+    assert_eq!(krate.items[0].attrs.len(), 2);
+    let stmts = body_stmts(&krate);
+    assert!(matches!(stmts[0].kind, StmtKind::Item(_)));
+    assert!(matches!(stmts[1].kind, StmtKind::Empty));
+    assert!(matches!(&stmts[2].kind, StmtKind::Expr(e) if matches!(&e.kind, ExprKind::Field(_, f) if f.name.as_str() == "0")));
+}
+
+#[test]
+fn inner_attributes() {
+    let krate = parse_ok("#![allow(warnings)] //! docs\n#![doc = \"text\"] mod m { #![cfg(test)] fn a() {} }");
+    let styles: Vec<_> = krate.attrs.iter().map(|attr| attr.style).collect();
+    assert_eq!(styles, [AttrStyle::Inner; 3]);
+    let AttrKind::Normal(item) = &krate.attrs[2].kind else { panic!("expected a normal attribute") };
+    assert!(matches!(&item.args, AttrArgs::Eq(_, expr) if matches!(expr.kind, ExprKind::Lit(_))));
+    assert!(krate.items[0].attrs[0].has_name(Symbol::get_or_store("cfg")));
+    assert_eq!(krate.items[0].attrs[0].style, AttrStyle::Inner);
+
+    assert_eq!(parse_err("fn a() {} #![inline] fn b() {}"), ["an inner attribute is not permitted in this context"]);
+    // the rest of the attribute is skipped
+    let (krate, diags) = parse("#![inline(always) x] fn a() {}");
+    assert_eq!(diags.len(), 1);
+    assert!(matches!(krate.items[..], [ref item] if matches!(item.kind, ItemKind::Fn(_))));
+}
+
+#[test]
+fn patterns_and_control_flow() {
+    let krate = parse_ok(
+        "fn a() {
+            let (mut x, _, &y): (u8, Vec<u8>, &u8) = (1, v, &2);
+            let 1..=9 = x;
+            if x { loop { break 1; } } else if y { while z { continue; } } else {}
+            let f = |a, b: u8| a;
+        }",
+    );
+    let stmts = body_stmts(&krate);
+    let StmtKind::Let(local) = &stmts[0].kind else { panic!("expected a let statement") };
+    let PatKind::Tuple(pats) = &local.pat.kind else { panic!("expected a tuple pattern") };
+    assert!(matches!(pats[0].kind, PatKind::Ident(Mutability::Mut, _)));
+    assert!(matches!(pats[1].kind, PatKind::Wild));
+    assert!(matches!(pats[2].kind, PatKind::Ref(..)));
+    let Some(TyKind::Tup(tys)) = local.ty.as_ref().map(|ty| &ty.kind) else { panic!("expected a tuple type") };
+    let TyKind::Path(vec) = &tys[1].kind else { panic!("expected a path") };
+    assert_eq!(vec.segments[0].args.as_ref().unwrap().args.len(), 1);
+
+    let StmtKind::Let(range) = &stmts[1].kind else { panic!("expected a let statement") };
+    assert!(matches!(range.pat.kind, PatKind::Range(Some(_), Some(_), RangeLimits::Closed)));
+    let StmtKind::Expr(if_expr) = &stmts[2].kind else { panic!("expected an if without `;`") };
+    assert!(matches!(&if_expr.kind, ExprKind::If(_, _, Some(els)) if matches!(els.kind, ExprKind::If(..))));
+    let StmtKind::Let(closure) = &stmts[3].kind else { panic!("expected a let statement") };
+    assert!(matches!(&closure.init.as_ref().unwrap().kind, ExprKind::Closure(c) if c.params.len() == 2));
+}
+
+#[test]
+fn modules_and_generic_paths() {
+    let krate = parse_ok("mod a { mod b; fn c() () {} } fn d() { Vec::<u8>::new() }");
+    let ItemKind::Mod(ModKind::Loaded(items)) = &krate.items[0].kind else { panic!("expected a module") };
+    assert!(matches!(items[0].kind, ItemKind::Mod(ModKind::Unloaded)));
+    assert!(matches!(&fn_item(&items[1]).sig.output, FnRetTy::Ty(ty) if matches!(&ty.kind, TyKind::Tup(tys) if tys.is_empty())));
+
+    let d = fn_item(&krate.items[1]);
+    let StmtKind::Expr(call) = &d.body.as_ref().unwrap().stmts[0].kind else { panic!("expected an expression") };
+    let ExprKind::Call(func, _) = &call.kind else { panic!("expected a call") };
+    let ExprKind::Path(path) = &func.kind else { panic!("expected a path") };
+    assert_eq!(path.segments.len(), 2);
+    assert!(path.segments[0].args.is_some());
+}
+
+#[test]
+fn node_ids_are_unique() {
+    struct CollectIds(Vec<NodeId>);
+    impl<'ast> Visitor<'ast> for CollectIds {
+        fn visit_expr(&mut self, expr: &'ast Expr) {
+            self.0.push(expr.id);
+            visit::walk_expr(self, expr);
+        }
+        fn visit_stmt(&mut self, stmt: &'ast Stmt) {
+            self.0.push(stmt.id);
+            visit::walk_stmt(self, stmt);
+        }
+        fn visit_item(&mut self, item: &'ast Item) {
+            self.0.push(item.id);
+            visit::walk_item(self, item);
+        }
+    }
+
+    let krate = parse_ok(include_str!("../../../../mock.sl"));
+    let mut ids = CollectIds(Vec::new());
+    ids.visit_crate(&krate);
+    let count = ids.0.len();
+    ids.0.sort();
+    ids.0.dedup();
+    assert_eq!(ids.0.len(), count);
+    assert!(count > 30);
+}
+
+/// prints an expression with parentheses around every operation,
+/// expressions written in parentheses are printed with a single pair of them
+fn expr_to_string(expr: &Expr, top: bool) -> String {
+    let wrap = |s: String| if top { s } else { format!("({})", s) };
+    let opt = |expr: &Option<P<Expr>>| expr.as_ref().map(|e| expr_to_string(e, false)).unwrap_or_default();
+    match &expr.kind {
+        ExprKind::Lit(LitValue::Int(value, _)) => value.to_string(),
+        ExprKind::Lit(value) => format!("{:?}", value),
+        ExprKind::Path(path) => {
+            path.segments.iter().map(|s| s.ident.name.as_str()).collect::<Vec<_>>().join("::")
+        }
+        ExprKind::Paren(expr) => format!("({})", expr_to_string(expr, true)),
+        ExprKind::Binary(op, lhs, rhs) => wrap(format!(
+            "{} {} {}",
+            expr_to_string(lhs, false),
+            op.kind.as_str(),
+            expr_to_string(rhs, false)
+        )),
+        ExprKind::AssignOp(op, lhs, rhs) => wrap(format!(
+            "{} {}= {}",
+            expr_to_string(lhs, false),
+            op.kind.as_str(),
+            expr_to_string(rhs, false)
+        )),
+        ExprKind::Assign(lhs, rhs, _) => {
+            wrap(format!("{} = {}", expr_to_string(lhs, false), expr_to_string(rhs, false)))
+        }
+        ExprKind::Unary(op, expr) => wrap(format!("{}{}", op.as_str(), expr_to_string(expr, false))),
+        ExprKind::AddrOf(Mutability::Not, expr) => wrap(format!("&{}", expr_to_string(expr, false))),
+        ExprKind::AddrOf(Mutability::Mut, expr) => wrap(format!("&mut {}", expr_to_string(expr, false))),
+        ExprKind::Range(start, end, limits) => {
+            let op = if *limits == RangeLimits::Closed { "..=" } else { ".." };
+            wrap(format!("{}{}{}", opt(start), op, opt(end)))
+        }
+        ExprKind::Try(expr) => format!("{}?", expr_to_string(expr, false)),
+        ExprKind::Index(expr, index) => format!("{}[{}]", expr_to_string(expr, false), expr_to_string(index, true)),
+        ExprKind::Field(expr, ident) => format!("{}.{}", expr_to_string(expr, false), ident.name),
+        ExprKind::Call(func, args) => format!("{}({})", expr_to_string(func, false), args.len()),
+        ExprKind::MethodCall(call) => {
+            format!("{}.{}({})", expr_to_string(&call.receiver, false), call.ident.name, call.args.len())
+        }
+        kind => panic!("unexpected expression {:?}", kind),
+    }
+}
+
+/// parses `expr` as the body of a function and prints it with [`expr_to_string`]
+fn parenthesize(expr: &str) -> String {
+    let krate = parse_ok(&format!("fn a() {{ {} }}", expr));
+    let StmtKind::Expr(expr) = &body_stmts(&krate)[0].kind else { panic!("expected an expression") };
+    expr_to_string(expr, true)
+}
+
+#[test]
+fn basic_operations() {
+    // the order documented in `mock.sl`
+    assert_eq!(parenthesize("(4 + 2 * 8 - 7 << 2) / 4"), "(((4 + (2 * 8)) - 7) << 2) / 4");
+
+    let krate = parse_ok(include_str!("../../../../mock.sl"));
+    let StmtKind::Expr(expr) = &fn_item(&krate.items[2]).body.as_ref().unwrap().stmts[0].kind else {
+        panic!("expected a trailing expression")
+    };
+    assert_eq!(expr_to_string(expr, true), "(((4 + (2 * 8)) - 7) << 2) / 4");
+}
+
+#[test]
+fn operator_precedence() {
+    let cases = [
+        ("a = b = c", "a = (b = c)"),
+        ("a += b * c", "a += (b * c)"),
+        ("a || b && c || d", "(a || (b && c)) || d"),
+        ("a == b && c != d", "(a == b) && (c != d)"),
+        ("a | b ^ c & d", "a | (b ^ (c & d))"),
+        ("a < b + c", "a < (b + c)"),
+        ("-a * b", "(-a) * b"),
+        ("!a == b", "(!a) == b"),
+        ("~a & *b", "(~a) & (*b)"),
+        ("&mut a[0] - &b", "(&mut a[0]) - (&b)"),
+        ("-1.abs()", "-1.abs(0)"),
+        ("a.b()?[c]? + 1", "a.b(0)?[c]? + 1"),
+        ("1..2 + 3", "1..(2 + 3)"),
+        ("..=a", "..=a"),
+        ("a.. ", "a.."),
+        ("x = 0..n", "x = (0..n)"),
+        ("a - b - c", "(a - b) - c"),
+        ("a % b * c / d", "((a % b) * c) / d"),
+    ];
+    for (src, expected) in cases {
+        assert_eq!(parenthesize(src), expected, "wrong precedence for `{}`", src);
+    }
+}
+
+#[test]
+fn literal_ranges() {
+    assert_eq!(parenthesize("-128i8"), "-128");
+    assert_eq!(parse_err("fn a() { 128i8 }"), ["literal out of range for `i8`"]);
+    assert_eq!(parse_err("fn a() { -129i8 }"), ["literal out of range for `i8`"]);
+    assert_eq!(parse_err("fn a() { -1u8 }"), ["literal out of range for `u8`"]);
+}
+
+#[test]
+fn chained_comparison() {
+    assert_eq!(parse_err("fn a() { a < b < c }"), ["comparison operators cannot be chained"]);
+    assert_eq!(parse_err("fn a() { a == b != c }"), ["comparison operators cannot be chained"]);
+    parse_ok("fn a() { (a < b) < c }");
+}
+
+#[test]
+fn syntax_errors() {
+    assert_eq!(parse_err("fn (a: u8) {}"), ["expected identifier, found `(`"]);
+    assert_eq!(parse_err("fn a(b u8) {}"), ["expected `:`, found `u8`"]);
+    assert_eq!(parse_err("fn a() { 1 2 }"), ["expected `;` or `}`, found literal `2`"]);
+    assert_eq!(parse_err("fn a(fn: u8) {}"), ["expected identifier, found keyword `fn`"]);
+    assert_eq!(parse_err("#[entry]"), ["expected item after attributes, found end of file"]);
+    assert_eq!(parse_err("fn a() -> u8 { 1 }"), ["return types are written without `->`"]);
+}
+
+#[test]
+fn split_glued_tokens() {
+    let krate = parse_ok(
+        "fn a() {
+            let v: Vec<Vec<u8>>= &&x;
+            let &&y = v;
+            let f = || 1;
+            let a..=b = y;
+        }",
+    );
+    let stmts = body_stmts(&krate);
+    let StmtKind::Let(local) = &stmts[0].kind else { panic!("expected a let statement") };
+    let TyKind::Path(outer) = &local.ty.as_ref().unwrap().kind else { panic!("expected a path type") };
+    let outer_args = outer.segments[0].args.as_ref().unwrap();
+    let TyKind::Path(inner) = &outer_args.args[0].kind else { panic!("expected a path type") };
+    let inner_args = inner.segments[0].args.as_ref().unwrap();
+    // each half of `>>=` belongs to another group of arguments
+    let len = |src_data: SrcData| src_data.hi().0 - src_data.lo().0;
+    assert_eq!(len(inner_args.src_data), "<u8>".len() as u32);
+    assert_eq!(len(outer_args.src_data), "<Vec<u8>>".len() as u32);
+
+    let init = local.init.as_ref().unwrap();
+    let ExprKind::AddrOf(_, inner_ref) = &init.kind else { panic!("expected a reference") };
+    assert!(matches!(inner_ref.kind, ExprKind::AddrOf(..)));
+    assert_eq!((len(init.src_data), len(inner_ref.src_data)), (3, 2));
+
+    let StmtKind::Let(local) = &stmts[1].kind else { panic!("expected a let statement") };
+    assert!(matches!(&local.pat.kind, PatKind::Ref(pat, _) if matches!(pat.kind, PatKind::Ref(..))));
+    let StmtKind::Let(local) = &stmts[2].kind else { panic!("expected a let statement") };
+    assert!(matches!(&local.init.as_ref().unwrap().kind, ExprKind::Closure(c) if c.params.is_empty()));
+    let StmtKind::Let(local) = &stmts[3].kind else { panic!("expected a let statement") };
+    assert!(matches!(local.pat.kind, PatKind::Range(Some(_), Some(_), RangeLimits::Closed)));
+}
+
+#[test]
+fn recovery() {
+    let (krate, diags) = parse("fn a() { let x = ; let = 2; let y = 1 } fn b(c u8, d: u8) { 1 2 } mod m { fn } fn c() {}");
+    let messages: Vec<_> = diags.iter().map(|diag| diag.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        [
+            "expected expression, found `;`",
+            "expected pattern, found `=`",
+            "expected `;`, found `}`",
+            "expected `:`, found `u8`",
+            "expected `;` or `}`, found literal `2`",
+            "expected identifier, found `}`",
+        ]
+    );
+    assert_eq!(krate.items.len(), 4);
+    // the binding of a `let` with a failed initializer is kept
+    let stmts = body_stmts(&krate);
+    let StmtKind::Let(local) = &stmts[0].kind else { panic!("expected a `let`, found {:?}", stmts[0].kind) };
+    assert!(matches!(local.pat.kind, PatKind::Ident(_, ident) if ident.name.as_str() == "x"));
+    assert!(matches!(local.init.as_deref(), Some(Expr { kind: ExprKind::Err, .. })));
+    assert_eq!((stmts[0].src_data.lo().0, stmts[0].src_data.hi().0), (9, 18));
+    // a statement without a pattern is replaced as a whole, starting at the `let`
+    assert!(matches!(&stmts[1].kind, StmtKind::Expr(expr) if matches!(expr.kind, ExprKind::Err)));
+    assert_eq!((stmts[1].src_data.lo().0, stmts[1].src_data.hi().0), (19, 27));
+    assert_eq!(fn_item(&krate.items[1]).sig.inputs.len(), 1);
+    let ItemKind::Mod(ModKind::Loaded(items)) = &krate.items[2].kind else { panic!("expected a module") };
+    assert!(matches!(items[0].kind, ItemKind::Err));
+    assert!(matches!(krate.items[3].kind, ItemKind::Fn(_)));
+}
+
+#[test]
+fn cascading_errors_are_capped() {
+    let src = "fn a() {".to_string() + &"let = 1;".repeat(MAX_SYNTAX_ERRORS + 5) + "}";
+    let (_, diags) = parse(&src);
+    assert_eq!(diags.iter().filter(|diag| diag.is_error()).count(), MAX_SYNTAX_ERRORS);
+    assert_eq!(diags.last().unwrap().message, format!("aborting after {} syntax errors", MAX_SYNTAX_ERRORS));
+}
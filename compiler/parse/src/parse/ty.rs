@@ -0,0 +1,65 @@
+use ast::{
+    ast::{Mutability, TyKind, P},
+    token::{BinOpToken, Delimiter, TokenKind},
+};
+use source_idx::kw;
+
+use super::{PResult, Parser, PathStyle};
+
+impl<'a> Parser<'a> {
+    pub fn parse_ty(&mut self) -> PResult<P<ast::ast::Ty>> {
+        let lo = self.token.src_data;
+        let kind = if self.break_and_eat(TokenKind::BinOp(BinOpToken::And)) {
+            let mutbl = if self.eat_keyword(kw::Mut) { Mutability::Mut } else { Mutability::Not };
+            TyKind::Ref(mutbl, self.parse_ty()?)
+        } else if self.eat(TokenKind::OpenDelim(Delimiter::Bracket)) {
+            let elem = self.parse_ty()?;
+            let kind = if self.eat(TokenKind::Semi) {
+                TyKind::Array(elem, self.parse_expr()?)
+            } else {
+                TyKind::Slice(elem)
+            };
+            self.expect(TokenKind::CloseDelim(Delimiter::Bracket))?;
+            kind
+        } else if self.eat(TokenKind::OpenDelim(Delimiter::Parenthesis)) {
+            let mut elems = Vec::new();
+            let mut trailing_comma = false;
+            while !self.check(TokenKind::CloseDelim(Delimiter::Parenthesis)) {
+                elems.push(self.parse_ty()?);
+                trailing_comma = self.eat(TokenKind::Comma);
+                if !trailing_comma {
+                    break;
+                }
+            }
+            self.expect(TokenKind::CloseDelim(Delimiter::Parenthesis))?;
+            // `(T)` is just `T` and `(T,)` is a tuple with one element
+            if elems.len() == 1 && !trailing_comma {
+                let mut ty = elems.pop().unwrap();
+                ty.src_data = lo.to(self.prev_token.src_data);
+                return Ok(ty);
+            }
+            TyKind::Tup(elems)
+        } else if self.eat(TokenKind::Not) {
+            TyKind::Never
+        } else if self.eat_keyword(kw::Underscore) {
+            TyKind::Infer
+        } else if self.check_path() {
+            TyKind::Path(self.parse_path(PathStyle::Type)?)
+        } else {
+            return Err(self.expected("type"));
+        };
+        Ok(self.mk_ty(kind, lo.to(self.prev_token.src_data)))
+    }
+
+    /// true if the current token can start a type
+    pub fn check_ty(&self) -> bool {
+        match self.token.kind {
+            TokenKind::BinOp(BinOpToken::And)
+            | TokenKind::AndAnd
+            | TokenKind::OpenDelim(Delimiter::Bracket | Delimiter::Parenthesis)
+            | TokenKind::Not => true,
+            TokenKind::Ident(name) => name == kw::Underscore || self.check_path(),
+            _ => false,
+        }
+    }
+}
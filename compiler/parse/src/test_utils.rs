@@ -0,0 +1,29 @@
+//! helpers shared by the tests of the lexer and the parser
+
+use std::rc::Rc;
+
+use ast::tokenstream::TokenStream;
+use diagnostics::{DiagCtxt, Diagnostic, SilentEmitter};
+use source_idx::{FileName, SourceMap};
+
+use crate::{lex::parse_token_trees, ParseSess};
+
+/// lexes `src` and returns the token trees together with all emitted diagnostics
+pub(crate) fn token_trees(src: &str) -> (TokenStream, Vec<Diagnostic>) {
+    let source_map = Rc::new(SourceMap::new());
+    let psess = ParseSess::with_dcx(DiagCtxt::new(Box::new(SilentEmitter)), source_map);
+    let file = psess.source_map.new_source_file(FileName::Anon("test.sl".into()), src.into());
+    let stream = parse_token_trees(&psess, &file.src, file.start_pos);
+    (stream, psess.dcx.diagnostics())
+}
+
+/// the message and the range of the primary label of every diagnostic
+pub(crate) fn primary_spans(diags: &[Diagnostic]) -> Vec<(&str, u32, u32)> {
+    diags
+        .iter()
+        .map(|diag| {
+            let src_data = diag.primary_src_data().expect("a diagnostic without a primary label");
+            (diag.message.as_str(), src_data.lo().0, src_data.hi().0)
+        })
+        .collect()
+}
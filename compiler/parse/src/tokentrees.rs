@@ -1,46 +1,66 @@
 use ast::{
-    token::{Token, TokenKind},
+    token::{Delimiter, Token, TokenKind},
     tokenstream::{GroupSpacing, Spacing, TokenStream, TokenTree},
 };
-use source_idx::GroupSrcIdx;
+use diagnostics::{codes, DiagCtxt, Diagnostic};
+use source_idx::{GroupSrcIdx, SrcData};
 
 use crate::lex::StringReader;
 
 pub(super) struct TokenTreesReader<'src> {
     string_reader: StringReader<'src>,
     token: Token,
+    diag_info: TokenTreeDiagInfo,
+}
+
+/// state used to report delimiter errors
+#[derive(Default)]
+struct TokenTreeDiagInfo {
+    /// the groups the current token is in
+    open_braces: Vec<(Delimiter, SrcData)>,
+    /// the open and close delimiters of every correctly closed group
+    matching_block_spans: Vec<(SrcData, SrcData)>,
+    /// the last wrong closing delimiter, which is reported only once
+    /// even when it is not eaten and closes multiple groups
+    last_unclosed_found: Option<SrcData>,
+    /// the end of the file is reported once for all groups which are still open
+    eof_reported: bool,
 }
 
 impl<'src> TokenTreesReader<'src> {
-    pub(super) fn parse_all_token_trees(string_reader: StringReader) -> (TokenStream, Option<()>) {
+    /// reads the whole file, delimiter errors are reported and recovered from
+    ///
+    /// unclosed groups are closed at the end of the file or before a closing delimiter
+    /// which matches an outer group, other wrong or extra closing delimiters are skipped
+    pub(super) fn parse_all_token_trees(string_reader: StringReader) -> TokenStream {
         let mut tt_reader = TokenTreesReader {
             string_reader,
             token: Token::dummy(),
+            diag_info: TokenTreeDiagInfo::default(),
         };
-        let (_spacing, stream, res) = tt_reader.parse_token_trees(false);
-        (stream, res)
+        let (_spacing, stream) = tt_reader.parse_token_trees(false);
+        stream
     }
 
-    fn parse_token_trees(&mut self, starts_in_group: bool) -> (Spacing, TokenStream, Option<()>) {
+    /// reads trees until the end of the file or the closing delimiter of the current group
+    fn parse_token_trees(&mut self, starts_in_group: bool) -> (Spacing, TokenStream) {
         let (_, open_spacing) = self.next_token(false);
 
         let mut buf = Vec::new();
         loop {
             match self.token.kind {
-                TokenKind::OpenDelim(delim) => {
-                    buf.push(match self.parse_tt_open_delim(delim) {
-                        Some(tt) => tt,
-                        None => return (open_spacing, TokenStream::new(buf), None),
-                    });
+                TokenKind::OpenDelim(delim) => buf.push(self.parse_tt_open_delim(delim)),
+                TokenKind::CloseDelim(_) if starts_in_group => return (open_spacing, TokenStream::new(buf)),
+                TokenKind::CloseDelim(delim) => {
+                    self.report_unexpected_close_delim(delim);
+                    self.next_token(false);
                 }
-                TokenKind::CloseDelim(_) => {
-                    return (
-                        open_spacing,
-                        TokenStream::new(buf),
-                        if starts_in_group { Some(()) } else { None },
-                    );
+                TokenKind::Eof => {
+                    if starts_in_group {
+                        self.report_eof();
+                    }
+                    return (open_spacing, TokenStream::new(buf));
                 }
-                TokenKind::Eof => return (open_spacing, TokenStream::new(buf), if starts_in_group { None } else { Some(()) } ),
                 _ => {
                     let (this_tok, this_spacing) = self.next_token(true);
                     buf.push(TokenTree::SingleToken(this_tok, this_spacing))
@@ -49,6 +69,10 @@ impl<'src> TokenTreesReader<'src> {
         }
     }
 
+    fn dcx(&self) -> &'src DiagCtxt {
+        self.string_reader.dcx()
+    }
+
     fn next_token(&mut self, glue: bool) -> (Token, Spacing) {
         let (this_spacing, next_tok) = loop {
             let (next_tok, preceding_whitespace) = self.string_reader.next_token();
@@ -78,31 +102,284 @@ impl<'src> TokenTreesReader<'src> {
         (this_tok, this_spacing)
     }
 
-    fn parse_tt_open_delim(&mut self, open_delim: ast::token::Delimiter) -> Option<TokenTree> {
-        let start_src_data = self.token.src_data;
+    fn parse_tt_open_delim(&mut self, open_delim: Delimiter) -> TokenTree {
+        let open_src_data = self.token.src_data;
+        self.diag_info.open_braces.push((open_delim, open_src_data));
 
-        let (open_spacing, tts, res) = self.parse_token_trees(true);
-        res?;
+        let (open_spacing, tts) = self.parse_token_trees(true);
 
-        let group_src_data = GroupSrcIdx::from_pair(start_src_data, self.token.src_data);
-        
-        let close_spacing = match self.token.kind {
+        let (close_src_data, close_spacing) = match self.token.kind {
             // correct delimiter
             TokenKind::CloseDelim(delim) if delim == open_delim => {
-                self.next_token(false).1
+                self.diag_info.open_braces.pop();
+                let close_src_data = self.token.src_data;
+                self.diag_info.matching_block_spans.push((open_src_data, close_src_data));
+                (close_src_data, self.next_token(false).1)
             }
             // incorrect delimiter
-            TokenKind::CloseDelim(_delim) => {
-                // TODO: Recover from unclosed delimiters
-                Spacing::Alone
+            TokenKind::CloseDelim(close_delim) => {
+                let close_src_data = self.token.src_data;
+                if self.diag_info.last_unclosed_found != Some(close_src_data) {
+                    self.diag_info.last_unclosed_found = Some(close_src_data);
+                    self.report_mismatched_close_delim(close_delim, open_src_data);
+                }
+                self.diag_info.open_braces.pop();
+                // a delimiter matching an outer group is left to close that group,
+                // this group is closed right before it
+                if self.diag_info.open_braces.iter().any(|&(delim, _)| delim == close_delim) {
+                    (close_src_data.shrink_to_lo(), Spacing::Alone)
+                } else {
+                    (close_src_data, self.next_token(false).1)
+                }
             }
+            // already reported, the group is closed at the end of the file
             TokenKind::Eof => {
-                Spacing::Alone
+                self.diag_info.open_braces.pop();
+                (self.token.src_data, Spacing::Alone)
             }
             _ => unreachable!(),
         };
 
+        let group_src_data = GroupSrcIdx::from_pair(open_src_data, close_src_data);
         let spacing = GroupSpacing::new(open_spacing, close_spacing);
-        Some(TokenTree::TokenGroup(group_src_data, spacing, open_delim, tts))
+        TokenTree::TokenGroup(group_src_data, spacing, open_delim, tts)
+    }
+
+    fn report_eof(&mut self) {
+        if self.diag_info.eof_reported {
+            return;
+        }
+        self.diag_info.eof_reported = true;
+        let mut diag = Diagnostic::error("this file contains an unclosed delimiter")
+            .with_code(codes::E0010)
+            .with_primary(self.token.src_data, "");
+        for &(_, open_src_data) in &self.diag_info.open_braces {
+            diag = diag.with_secondary(open_src_data, "unclosed delimiter");
+        }
+        let diag = self.with_suspicious_block(diag);
+        self.dcx().emit(diag);
+    }
+
+    /// `open_src_data` is the delimiter of the group which is closed by `close_delim`
+    fn report_mismatched_close_delim(&self, close_delim: Delimiter, open_src_data: SrcData) {
+        let close_str = close_delim.close_str().unwrap_or_default();
+        let mut diag = Diagnostic::error(format!("mismatched closing delimiter: `{}`", close_str))
+            .with_code(codes::E0011)
+            .with_primary(self.token.src_data, "mismatched closing delimiter")
+            .with_secondary(open_src_data, "unclosed delimiter");
+        // an outer group with the same indentation is more likely to be closed by it
+        let candidate = self.diag_info.open_braces.iter().rev().find(|&&(delim, src_data)| {
+            delim == close_delim && src_data != open_src_data && self.same_indentation(src_data, self.token.src_data)
+        });
+        if let Some(&(_, candidate)) = candidate {
+            diag = diag.with_secondary(candidate, "closing delimiter possibly meant for this");
+        }
+        self.dcx().emit(diag);
+    }
+
+    fn report_unexpected_close_delim(&self, close_delim: Delimiter) {
+        let close_str = close_delim.close_str().unwrap_or_default();
+        let diag = Diagnostic::error(format!("unexpected closing delimiter: `{}`", close_str))
+            .with_code(codes::E0012)
+            .with_primary(self.token.src_data, "unexpected closing delimiter");
+        let diag = self.with_suspicious_block(diag);
+        self.dcx().emit(diag);
+    }
+
+    /// points at the innermost group whose delimiters have a different indentation,
+    /// it is likely closed by the wrong delimiter
+    ///
+    /// groups inside of a group with matching indentation are not suspicious
+    fn with_suspicious_block(&self, diag: Diagnostic) -> Diagnostic {
+        let mut blocks: Vec<_> = self
+            .diag_info
+            .matching_block_spans
+            .iter()
+            .map(|&(open, close)| (open, close, self.same_indentation(open, close)))
+            .collect();
+        blocks.sort_by_key(|&(open, _, _)| open.lo());
+        for i in 0..blocks.len() {
+            let (_, close, same_indentation) = blocks[i];
+            if same_indentation {
+                for inner in &mut blocks[i + 1..] {
+                    if inner.0.lo() < close.lo() {
+                        inner.2 = true;
+                    }
+                }
+            }
+        }
+        match blocks.into_iter().rev().find(|&(_, _, same_indentation)| !same_indentation) {
+            Some((open, close, _)) => diag
+                .with_secondary(open, "this delimiter might not be properly closed...")
+                .with_secondary(close, "...as it matches this but it has different indentation"),
+            None => diag,
+        }
+    }
+
+    fn same_indentation(&self, a: SrcData, b: SrcData) -> bool {
+        self.string_reader.line_indent(a.lo()) == self.string_reader.line_indent(b.lo())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ast::{
+        ast::AttrStyle,
+        lit::LitValue,
+        pprust,
+        token::{CommentKind, Delimiter, Token, TokenKind},
+        tokenstream::{TokenStream, TokenTree},
+    };
+    use diagnostics::Diagnostic;
+
+    use crate::test_utils::token_trees;
+
+    /// the delimiters of the groups and the text of the tokens
+    fn shape(stream: &TokenStream) -> String {
+        stream
+            .0
+            .iter()
+            .map(|tree| match tree {
+                TokenTree::SingleToken(token, _) => match token.kind {
+                    TokenKind::Ident(name) => name.to_string(),
+                    kind => kind.punct_str().unwrap_or("?").to_string(),
+                },
+                TokenTree::TokenGroup(_, _, delim, stream) => {
+                    format!("{}{}{}", delim.open_str().unwrap(), shape(stream), delim.close_str().unwrap())
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn messages(diags: &[Diagnostic]) -> Vec<&str> {
+        diags.iter().map(|diag| diag.message.as_str()).collect()
+    }
+
+    #[test]
+    fn unexpected_close_delim() {
+        let (stream, diags) = token_trees("a ) b ] c");
+        assert_eq!(shape(&stream), "a b c");
+        assert_eq!(messages(&diags), ["unexpected closing delimiter: `)`", "unexpected closing delimiter: `]`"]);
+    }
+
+    #[test]
+    fn mismatched_close_delim() {
+        // `]` closes the parenthesis and the brace is closed by its `}`
+        let (stream, diags) = token_trees("{ a ( b ] c }");
+        assert_eq!(shape(&stream), "{a (b) c}");
+        assert_eq!(messages(&diags), ["mismatched closing delimiter: `]`"]);
+        let labels: Vec<_> = diags[0].labels.iter().map(|label| label.label.as_deref().unwrap_or_default()).collect();
+        assert_eq!(labels, ["mismatched closing delimiter", "unclosed delimiter"]);
+
+        // `}` is left for the brace, the parenthesis is closed right before it
+        let (stream, diags) = token_trees("{ a ( b }");
+        assert_eq!(shape(&stream), "{a (b)}");
+        assert_eq!(messages(&diags), ["mismatched closing delimiter: `}`"]);
+        assert!(matches!(&stream.0[0], TokenTree::TokenGroup(_, _, Delimiter::Brace, _)));
+    }
+
+    #[test]
+    fn unclosed_delim() {
+        let (stream, diags) = token_trees("fn a() {\n    if b {\n        c\n}\n");
+        assert_eq!(shape(&stream), "fn a () {if b {c}}");
+        assert_eq!(messages(&diags), ["this file contains an unclosed delimiter"]);
+        let labels: Vec<_> = diags[0].labels.iter().map(|label| label.label.as_deref().unwrap_or_default()).collect();
+        assert_eq!(
+            labels,
+            [
+                "",
+                "unclosed delimiter",
+                "this delimiter might not be properly closed...",
+                "...as it matches this but it has different indentation",
+            ]
+        );
+    }
+
+    /// the kinds and spacing of the tokens without their source
+    fn unspanned(stream: &TokenStream) -> Vec<String> {
+        stream
+            .0
+            .iter()
+            .flat_map(|tree| match tree {
+                TokenTree::SingleToken(token, spacing) => vec![format!("{:?} {:?}", token.kind, spacing)],
+                TokenTree::TokenGroup(_, spacing, delim, stream) => {
+                    let mut tokens = vec![format!("{:?} {:?}", delim, spacing)];
+                    tokens.extend(unspanned(stream));
+                    tokens
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn doc_comments() {
+        let (stream, _) = token_trees("//! crate\n/// a\n//// b\n/** c */ /*! d */ /*** e */ /**/");
+        let kinds: Vec<_> = stream
+            .0
+            .iter()
+            .map(|tree| match tree {
+                TokenTree::SingleToken(token, _) => token.kind,
+                TokenTree::TokenGroup(..) => panic!("unexpected group"),
+            })
+            .collect();
+        let doc = |kind, style, text| TokenKind::DocComment(kind, style, source_idx::Symbol::get_or_store(text));
+        assert_eq!(
+            kinds,
+            [
+                doc(CommentKind::Line, AttrStyle::Inner, " crate"),
+                doc(CommentKind::Line, AttrStyle::Outer, " a"),
+                TokenKind::Comment(CommentKind::Line),
+                doc(CommentKind::Block, AttrStyle::Outer, " c "),
+                doc(CommentKind::Block, AttrStyle::Inner, " d "),
+                TokenKind::Comment(CommentKind::Block),
+                TokenKind::Comment(CommentKind::Block),
+            ]
+        );
+    }
+
+    #[test]
+    fn lossless() {
+        let src = include_str!("../../../mock.sl");
+        let (stream, diags) = token_trees(src);
+        assert!(diags.is_empty());
+        let (relexed, diags) = token_trees(&pprust::tts_to_string(&stream));
+        assert!(diags.is_empty());
+        assert_eq!(unspanned(&stream), unspanned(&relexed));
+    }
+
+    #[test]
+    fn crlf_round_trip() {
+        // the `\r` of a line break is neither part of a doc comment nor printed
+        let (stream, diags) = token_trees("/// a\r\n/** b\r\n */\r\nfn c() {}\r\n");
+        assert!(diags.is_empty());
+        let printed = pprust::tts_to_string(&stream);
+        assert!(!printed.contains('\r'), "{:?}", printed);
+        assert_eq!(printed, pprust::tts_to_string(&token_trees("/// a\n/** b\n */\nfn c() {}\n").0));
+
+        let src = include_str!("../../../mock.sl");
+        assert!(src.contains("\r\n"));
+        let printed = pprust::tts_to_string(&token_trees(src).0);
+        assert_eq!(printed, pprust::tts_to_string(&token_trees(&src.replace("\r\n", "\n")).0));
+    }
+
+    #[test]
+    fn crlf_in_literals() {
+        // the `\r\n` of a multi-line string and of a line continuation is no bare CR
+        let (stream, diags) = token_trees("\"a\r\nb\"\r\n\"c\\\r\n    d\"\r\n");
+        assert!(diags.is_empty());
+        let values: Vec<_> = stream
+            .0
+            .iter()
+            .map(|tree| match tree {
+                TokenTree::SingleToken(Token { kind: TokenKind::Literal(lit), .. }, _) => LitValue::from_token_lit(*lit),
+                tree => panic!("expected a literal, found {:?}", tree),
+            })
+            .collect();
+        assert!(matches!(values[0], Ok(LitValue::Str(s, _)) if s.as_str() == "a\nb"));
+        assert!(matches!(values[1], Ok(LitValue::Str(s, _)) if s.as_str() == "cd"));
+
+        let (_, diags) = token_trees("\"a\rb\"");
+        assert_eq!(messages(&diags), ["bare CR not allowed in literal"]);
     }
 }
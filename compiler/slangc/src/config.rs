@@ -0,0 +1,260 @@
+//! The options of a compiler invocation, parsed from the command line
+
+use std::path::PathBuf;
+
+use diagnostics::LintLevel;
+use source_idx::{Edition, ALL_EDITIONS};
+
+pub const USAGE: &str = "\
+Usage: slangc [OPTIONS] INPUT
+
+INPUT is the path of the root file of the crate, `-` reads it from stdin
+
+Options:
+    -h, --help              print this message
+    -o FILE                 write the emitted output to FILE instead of stdout
+        --emit KIND[,KIND]  print the intermediate output of the compiler,
+                            one of tokens|token-trees|ast|lowered-ast
+        --error-format human|json
+                            how diagnostics are rendered
+        --edition EDITION   the edition of the crate, 2024
+        --crate-type bin|lib
+                            whether the crate is a binary with an entry point
+                            or a library, bin by default
+    -W, --warn LINT         set the level of a lint to warn
+    -A, --allow LINT        set the level of a lint to allow
+    -D, --deny LINT         set the level of a lint to deny
+";
+
+/// The intermediate output which can be printed with `--emit`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EmitKind {
+    /// the token trees printed back to source text
+    Tokens,
+    /// the debug output of the token trees
+    TokenTrees,
+    /// the debug output of the AST
+    Ast,
+    /// the debug output of the AST after the sugar is removed
+    LoweredAst,
+}
+
+impl EmitKind {
+    fn from_name(name: &str) -> Option<EmitKind> {
+        match name {
+            "tokens" => Some(EmitKind::Tokens),
+            "token-trees" => Some(EmitKind::TokenTrees),
+            "ast" => Some(EmitKind::Ast),
+            "lowered-ast" => Some(EmitKind::LoweredAst),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ErrorFormat {
+    Human,
+    /// one JSON object per line, see [`diagnostics::JsonEmitter`]
+    Json,
+}
+
+impl ErrorFormat {
+    fn from_name(name: &str) -> Result<ErrorFormat, String> {
+        match name {
+            "human" => Ok(ErrorFormat::Human),
+            "json" => Ok(ErrorFormat::Json),
+            _ => Err(format!("unknown error format `{}`, expected `human` or `json`", name)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CrateType {
+    /// needs an entry point, see [`ast_lowering::entry`]
+    Bin,
+    Lib,
+}
+
+impl CrateType {
+    fn from_name(name: &str) -> Result<CrateType, String> {
+        match name {
+            "bin" => Ok(CrateType::Bin),
+            "lib" => Ok(CrateType::Lib),
+            _ => Err(format!("unknown crate type `{}`, expected `bin` or `lib`", name)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Options {
+    pub input: PathBuf,
+    pub output: Option<PathBuf>,
+    /// without duplicates, the output is printed in the order of the compiler stages
+    pub emit: Vec<EmitKind>,
+    pub error_format: ErrorFormat,
+    pub edition: Edition,
+    pub crate_type: CrateType,
+    /// in the order they were given, a later level for the same lint wins
+    pub lint_levels: Vec<(String, LintLevel)>,
+}
+
+/// What the command line asks for
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Compile(Options),
+    Help,
+}
+
+/// parses the arguments without the name of the program
+///
+/// options with a value accept it as the next argument or joined with `=`,
+/// the short lint options also directly joined e.g. `-Awarnings`
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let mut args = args.into_iter();
+    let mut input = None;
+    let mut output = None;
+    let mut emit = Vec::new();
+    let mut error_format = ErrorFormat::Human;
+    let mut edition = Edition::DEFAULT;
+    let mut crate_type = CrateType::Bin;
+    let mut lint_levels = Vec::new();
+
+    while let Some(arg) = args.next() {
+        let (name, joined) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = |option: &str| match joined.clone() {
+            Some(value) => Ok(value),
+            None => args.next().ok_or_else(|| format!("option `{}` requires a value", option)),
+        };
+        match name {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-o" => output = Some(PathBuf::from(value("-o")?)),
+            "--emit" => {
+                for kind in value("--emit")?.split(',') {
+                    let kind = EmitKind::from_name(kind).ok_or_else(|| {
+                        format!("unknown emit kind `{}`, expected one of `tokens`, `token-trees`, `ast`, `lowered-ast`", kind)
+                    })?;
+                    if !emit.contains(&kind) {
+                        emit.push(kind);
+                    }
+                }
+            }
+            "--error-format" => error_format = ErrorFormat::from_name(&value("--error-format")?)?,
+            "--edition" => {
+                let name = value("--edition")?;
+                edition = Edition::from_name(&name).ok_or_else(|| {
+                    let editions: Vec<_> = ALL_EDITIONS.iter().map(|edition| format!("`{}`", edition)).collect();
+                    format!("unknown edition `{}`, expected one of {}", name, editions.join(", "))
+                })?;
+            }
+            "--crate-type" => crate_type = CrateType::from_name(&value("--crate-type")?)?,
+            _ => {
+                if let Some((level, lint)) = parse_lint_arg(name, &mut value)? {
+                    lint_levels.push((lint, level));
+                } else if name.starts_with('-') && name != "-" {
+                    return Err(format!("unknown option `{}`", name));
+                } else if input.is_some() {
+                    return Err(format!("multiple input files given, `{}` is the second one", arg));
+                } else {
+                    input = Some(PathBuf::from(arg.clone()));
+                }
+            }
+        }
+    }
+
+    let input = input.ok_or_else(|| "no input file given".to_string())?;
+    Ok(Command::Compile(Options { input, output, emit, error_format, edition, crate_type, lint_levels }))
+}
+
+/// `-W lint`, `-Wlint` or `--warn lint` and the same for allow and deny
+fn parse_lint_arg(
+    name: &str,
+    value: &mut impl FnMut(&str) -> Result<String, String>,
+) -> Result<Option<(LintLevel, String)>, String> {
+    let (level, joined) = match name {
+        "--warn" => (LintLevel::Warn, None),
+        "--allow" => (LintLevel::Allow, None),
+        "--deny" => (LintLevel::Deny, None),
+        _ => {
+            let level = match name.get(..2) {
+                Some("-W") => LintLevel::Warn,
+                Some("-A") => LintLevel::Allow,
+                Some("-D") => LintLevel::Deny,
+                _ => return Ok(None),
+            };
+            (level, Some(&name[2..]).filter(|lint| !lint.is_empty()))
+        }
+    };
+    let lint = match joined {
+        Some(lint) => lint.to_string(),
+        None => value(name)?,
+    };
+    Ok(Some((level, lint.replace('-', "_"))))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Command, String> {
+        parse_args(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn options() {
+        let Ok(Command::Compile(options)) =
+            parse(
+            "main.sl -o out.txt --emit=ast,tokens --emit ast --edition 2024 --crate-type=lib -Awarnings --deny unknown-attributes",
+        )
+        else {
+            panic!("expected options");
+        };
+        assert_eq!(
+            options,
+            Options {
+                input: PathBuf::from("main.sl"),
+                output: Some(PathBuf::from("out.txt")),
+                emit: vec![EmitKind::Ast, EmitKind::Tokens],
+                error_format: ErrorFormat::Human,
+                edition: Edition::Edition2024,
+                crate_type: CrateType::Lib,
+                lint_levels: vec![
+                    ("warnings".to_string(), LintLevel::Allow),
+                    ("unknown_attributes".to_string(), LintLevel::Deny),
+                ],
+            }
+        );
+        assert_eq!(parse("main.sl --help"), Ok(Command::Help));
+    }
+
+    #[test]
+    fn error_format() {
+        let error_format = |args| match parse(args) {
+            Ok(Command::Compile(options)) => Ok(options.error_format),
+            Ok(Command::Help) => panic!("expected options"),
+            Err(err) => Err(err),
+        };
+        assert_eq!(error_format("main.sl"), Ok(ErrorFormat::Human));
+        assert_eq!(error_format("main.sl --error-format=json"), Ok(ErrorFormat::Json));
+        assert_eq!(
+            error_format("main.sl --error-format xml"),
+            Err("unknown error format `xml`, expected `human` or `json`".to_string())
+        );
+    }
+
+    #[test]
+    fn invalid_options() {
+        assert_eq!(parse(""), Err("no input file given".to_string()));
+        assert_eq!(parse("a.sl b.sl"), Err("multiple input files given, `b.sl` is the second one".to_string()));
+        assert_eq!(parse("main.sl -o"), Err("option `-o` requires a value".to_string()));
+        assert_eq!(parse("main.sl --frobnicate"), Err("unknown option `--frobnicate`".to_string()));
+        assert_eq!(
+            parse("main.sl --emit=tokens,mir"),
+            Err("unknown emit kind `mir`, expected one of `tokens`, `token-trees`, `ast`, `lowered-ast`".to_string())
+        );
+        assert_eq!(parse("main.sl --crate-type dylib"), Err("unknown crate type `dylib`, expected `bin` or `lib`".to_string()));
+        assert_eq!(parse("main.sl --edition=2015"), Err("unknown edition `2015`, expected one of `2024`".to_string()));
+    }
+}
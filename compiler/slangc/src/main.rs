@@ -1,3 +1,114 @@
+use std::{
+    env,
+    fmt::Write as _,
+    fs,
+    io::{self, Read},
+    process,
+    rc::Rc,
+};
+
+use ast::pprust;
+use config::{Command, CrateType, EmitKind, ErrorFormat, Options};
+use diagnostics::{lint, DiagCtxt, Diagnostic, Emitter, HumanEmitter, JsonEmitter};
+use parse::{lex, parse::Parser, ParseSess};
+use source_idx::{FileName, SourceMap};
+
+mod config;
+
+/// the exit code when the compilation failed or the command line is invalid
+const EXIT_FAILURE: i32 = 1;
+
 fn main() {
-    println!("Hello, world!");
+    let code = match config::parse_args(env::args().skip(1)) {
+        Ok(Command::Help) => {
+            print!("{}", config::USAGE);
+            0
+        }
+        Ok(Command::Compile(options)) => run_compiler(&options),
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, config::USAGE);
+            EXIT_FAILURE
+        }
+    };
+    process::exit(code);
+}
+
+/// compiles the input and writes the requested output, returns the exit code
+fn run_compiler(options: &Options) -> i32 {
+    let source_map = Rc::new(SourceMap::new());
+    let emitter: Box<dyn Emitter> = match options.error_format {
+        ErrorFormat::Human => Box::new(HumanEmitter::stderr(source_map.clone())),
+        ErrorFormat::Json => Box::new(JsonEmitter::stderr(source_map.clone())),
+    };
+    let mut psess = ParseSess::with_dcx(DiagCtxt::new(emitter), source_map.clone());
+    psess.edition = options.edition;
+    for (name, level) in &options.lint_levels {
+        if name != lint::WARNINGS && lint::find_lint(name).is_none() {
+            psess.dcx.emit(Diagnostic::warning(format!("unknown lint: `{}`", name)));
+        }
+        psess.dcx.set_lint_level(name.clone(), *level);
+    }
+
+    // `-` reads the input from stdin
+    let (name, read) = if options.input.as_os_str() == "-" {
+        let mut src = String::new();
+        (FileName::Anon("stdin".into()), io::stdin().read_to_string(&mut src).map(|_| src))
+    } else {
+        (FileName::Real(options.input.clone()), fs::read_to_string(&options.input))
+    };
+    let src = match read {
+        Ok(src) => src,
+        Err(err) => {
+            psess.dcx.emit(Diagnostic::error(format!("couldn't read `{}`: {}", name, err)));
+            return finish(&psess.dcx);
+        }
+    };
+    let file = source_map.new_source_file(name, src);
+
+    let mut out = String::new();
+    let stream = lex::parse_token_trees(&psess, &file.src, file.start_pos);
+    if options.emit.contains(&EmitKind::Tokens) {
+        writeln!(out, "{}", pprust::tts_to_string_with_invisible_delims(&stream)).unwrap();
+    }
+    if options.emit.contains(&EmitKind::TokenTrees) {
+        writeln!(out, "{:#?}", stream).unwrap();
+    }
+
+    let mut krate = Parser::new(&psess, stream).parse_crate_mod();
+    if options.emit.contains(&EmitKind::Ast) {
+        writeln!(out, "{:#?}", krate).unwrap();
+    }
+
+    ast_lowering::lower_crate(&psess, &mut krate);
+    if options.emit.contains(&EmitKind::LoweredAst) {
+        writeln!(out, "{:#?}", krate).unwrap();
+    }
+    // the backend starts the binary at this function
+    let _entry_fn = match options.crate_type {
+        CrateType::Bin => ast_lowering::entry::entry_fn(&psess, &krate),
+        CrateType::Lib => None,
+    };
+
+    let written = match &options.output {
+        Some(path) => fs::write(path, out).map_err(|err| (path.display().to_string(), err)),
+        None => {
+            print!("{}", out);
+            Ok(())
+        }
+    };
+    if let Err((path, err)) = written {
+        psess.dcx.emit(Diagnostic::error(format!("couldn't write `{}`: {}", path, err)));
+    }
+    finish(&psess.dcx)
+}
+
+/// the summary is emitted as a diagnostic as well, so it has the requested error format
+fn finish(dcx: &DiagCtxt) -> i32 {
+    let message = match dcx.err_count() {
+        0 => return 0,
+        1 => "aborting due to 1 previous error".to_string(),
+        count => format!("aborting due to {} previous errors", count),
+    };
+    dcx.emit(Diagnostic::error(message));
+    EXIT_FAILURE
 }
@@ -0,0 +1,33 @@
+use std::fmt;
+
+/// The edition of the language a crate is written in
+///
+/// editions can change the syntax and meaning of the language without breaking crates
+/// written in an older edition
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Edition {
+    Edition2024,
+}
+
+pub const ALL_EDITIONS: &[Edition] = &[Edition::Edition2024];
+
+impl Edition {
+    pub const DEFAULT: Edition = Edition::Edition2024;
+    pub const LATEST: Edition = Edition::Edition2024;
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Edition::Edition2024 => "2024",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Edition> {
+        ALL_EDITIONS.iter().copied().find(|edition| edition.as_str() == name)
+    }
+}
+
+impl fmt::Display for Edition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
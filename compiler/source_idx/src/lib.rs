@@ -1,13 +1,12 @@
 use std::cmp;
 
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
-pub struct Symbol;
+mod edition;
+mod source_map;
+mod symbol;
 
-impl Symbol {
-    pub fn get_or_store(string: &str) -> Self {
-        Self
-    }
-}
+pub use edition::{Edition, ALL_EDITIONS};
+pub use source_map::{FileId, FileName, Loc, SourceFile, SourceMap};
+pub use symbol::{kw, sym, Symbol};
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub struct SrcData {
@@ -28,12 +27,43 @@ impl SrcData {
         SrcData { lo: lo.0, hi: hi.0, ctxt: ROOT_CTXT }
     }
     
+    pub fn lo(&self) -> BytePos {
+        BytePos(self.lo)
+    }
+
+    pub fn hi(&self) -> BytePos {
+        BytePos(self.hi)
+    }
+
     pub fn combine(&self, src_data: SrcData) -> SrcData {
         SrcData::new(cmp::min(self.lo, src_data.lo), cmp::max(self.hi, src_data.hi), self.ctxt)
     }
+
+    pub fn with_lo(&self, lo: BytePos) -> SrcData {
+        SrcData::new(lo.0, self.hi, self.ctxt)
+    }
+
+    pub fn with_hi(&self, hi: BytePos) -> SrcData {
+        SrcData::new(self.lo, hi.0, self.ctxt)
+    }
+
+    /// the empty source at the start
+    pub fn shrink_to_lo(&self) -> SrcData {
+        SrcData::new(self.lo, self.lo, self.ctxt)
+    }
+
+    /// the empty source at the end
+    pub fn shrink_to_hi(&self) -> SrcData {
+        SrcData::new(self.hi, self.hi, self.ctxt)
+    }
+
+    /// the source from the start of `self` to the end of `end`
+    pub fn to(&self, end: SrcData) -> SrcData {
+        SrcData::new(self.lo, cmp::max(self.lo, end.hi), self.ctxt)
+    }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct GroupSrcIdx {
     open: SrcData,
     close: SrcData,
@@ -43,6 +73,14 @@ impl GroupSrcIdx {
     pub fn from_pair(open: SrcData, close: SrcData) -> GroupSrcIdx {
         GroupSrcIdx { open, close }
     }
+
+    pub fn open(&self) -> SrcData {
+        self.open
+    }
+
+    pub fn close(&self) -> SrcData {
+        self.close
+    }
 }
 
 macro_rules! impl_pos {
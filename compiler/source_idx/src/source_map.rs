@@ -0,0 +1,282 @@
+use std::{cell::RefCell, fmt, path::PathBuf, rc::Rc};
+
+use crate::{BytePos, SrcData};
+
+/// index of a [`SourceFile`] inside of its [`SourceMap`]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct FileId(u32);
+
+impl FileId {
+    pub fn to_usize(self) -> usize {
+        self.0 as usize
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum FileName {
+    Real(PathBuf),
+    /// source which does not come from a file e.g. a test snippet or `stdin`
+    Anon(String),
+}
+
+impl fmt::Display for FileName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileName::Real(path) => write!(f, "{}", path.display()),
+            FileName::Anon(name) => write!(f, "<{}>", name),
+        }
+    }
+}
+
+/// A resolved position inside of a [`SourceFile`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Loc {
+    pub file: FileId,
+    /// 1-based line number
+    pub line: usize,
+    /// 0-based column counted in chars
+    pub col: usize,
+}
+
+/// A char which takes more than one byte in utf-8
+#[derive(Clone, Copy, Debug)]
+struct MultiByteChar {
+    pos: BytePos,
+    bytes: u8,
+}
+
+/// A `\r` of a `\r\n` which was removed from the source of a file
+#[derive(Clone, Copy, Debug)]
+struct NormalizedPos {
+    /// global position of the `\n` following the removed `\r`
+    pos: BytePos,
+    /// the number of bytes removed up to and including this one
+    diff: u32,
+}
+
+/// A single file registered in the [`SourceMap`].
+///
+/// The file occupies the global positions `start_pos..end_pos`. Every `\r\n` of the source is
+/// replaced by `\n` when the file is added, like rustc does, so the lexer never sees the `\r` of
+/// a line break. A lone `\r` is kept.
+#[derive(Debug)]
+pub struct SourceFile {
+    pub id: FileId,
+    pub name: FileName,
+    pub src: Rc<str>,
+    pub start_pos: BytePos,
+    pub end_pos: BytePos,
+    /// global position of the first byte of every line
+    lines: Vec<BytePos>,
+    multi_byte_chars: Vec<MultiByteChar>,
+    /// the removed `\r`s, to get the positions in the original source
+    normalized_pos: Vec<NormalizedPos>,
+}
+
+impl SourceFile {
+    fn new(id: FileId, name: FileName, mut src: String, start_pos: BytePos) -> SourceFile {
+        let normalized_pos = normalize_newlines(&mut src)
+            .into_iter()
+            .map(|(offset, diff)| NormalizedPos { pos: start_pos + BytePos::from_usize(offset), diff })
+            .collect();
+        let mut lines = vec![start_pos];
+        let mut multi_byte_chars = Vec::new();
+        for (idx, c) in src.char_indices() {
+            let pos = start_pos + BytePos::from_usize(idx);
+            match c.len_utf8() {
+                1 if c == '\n' => lines.push(pos + BytePos(1)),
+                1 => {}
+                bytes => multi_byte_chars.push(MultiByteChar { pos, bytes: bytes as u8 }),
+            }
+        }
+        SourceFile {
+            id,
+            name,
+            end_pos: start_pos + BytePos::from_usize(src.len()),
+            src: src.into(),
+            start_pos,
+            lines,
+            multi_byte_chars,
+            normalized_pos,
+        }
+    }
+
+    pub fn contains(&self, pos: BytePos) -> bool {
+        self.start_pos <= pos && pos <= self.end_pos
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// 0-based index of the line containing `pos`, `None` if `pos` is in front of the file
+    pub fn lookup_line(&self, pos: BytePos) -> Option<usize> {
+        self.lines.partition_point(|&line_start| line_start <= pos).checked_sub(1)
+    }
+
+    /// global position of the start of the 0-based `line_idx`
+    pub fn line_start(&self, line_idx: usize) -> BytePos {
+        self.lines[line_idx]
+    }
+
+    /// the text of the 0-based `line_idx` without the line terminator
+    pub fn line_text(&self, line_idx: usize) -> Option<&str> {
+        let start = (*self.lines.get(line_idx)? - self.start_pos).to_usize();
+        self.src[start..].split('\n').next()
+    }
+
+    /// the byte offset of the global `pos` in the source before `\r\n` was replaced by `\n`
+    pub fn original_offset(&self, pos: BytePos) -> usize {
+        let idx = self.normalized_pos.partition_point(|normalized| normalized.pos <= pos);
+        let diff = idx.checked_sub(1).map_or(0, |idx| self.normalized_pos[idx].diff);
+        (pos - self.start_pos).to_usize() + diff as usize
+    }
+
+    /// number of chars in between the global positions `start` and `end`
+    fn char_count(&self, start: BytePos, end: BytePos) -> usize {
+        let first = self.multi_byte_chars.partition_point(|mbc| mbc.pos < start);
+        let last = self.multi_byte_chars.partition_point(|mbc| mbc.pos < end);
+        let extra_bytes: usize = self.multi_byte_chars[first..last]
+            .iter()
+            .map(|mbc| mbc.bytes as usize - 1)
+            .sum();
+        (end - start).to_usize() - extra_bytes
+    }
+
+    /// `None` if `pos` is in front of the file
+    pub fn lookup(&self, pos: BytePos) -> Option<Loc> {
+        let line_idx = self.lookup_line(pos)?;
+        Some(Loc {
+            file: self.id,
+            line: line_idx + 1,
+            col: self.char_count(self.lines[line_idx], pos),
+        })
+    }
+}
+
+/// replaces every `\r\n` by `\n`, returns the offset of every `\n` after a removed `\r` in the
+/// new source and the number of bytes removed up to there
+fn normalize_newlines(src: &mut String) -> Vec<(usize, u32)> {
+    if !src.contains("\r\n") {
+        return Vec::new();
+    }
+    let mut normalized = String::with_capacity(src.len());
+    let mut removed = Vec::new();
+    let mut rest = src.as_str();
+    while let Some(idx) = rest.find("\r\n") {
+        normalized.push_str(&rest[..idx]);
+        removed.push((normalized.len(), removed.len() as u32 + 1));
+        rest = &rest[idx + 1..];
+    }
+    normalized.push_str(rest);
+    *src = normalized;
+    removed
+}
+
+/// Keeps all source files of a compilation session.
+///
+/// Every file is registered at its own range of [`BytePos`]s,
+/// so that a single position is enough to know the file, line and column it is pointing at.
+#[derive(Default)]
+pub struct SourceMap {
+    files: RefCell<Vec<Rc<SourceFile>>>,
+}
+
+impl SourceMap {
+    pub fn new() -> SourceMap {
+        SourceMap::default()
+    }
+
+    /// registers a new file after all other files
+    pub fn new_source_file(&self, name: FileName, src: String) -> Rc<SourceFile> {
+        let mut files = self.files.borrow_mut();
+        // leave a gap of one byte between files, so that the end of a file
+        // is not also the start of the following one
+        let start_pos = files
+            .last()
+            .map(|file| file.end_pos + BytePos(1))
+            .unwrap_or(BytePos(0));
+        let id = FileId(files.len() as u32);
+        let file = Rc::new(SourceFile::new(id, name, src, start_pos));
+        files.push(file.clone());
+        file
+    }
+
+    pub fn file(&self, id: FileId) -> Rc<SourceFile> {
+        self.files.borrow()[id.to_usize()].clone()
+    }
+
+    pub fn files(&self) -> Vec<Rc<SourceFile>> {
+        self.files.borrow().clone()
+    }
+
+    /// the file containing `pos`
+    pub fn lookup_file(&self, pos: BytePos) -> Option<Rc<SourceFile>> {
+        let files = self.files.borrow();
+        let idx = files.partition_point(|file| file.start_pos <= pos).checked_sub(1)?;
+        let file = &files[idx];
+        file.contains(pos).then(|| file.clone())
+    }
+
+    pub fn lookup(&self, pos: BytePos) -> Option<Loc> {
+        self.lookup_file(pos)?.lookup(pos)
+    }
+
+    /// the source text covered by `src_data` if it lies inside of a single file
+    pub fn snippet(&self, src_data: SrcData) -> Option<String> {
+        let file = self.lookup_file(src_data.lo())?;
+        if !file.contains(src_data.hi()) {
+            return None;
+        }
+        let lo = (src_data.lo() - file.start_pos).to_usize();
+        let hi = (src_data.hi() - file.start_pos).to_usize();
+        file.src.get(lo..hi).map(str::to_string)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lookup() {
+        let sm = SourceMap::new();
+        let a = sm.new_source_file(FileName::Anon("a".into()), "fn a() {}\r\nlet x".into());
+        let b = sm.new_source_file(FileName::Anon("b".into()), "\"äö\" x\n\ny".into());
+        assert!(a.end_pos < b.start_pos);
+
+        let loc = |file: &SourceFile, offset: usize| sm.lookup(file.start_pos + BytePos::from_usize(offset));
+        assert_eq!(loc(&a, 0), Some(Loc { file: a.id, line: 1, col: 0 }));
+        assert_eq!(loc(&a, 10), Some(Loc { file: a.id, line: 2, col: 0 }));
+        assert_eq!(loc(&a, 14), Some(Loc { file: a.id, line: 2, col: 4 }));
+        // `x` after two chars with two bytes each
+        assert_eq!(loc(&b, 7), Some(Loc { file: b.id, line: 1, col: 5 }));
+        assert_eq!(loc(&b, 9), Some(Loc { file: b.id, line: 2, col: 0 }));
+        assert_eq!(loc(&b, 10), Some(Loc { file: b.id, line: 3, col: 0 }));
+
+        assert_eq!(a.line_text(0), Some("fn a() {}"));
+        assert_eq!(b.line_text(1), Some(""));
+        assert_eq!(b.lookup_line(a.start_pos), None);
+        assert_eq!(b.lookup(a.end_pos), None);
+    }
+
+    #[test]
+    fn normalize_crlf() {
+        let sm = SourceMap::new();
+        let file = sm.new_source_file(FileName::Anon("a".into()), "a\r\nb\rc\r\n\r\nd".into());
+        assert_eq!(&*file.src, "a\nb\rc\n\nd");
+
+        let pos = |offset: usize| file.start_pos + BytePos::from_usize(offset);
+        assert_eq!(file.lookup(pos(2)), Some(Loc { file: file.id, line: 2, col: 0 }));
+        // a lone `\r` is not a line break
+        assert_eq!(file.lookup(pos(4)), Some(Loc { file: file.id, line: 2, col: 2 }));
+        assert_eq!(file.lookup(pos(7)), Some(Loc { file: file.id, line: 4, col: 0 }));
+        assert_eq!(file.line_text(1), Some("b\rc"));
+
+        assert_eq!(file.original_offset(pos(0)), 0);
+        assert_eq!(file.original_offset(pos(1)), 2);
+        assert_eq!(file.original_offset(pos(2)), 3);
+        assert_eq!(file.original_offset(pos(5)), 7);
+        assert_eq!(file.original_offset(pos(7)), 10);
+    }
+}
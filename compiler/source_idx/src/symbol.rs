@@ -0,0 +1,226 @@
+use std::{cell::RefCell, collections::HashMap, fmt};
+
+/// An interned string.
+///
+/// Comparing, hashing and copying a `Symbol` only touches its index,
+/// the text lives in the thread local [`Interner`] and can be retrieved with [`Symbol::as_str`].
+/// Note that the order of symbols is the order they were interned in and not the lexical order.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    const fn new(idx: u32) -> Symbol {
+        Symbol(idx)
+    }
+
+    /// interns `string` or returns the symbol of the already interned text
+    pub fn get_or_store(string: &str) -> Self {
+        with_interner(|interner| interner.intern(string))
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        with_interner(|interner| interner.get(*self))
+    }
+
+    pub fn as_u32(self) -> u32 {
+        self.0
+    }
+
+    /// true if the symbol is a keyword which can not be used as an identifier
+    pub fn is_reserved(self) -> bool {
+        self >= kw::Fn && self <= kw::Dyn
+    }
+
+    /// true for the `true` and `false` keywords
+    pub fn is_bool_lit(self) -> bool {
+        self == kw::True || self == kw::False
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+/// String table handing out the indices of [`Symbol`]s.
+///
+/// Interned strings are leaked, so they stay valid for the whole lifetime of the compiler.
+struct Interner {
+    names: HashMap<&'static str, Symbol>,
+    strings: Vec<&'static str>,
+}
+
+impl Interner {
+    fn prefill(init: &[&'static str]) -> Interner {
+        Interner {
+            names: init.iter().copied().zip((0..).map(Symbol::new)).collect(),
+            strings: init.to_vec(),
+        }
+    }
+
+    fn intern(&mut self, string: &str) -> Symbol {
+        if let Some(&sym) = self.names.get(string) {
+            return sym;
+        }
+        let sym = Symbol::new(self.strings.len() as u32);
+        let string: &'static str = Box::leak(string.into());
+        self.strings.push(string);
+        self.names.insert(string, sym);
+        sym
+    }
+
+    fn get(&self, sym: Symbol) -> &'static str {
+        self.strings[sym.0 as usize]
+    }
+}
+
+thread_local! {
+    static INTERNER: RefCell<Interner> = RefCell::new(Interner::prefill(PREINTERNED));
+}
+
+fn with_interner<R>(f: impl FnOnce(&mut Interner) -> R) -> R {
+    INTERNER.with(|interner| f(&mut interner.borrow_mut()))
+}
+
+/// defines the pre-interned symbols
+///
+/// every entry gets a constant in the given module and
+/// the index of the constant matches its position in `PREINTERNED`
+macro_rules! symbols {
+    (
+        Keywords { $( $kw_name:ident : $kw_str:literal, )* }
+        Symbols { $( $sym_name:ident $( : $sym_str:literal )?, )* }
+    ) => {
+        const PREINTERNED: &[&str] = &[
+            $( $kw_str, )*
+            $( symbols!(@str $sym_name $( $sym_str )?), )*
+        ];
+
+        /// pre-interned keywords
+        #[allow(non_upper_case_globals)]
+        pub mod kw {
+            use super::Symbol;
+            symbols!(@consts 0u32; $( $kw_name )*);
+        }
+
+        /// pre-interned symbols which are not keywords
+        #[allow(non_upper_case_globals)]
+        pub mod sym {
+            use super::Symbol;
+            const OFFSET: u32 = [$( $kw_str ),*].len() as u32;
+            symbols!(@consts OFFSET; $( $sym_name )*);
+        }
+    };
+    (@str $name:ident $str:literal) => { $str };
+    (@str $name:ident) => { stringify!($name) };
+    (@consts $idx:expr; ) => {};
+    (@consts $idx:expr; $name:ident $( $rest:ident )*) => {
+        pub const $name: Symbol = Symbol::new($idx);
+        symbols!(@consts $idx + 1; $( $rest )*);
+    };
+}
+
+symbols! {
+    Keywords {
+        Empty: "",
+        Underscore: "_",
+
+        // keywords which are reserved, keep `Fn` first and `Dyn` last (see `Symbol::is_reserved`)
+        Fn: "fn",
+        Let: "let",
+        Mut: "mut",
+        Return: "return",
+        If: "if",
+        Else: "else",
+        While: "while",
+        Loop: "loop",
+        For: "for",
+        In: "in",
+        Break: "break",
+        Continue: "continue",
+        Match: "match",
+        Struct: "struct",
+        Enum: "enum",
+        Trait: "trait",
+        Impl: "impl",
+        Pub: "pub",
+        Use: "use",
+        Mod: "mod",
+        Const: "const",
+        Static: "static",
+        Type: "type",
+        True: "true",
+        False: "false",
+        SelfLower: "self",
+        SelfUpper: "Self",
+        Super: "super",
+        Crate: "crate",
+        As: "as",
+        Where: "where",
+        Unsafe: "unsafe",
+        Extern: "extern",
+        Ref: "ref",
+        Move: "move",
+        Dyn: "dyn",
+    }
+
+    Symbols {
+        Option,
+        Result,
+        allow,
+        always,
+        cfg,
+        def,
+        deny,
+        derive,
+        doc,
+        entry,
+        explicit_param,
+        f32,
+        f64,
+        hidden,
+        i8,
+        i16,
+        i32,
+        i64,
+        i128,
+        inline,
+        isize,
+        main,
+        never,
+        option,
+        result,
+        std,
+        test,
+        u8,
+        u16,
+        u32,
+        u64,
+        u128,
+        usize,
+        warn,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn interner() {
+        let a = Symbol::get_or_store("another_function");
+        assert_eq!(a, Symbol::get_or_store("another_function"));
+        assert_ne!(a, Symbol::get_or_store("string"));
+        assert_eq!(a.as_str(), "another_function");
+        assert_eq!(Symbol::get_or_store("fn"), kw::Fn);
+        assert_eq!(Symbol::get_or_store("main"), sym::main);
+        assert!(kw::Return.is_reserved() && !sym::main.is_reserved());
+    }
+}